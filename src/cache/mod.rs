@@ -1,6 +1,6 @@
 use anyhow::Result;
 use dirs::cache_dir;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub use metadata::CacheMetadata;
 
@@ -10,6 +10,8 @@ mod metadata;
 pub struct CachePaths {
     base_dir: PathBuf,
     git_dir: PathBuf,
+    oci_dir: PathBuf,
+    archive_dir: PathBuf,
     metadata_dir: PathBuf,
 }
 
@@ -19,16 +21,29 @@ impl CachePaths {
             .ok_or_else(|| anyhow::anyhow!("No cache directory found"))?
             .join("skillset");
 
-        Ok(Self {
+        Ok(Self::at(base_dir))
+    }
+
+    /// A cache rooted somewhere other than the user cache directory
+    pub fn at(base_dir: PathBuf) -> Self {
+        Self {
             git_dir: base_dir.join("git"),
+            oci_dir: base_dir.join("oci"),
+            archive_dir: base_dir.join("archive"),
             metadata_dir: base_dir.join("metadata"),
             base_dir,
-        })
+        }
+    }
+
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
     }
 
     pub fn ensure_directories(&self) -> Result<()> {
-        std::fs::create_dir_all(&self.git_dir.join("db"))?;
-        std::fs::create_dir_all(&self.git_dir.join("checkouts"))?;
+        std::fs::create_dir_all(self.git_dir.join("db"))?;
+        std::fs::create_dir_all(self.git_dir.join("checkouts"))?;
+        std::fs::create_dir_all(&self.oci_dir)?;
+        std::fs::create_dir_all(&self.archive_dir)?;
         std::fs::create_dir_all(&self.metadata_dir)?;
         Ok(())
     }
@@ -47,6 +62,24 @@ impl CachePaths {
         self.git_dir.join("checkouts").join(skill_name)
     }
 
+    /// Unpacked OCI artifact, keyed by manifest digest
+    pub fn oci_path(&self, digest: &str) -> PathBuf {
+        let hex = digest.split_once(':').map_or(digest, |(_, hex)| hex);
+        self.oci_dir.join(hex)
+    }
+
+    /// Extracted archive, keyed by the sha256 of the archive file
+    pub fn archive_path(&self, sha256_hex: &str) -> PathBuf {
+        self.archive_dir.join(sha256_hex)
+    }
+
+    /// Lock guarding a cache entry, next to it
+    pub fn entry_lock_path(&self, entry: &Path) -> PathBuf {
+        let mut name = entry.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        entry.with_file_name(name)
+    }
+
     pub fn metadata_path(&self, cache_key: &str) -> PathBuf {
         self.metadata_dir.join(format!("{}.json", cache_key))
     }
@@ -55,7 +88,6 @@ impl CachePaths {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_cache_paths_creation() {
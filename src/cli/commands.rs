@@ -1,297 +1,730 @@
-use crate::{cli::ConventionCommands, error::Result};
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::{
+    cli::{output::Output, ConfigCommands, ConfigOverrides, ConventionCommands, MirrorCommands},
+    error::Result,
+    skill::plan::Plan,
+    skill::report::{InstallReport, InstalledSkill},
+};
+
+/// Skill manager for either the current project or the user-level skills
+fn skill_manager(
+    global: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<crate::skill::manager::SkillManager> {
+    let skill_manager = if global {
+        crate::skill::manager::SkillManager::new_global()?
+    } else {
+        // Get current directory as project path
+        let project_path = std::env::current_dir()?;
+        crate::skill::manager::SkillManager::new(project_path)?
+    };
+    skill_manager
+        .with_overrides(overrides.layers())
+        .with_reporter(output.clone())
+}
+
+/// Skill manager for a mutating command, holding the project lock unless it
+/// only plans because `dry_run` is set
+async fn mutating_skill_manager(
+    global: bool,
+    dry_run: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<crate::skill::manager::SkillManager> {
+    let skill_manager = skill_manager(global, overrides, output)?;
+    if dry_run {
+        return skill_manager.with_dry_run();
+    }
+    skill_manager.with_lock().await
+}
+
+/// Emit a dry run's plan in place of the command's usual result
+fn plan_result(command: &str, plan: &Plan, output: &Output) -> Result<()> {
+    output.result(command, &json!({ "dry_run": true, "plan": plan }), |_| {
+        print!("Dry run, nothing was changed:\n{}", plan);
+    })
+}
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_add(
     reference: String,
     convention: Option<String>,
+    targets: Vec<String>,
     version: Option<String>,
+    global: bool,
+    dry_run: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
 ) -> Result<()> {
-    // Get current directory as project path
-    let project_path = std::env::current_dir()?;
+    let mut skill_manager = mutating_skill_manager(global, dry_run, overrides, output).await?;
 
-    // Initialize skill manager
-    let mut skill_manager = crate::skill::manager::SkillManager::new(project_path)?;
-
-    // Parse the reference to determine if it's a simplified name or explicit source
-    if is_simplified_skill_reference(&reference) {
-        // Handle simplified skill names like "file-analyzer@1.0.0" or "@user/skill@2.0.0"
-        let (skill_name, skill_version) = parse_skill_reference(&reference)?;
-
-        // Create or update configuration with the new skill
-        let skill_config = if version.is_some() {
-            // Version was overridden in CLI
-            crate::config::skillset::SkillConfig::Detailed {
-                version: version.unwrap(),
-                source: None,
-                convention,
-            }
-        } else {
-            crate::config::skillset::SkillConfig::Simple(skill_version.to_string())
-        };
+    let skill = skill_manager
+        .add(&reference, convention, targets, version)
+        .await?;
+    if dry_run {
+        return plan_result("add", &skill_manager.plan()?, output);
+    }
+    let report = InstallReport {
+        installed: vec![skill],
+        ..Default::default()
+    };
+    output.result("add", &report, |_| {})
+}
 
-        // Add to configuration first
-        {
-            let config = skill_manager.config_mut();
-            config
-                .skills
-                .insert(skill_name.clone(), skill_config.clone());
-        }
+pub async fn handle_remove(
+    name: String,
+    global: bool,
+    dry_run: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let mut skill_manager = mutating_skill_manager(global, dry_run, overrides, output).await?;
 
-        // Save configuration before adding skill
-        skill_manager.save_config()?;
+    let removed = skill_manager.remove_skill(&name).await?;
+    if dry_run {
+        return plan_result("remove", &skill_manager.plan()?, output);
+    }
+    output.result("remove", &json!({ "removed": [removed] }), |_| {})
+}
 
-        // Add skill using simplified name resolution
-        skill_manager
-            .add_skill_by_name(&skill_name, &skill_config)
-            .await?;
-    } else {
-        // Handle explicit source references
-        skill_manager
-            .add_skill(&reference, convention, version)
+pub async fn handle_install(
+    global: bool,
+    workspace: bool,
+    dry_run: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    if workspace {
+        let current_dir = std::env::current_dir()?;
+        let workspace =
+            crate::config::workspace::Workspace::discover(&current_dir)?.ok_or_else(|| {
+                crate::error::SkillsetError::Config(format!(
+                    "{} is not part of a skillset workspace",
+                    current_dir.display()
+                ))
+            })?;
+        if dry_run {
+            let plan = crate::skill::manager::SkillManager::plan_workspace(
+                &workspace,
+                overrides.layers(),
+                output.clone(),
+            )
             .await?;
+            return plan_result("install", &plan, output);
+        }
+        let report = crate::skill::manager::SkillManager::install_workspace(
+            &workspace,
+            overrides.layers(),
+            output.clone(),
+        )
+        .await?;
+        return output.result("install", &report, |_| {});
     }
 
-    Ok(())
-}
+    let mut skill_manager = mutating_skill_manager(global, dry_run, overrides, output).await?;
 
-fn is_simplified_skill_reference(reference: &str) -> bool {
-    // Check if it's a simplified skill name (not an explicit source)
-    !reference.starts_with("git:")
-        && !reference.starts_with("oci:")
-        && !reference.starts_with("https://")
-        && !reference.starts_with("http://")
-        && !reference.starts_with("/")
-        && !reference.starts_with("./")
-        && !reference.starts_with("../")
-        && reference.contains('@') // Should have @version
+    let report = skill_manager.install_all().await?;
+    if dry_run {
+        return plan_result("install", &skill_manager.plan()?, output);
+    }
+    output.result("install", &report, |_| {})
 }
 
-fn parse_skill_reference(reference: &str) -> Result<(String, &str)> {
-    // Parse skill references like "file-analyzer@1.0.0" or "@user/skill@2.0.0"
-    if let Some(at_pos) = reference.rfind('@') {
-        let skill_name = &reference[..at_pos];
-        let version = &reference[at_pos + 1..];
-
-        // Validate skill name format
-        validate_skill_name(skill_name)?;
-        validate_version(version)?;
+pub async fn handle_list(
+    verbose: bool,
+    global: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct SkillLists {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        project: Option<Vec<InstalledSkill>>,
+        global: Vec<InstalledSkill>,
+    }
 
-        Ok((skill_name.to_string(), version))
+    let project = if global {
+        None
     } else {
-        Err(crate::error::SkillsetError::InvalidSkillName(
-            "Skill reference must include version with @. Expected: name@version".to_string(),
-        ))
-    }
+        Some(skill_manager(false, overrides, output)?.installed_skills())
+    };
+    let lists = SkillLists {
+        project,
+        global: skill_manager(true, overrides, output)?.installed_skills(),
+    };
+
+    output.result("list", &lists, |lists| {
+        if let Some(project) = &lists.project {
+            print_skills("project", project, verbose);
+            println!();
+        }
+        print_skills("global", &lists.global, verbose);
+    })
 }
 
-fn validate_skill_name(name: &str) -> Result<()> {
-    if name.is_empty() {
-        return Err(crate::error::SkillsetError::InvalidSkillName(
-            "Skill name cannot be empty".to_string(),
-        ));
+fn print_skills(scope: &str, skills: &[InstalledSkill], verbose: bool) {
+    if skills.is_empty() {
+        println!("No {} skills installed.", scope);
+        return;
     }
 
-    // Handle scoped names like @user/skill
-    if name.starts_with('@') {
-        let scoped_part = &name[1..]; // Remove '@'
-        if scoped_part.is_empty() {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Scoped skill name cannot be empty after @".to_string(),
-            ));
+    println!("Installed {} skills:", scope);
+    for skill in skills {
+        match (verbose, &skill.source, &skill.convention) {
+            (true, Some(source), _) if !skill.targets.is_empty() => println!(
+                "  {} (v{}) - Source: {} - Targets: {}",
+                skill.name,
+                skill.version,
+                source,
+                skill.targets.join(", ")
+            ),
+            (true, Some(source), Some(convention)) => println!(
+                "  {} (v{}) - Source: {} - Convention: {}",
+                skill.name, skill.version, source, convention
+            ),
+            (true, Some(source), None) => {
+                println!("  {} (v{}) - Source: {}", skill.name, skill.version, source)
+            }
+            _ => println!("  {} (v{})", skill.name, skill.version),
         }
+    }
+}
 
-        let parts: Vec<&str> = scoped_part.split('/').collect();
-        if parts.len() != 2 {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Scoped skill name must be in format @user/skill".to_string(),
-            ));
-        }
+pub async fn handle_update(
+    name: Option<String>,
+    global: bool,
+    dry_run: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let mut skill_manager = mutating_skill_manager(global, dry_run, overrides, output).await?;
 
-        let (user, skill) = (parts[0], parts[1]);
+    let names: Vec<String> = name.into_iter().collect();
+    let report = skill_manager.update(&names).await?;
+    if dry_run {
+        return plan_result("update", &skill_manager.plan()?, output);
+    }
+    output.result("update", &report, |_| {})
+}
 
-        // Validate username part
-        if user.is_empty() || user.len() > 39 {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Username in scoped skill name must be 1-39 characters".to_string(),
-            ));
+pub async fn handle_info(
+    name: String,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let skill_manager = skill_manager(false, overrides, output)?;
+    let info = skill_manager.skill_info(&name)?;
+
+    output.result("info", &info, |info| {
+        println!("Skill: {}", info.name);
+        println!("Version: {}", info.version);
+        match &info.source {
+            Some(source) => println!("Source: {}", source),
+            None => println!("Resolved Source: {}", info.resolved_source),
         }
-
-        // Validate skill name part
-        if skill.is_empty() || skill.len() > 100 {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Skill name must be 1-100 characters".to_string(),
-            ));
+        if let Some(convention) = &info.convention {
+            println!("Convention: {}", convention);
         }
-
-        // Check for valid characters (alphanumeric, hyphens, underscores)
-        if !user
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-        {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Username can only contain alphanumeric characters, hyphens, and underscores"
-                    .to_string(),
-            ));
+        match &info.locked {
+            Some(locked) => {
+                println!("Installed at: {}", locked.path.display());
+                for target in &locked.targets {
+                    println!(
+                        "Also installed at: {} ({})",
+                        target.path.display(),
+                        target.convention
+                    );
+                }
+                println!("Locked source: {}", locked.source);
+                println!("Checksum: {}", locked.checksum);
+            }
+            None => println!("Not installed"),
         }
+    })
+}
 
-        if !skill
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-        {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Skill name can only contain alphanumeric characters, hyphens, and underscores"
-                    .to_string(),
-            ));
-        }
-    } else {
-        // Validate simple skill names
-        if name.len() > 100 {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Skill name must be 1-100 characters".to_string(),
-            ));
-        }
+pub async fn handle_verify(
+    fix: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let mut skill_manager = skill_manager(false, overrides, output)?;
+
+    let mut report = skill_manager.verify().await?;
+    if output.is_text() {
+        print_verify_report(&report);
+    }
 
-        if !name
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-        {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Skill name can only contain alphanumeric characters, hyphens, and underscores"
-                    .to_string(),
-            ));
+    let mut fixed = Vec::new();
+    if fix && !report.skills_to_reinstall().is_empty() {
+        fixed = skill_manager.fix(&report).await?;
+        if output.is_text() {
+            for name in &fixed {
+                println!("Fixed: {}", name);
+            }
         }
+
+        // Re-check so the exit status reflects the repaired state
+        report = skill_manager.verify().await?;
     }
 
-    Ok(())
+    let data = json!({
+        "clean": report.is_clean(),
+        "skills": report.skills,
+        "environment": report.environment,
+        "fixed": fixed,
+    });
+    if report.is_clean() {
+        output.result("verify", &data, |_| {})?;
+    } else {
+        output.failure("verify", &data, |_| {})?;
+    }
+    verify_result(&report)
 }
 
-fn validate_version(version: &str) -> Result<()> {
-    if version.is_empty() {
-        return Err(crate::error::SkillsetError::InvalidSkillName(
-            "Version cannot be empty".to_string(),
-        ));
+fn print_verify_report(report: &crate::skill::verify::VerifyReport) {
+    if report.skills.is_empty() {
+        println!("Skills: all installed skills match skillset.json and skillset.lock");
+    } else {
+        println!("Skills:");
+        for drift in &report.skills {
+            match &drift.path {
+                Some(path) => println!(
+                    "  {:<10} {} ({}) - {}",
+                    drift.kind.to_string(),
+                    drift.name,
+                    path.display(),
+                    drift.detail
+                ),
+                None => println!(
+                    "  {:<10} {} - {}",
+                    drift.kind.to_string(),
+                    drift.name,
+                    drift.detail
+                ),
+            }
+        }
     }
 
-    // Allow "latest" or semantic version patterns
-    if version == "latest" {
-        return Ok(());
+    println!("Environment:");
+    for check in &report.environment {
+        println!(
+            "  {:<10} {} - {}",
+            check.status.to_string(),
+            check.name,
+            check.detail
+        );
     }
+}
 
-    // Basic semantic version validation (X.Y.Z or vX.Y.Z)
-    let version_to_check = if version.starts_with('v') {
-        &version[1..]
+fn verify_result(report: &crate::skill::verify::VerifyReport) -> Result<()> {
+    if report.is_clean() {
+        Ok(())
     } else {
-        version
-    };
-
-    // Split by dots and validate each part is numeric
-    let parts: Vec<&str> = version_to_check.split('.').collect();
-    if parts.len() < 2 || parts.len() > 4 {
-        return Err(crate::error::SkillsetError::InvalidSkillName(
-            "Version must be in semantic version format (e.g., 1.0.0 or v1.0.0)".to_string(),
-        ));
+        Err(crate::error::SkillsetError::Verification(format!(
+            "{} problem(s) found",
+            report.skills.len()
+                + report
+                    .environment
+                    .iter()
+                    .filter(|c| c.status == crate::skill::verify::CheckStatus::Failed)
+                    .count()
+        )))
     }
+}
+
+pub async fn handle_config(
+    command: ConfigCommands,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let project_path = std::env::current_dir()?;
+
+    match command {
+        ConfigCommands::Get { key, show_origin } => {
+            let skill_manager = skill_manager(false, overrides, output)?;
+            let own_origin =
+                crate::config::layered::ConfigOrigin::Project(skill_manager.manifest_path()?);
+            let (value, origin) = skill_manager.config().resolve_setting(&key, &own_origin)?;
+
+            let data = json!({ "key": key, "value": value, "origin": origin.to_string() });
+            output.result("config get", &data, |_| {
+                if show_origin {
+                    println!("{}\t{}", value, origin);
+                } else {
+                    println!("{}", value);
+                }
+            })
+        }
+        ConfigCommands::Set { key, value, user } => {
+            let path = if user {
+                let path = crate::config::layered::user_config_path().ok_or_else(|| {
+                    crate::error::SkillsetError::Config("No config directory found".to_string())
+                })?;
+                let mut settings = if path.exists() {
+                    crate::config::layered::load_settings(&path)?
+                } else {
+                    crate::config::layered::Settings::default()
+                };
+                settings.set(&key, &value)?;
+                crate::config::layered::save_settings(&path, &settings)?;
+                path
+            } else {
+                let mut skill_manager =
+                    skill_manager(false, overrides, output)?.with_lock().await?;
+                skill_manager.config_mut().set_setting(&key, &value)?;
+                skill_manager.save_config()?;
+                skill_manager.manifest_path()?
+            };
+
+            let data = json!({ "key": key, "value": value, "file": path });
+            output.result("config set", &data, |_| {
+                println!("Set {} = {} in {}", key, value, path.display());
+            })
+        }
+        ConfigCommands::List { show_origin } => {
+            let skill_manager = skill_manager(false, overrides, output)?;
+            let own_origin =
+                crate::config::layered::ConfigOrigin::Project(skill_manager.manifest_path()?);
+
+            let mut settings = Vec::new();
+            for key in crate::config::layered::SETTING_KEYS {
+                let (value, origin) = skill_manager.config().resolve_setting(key, &own_origin)?;
+                settings.push((key, value, origin));
+            }
+
+            let data = json!({
+                "settings": settings
+                    .iter()
+                    .map(|(key, value, origin)| {
+                        json!({ "key": key, "value": value, "origin": origin.to_string() })
+                    })
+                    .collect::<Vec<_>>(),
+            });
+            output.result("config list", &data, |_| {
+                for (key, value, origin) in &settings {
+                    if show_origin {
+                        println!("{} = {}\t{}", key, value, origin);
+                    } else {
+                        println!("{} = {}", key, value);
+                    }
+                }
+            })
+        }
+        ConfigCommands::Validate => {
+            let manifest =
+                crate::config::format::find_manifest(&project_path)?.ok_or_else(|| {
+                    crate::error::SkillsetError::Config(format!(
+                        "No skillset manifest found in {}",
+                        project_path.display()
+                    ))
+                })?;
+
+            let diagnostics = crate::config::validate::validate_manifest(&manifest)?;
+            let data = json!({
+                "file": manifest,
+                "valid": diagnostics.is_empty(),
+                "diagnostics": diagnostics,
+            });
+            if diagnostics.is_empty() {
+                return output.result("config validate", &data, |_| {
+                    println!("{} is valid", manifest.display());
+                });
+            }
 
-    for part in parts {
-        if part.is_empty() {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Version parts cannot be empty".to_string(),
-            ));
+            output.failure("config validate", &data, |_| {
+                for diagnostic in &diagnostics {
+                    eprintln!("{}", diagnostic);
+                }
+            })?;
+            Err(crate::error::SkillsetError::Config(format!(
+                "{} problem(s) found in {}",
+                diagnostics.len(),
+                manifest.display()
+            )))
         }
+        ConfigCommands::Convert { to } => {
+            let format =
+                crate::config::format::ManifestFormat::from_name(&to).ok_or_else(|| {
+                    crate::error::SkillsetError::Config(format!(
+                        "Unknown manifest format: {}. Available: json, toml, yaml",
+                        to
+                    ))
+                })?;
+            // Held while the manifest is rewritten in the new format
+            let _locked = skill_manager(false, overrides, output)?.with_lock().await?;
+            let source = crate::config::format::find_manifest(&project_path)?.ok_or_else(|| {
+                crate::error::SkillsetError::Config(format!(
+                    "No skillset manifest found in {}",
+                    project_path.display()
+                ))
+            })?;
+
+            if crate::config::format::ManifestFormat::from_path(&source) == format {
+                let data = json!({ "from": source, "to": source, "converted": false });
+                return output.result("config convert", &data, |_| {
+                    println!(
+                        "{} is already in {} format",
+                        source.display(),
+                        format.name()
+                    );
+                });
+            }
 
-        // Allow numeric parts and pre-release identifiers
-        if part
-            .chars()
-            .any(|c| !c.is_alphanumeric() && c != '-' && c != '+')
-        {
-            return Err(crate::error::SkillsetError::InvalidSkillName(
-                "Version parts can only contain alphanumeric characters, hyphens, and plus signs"
-                    .to_string(),
-            ));
+            let config = crate::config::skillset::SkillsetConfig::load_from_file(&source)?;
+            let target = project_path.join(format.manifest_name());
+            config.save_to_file(&target)?;
+            // Comments and layout do not survive the conversion, so the
+            // original is kept under a name that is not read as a manifest
+            let mut backup = source.clone().into_os_string();
+            backup.push(".bak");
+            let backup = std::path::PathBuf::from(backup);
+            std::fs::rename(&source, &backup)?;
+
+            let data = json!({
+                "from": source,
+                "to": target,
+                "converted": true,
+                "backup": backup,
+            });
+            output.result("config convert", &data, |_| {
+                println!("Converted {} to {}", source.display(), target.display());
+                println!("The original was kept as {}", backup.display());
+            })
         }
     }
+}
 
-    Ok(())
+pub async fn handle_schema(output: &Arc<Output>) -> Result<()> {
+    let schema = crate::config::validate::schema();
+    let content = serde_json::to_string_pretty(&schema)
+        .map_err(|e| crate::error::SkillsetError::SerializationError(e.to_string()))?;
+    output.result("schema", &schema, |_| println!("{}", content))
 }
 
-pub async fn handle_remove(name: String) -> Result<()> {
-    let project_path = std::env::current_dir()?;
-    let mut skill_manager = crate::skill::manager::SkillManager::new(project_path)?;
+pub async fn handle_mirror(
+    command: MirrorCommands,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    match command {
+        MirrorCommands::Sync { mirror, names } => {
+            let skill_manager = skill_manager(false, overrides, output)?;
+            let report = skill_manager.mirror_sync(&mirror, &names).await?;
 
-    skill_manager.remove_skill(&name).await
+            output.result("mirror sync", &report, |report| {
+                for skill in &report.synced {
+                    println!("Synced {} to {} ({})", skill.name, mirror, skill.digest);
+                }
+                println!("{} skill(s) synced", report.synced.len());
+            })
+        }
+    }
 }
 
-pub async fn handle_list(verbose: bool) -> Result<()> {
-    let project_path = std::env::current_dir()?;
-    let skill_manager = crate::skill::manager::SkillManager::new(project_path)?;
+/// Host credentials are stored under for a registry given on the command
+/// line, e.g. `https://ghcr.io/acme` -> `ghcr.io`
+fn credentials_host(registry: &str) -> String {
+    let registry = registry
+        .split_once("://")
+        .map_or(registry, |(_, rest)| rest);
+    crate::config::skillset::registry_host(registry).to_string()
+}
 
-    skill_manager.list_skills(verbose)
+pub async fn handle_login(
+    registry: String,
+    username: Option<String>,
+    password_stdin: bool,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    use crate::registry::auth::{login, Credentials};
+    use std::io::{BufRead, Write};
+
+    let host = credentials_host(&registry);
+    let username = match username {
+        Some(username) => username,
+        None => {
+            // Prompts go to stderr so they never mix with JSON output
+            eprint!("Username: ");
+            std::io::stderr().flush()?;
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)?;
+            line.trim().to_string()
+        }
+    };
+    let password = if password_stdin {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        rpassword::prompt_password("Password: ")?
+    };
+    if username.is_empty() || password.is_empty() {
+        return Err(crate::error::SkillsetError::Config(
+            "Username and password must not be empty".to_string(),
+        ));
+    }
+
+    let credentials = Credentials { username, password };
+    let settings = skill_manager(false, overrides, output)
+        .map(|manager| manager.config().registry_settings(&host))
+        .unwrap_or_default();
+    let path = login(&host, &settings, &credentials).await?;
+    let data = json!({ "registry": host, "credentials_file": path });
+    output.result("login", &data, |_| {
+        println!("Login succeeded for {}", host);
+        println!("Credentials stored in {}", path.display());
+    })
 }
 
-pub async fn handle_update(name: Option<String>) -> Result<()> {
-    println!("Updating skills (specific: {:?})", name);
-    todo!("Implement update command")
+pub async fn handle_logout(registry: String, output: &Arc<Output>) -> Result<()> {
+    let host = credentials_host(&registry);
+    let removed = crate::registry::auth::remove_credentials(&host)?;
+
+    let data = json!({ "registry": host, "removed": removed });
+    output.result("logout", &data, |_| {
+        if removed {
+            println!("Removed credentials for {}", host);
+        } else {
+            println!("No stored credentials for {}", host);
+        }
+    })
 }
 
-pub async fn handle_info(name: String) -> Result<()> {
+/// Answer MCP requests on stdin and stdout until stdin is closed. Progress
+/// is not reported, stdout belongs to the protocol.
+pub async fn handle_serve(global: bool, overrides: &ConfigOverrides) -> Result<()> {
     let project_path = std::env::current_dir()?;
-    let skill_manager = crate::skill::manager::SkillManager::new(project_path)?;
+    let layers = overrides.layers();
+    let server = crate::server::McpServer::new(Box::new(move || {
+        let skill_manager = if global {
+            crate::skill::manager::SkillManager::new_global()?
+        } else {
+            crate::skill::manager::SkillManager::new(project_path.clone())?
+        };
+        Ok(skill_manager.with_overrides(layers.clone()))
+    }));
+
+    let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+    server.serve(stdin, tokio::io::stdout()).await
+}
+
+pub async fn handle_convert(
+    path: std::path::PathBuf,
+    to: String,
+    dest: Option<std::path::PathBuf>,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let builtin = crate::conventions::builtin_conventions();
+    let convention = builtin
+        .iter()
+        .find(|convention| convention.name() == to)
+        .ok_or_else(|| crate::error::SkillsetError::ConventionNotFound(to.clone()))?;
+    let dest = match dest {
+        Some(dest) => dest,
+        None => {
+            let source = path.canonicalize()?;
+            let name = source.file_name().unwrap_or_default().to_string_lossy();
+            std::env::current_dir()?.join(format!("{}-{}", name, to))
+        }
+    };
 
-    skill_manager.show_skill_info(&name).await
+    let converted =
+        crate::conventions::convert::convert_skill(convention.as_ref(), &path, &dest).await?;
+    output.result("convert", &converted, |converted| {
+        match &converted.from {
+            Some(from) => println!(
+                "Converted {} from {} to {}",
+                converted.name, from, converted.to
+            ),
+            None => println!("Copied {} as {}", converted.name, converted.to),
+        }
+        println!("Written to {}", converted.path.display());
+    })
 }
 
-pub async fn handle_convention(command: ConventionCommands) -> Result<()> {
-    let project_path = std::env::current_dir().map_err(|e| crate::error::SkillsetError::Io(e))?;
-    let mut manager = crate::skill::manager::SkillManager::new(project_path)?;
+pub async fn handle_convention(
+    command: ConventionCommands,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> Result<()> {
+    let manager = skill_manager(false, overrides, output)?;
 
     match command {
         ConventionCommands::List => {
             let conventions = manager.config().get_conventions();
-            if conventions.is_empty() {
-                println!("No conventions are enabled");
-            } else {
-                println!("Enabled conventions:");
-                for convention in conventions {
-                    println!("  - {}", convention);
+            let data = json!({ "conventions": conventions });
+            output.result("convention list", &data, |_| {
+                if conventions.is_empty() {
+                    println!("No conventions are enabled");
+                } else {
+                    println!("Enabled conventions:");
+                    for convention in &conventions {
+                        println!("  - {}", convention);
+                    }
                 }
-            }
-            Ok(())
+            })
         }
         ConventionCommands::Enable { name } => {
+            let mut manager = manager.with_lock().await?;
             let config = manager.config_mut();
-            let mut conventions = config.get_conventions();
+            let mut conventions = config.project_conventions();
 
             if conventions.contains(&name) {
-                println!("Convention '{}' is already enabled", name);
-                return Ok(());
+                let data = json!({ "name": name, "enabled": true, "changed": false });
+                return output.result("convention enable", &data, |_| {
+                    println!("Convention '{}' is already enabled", name);
+                });
             }
 
             // Validate convention name
-            if !["autogpt", "langchain", "agent-skills"].contains(&name.as_str()) {
+            let builtin = crate::conventions::builtin_conventions();
+            let available: Vec<&str> = builtin.iter().map(|c| c.name()).collect();
+            if !available.contains(&name.as_str()) {
                 return Err(crate::error::SkillsetError::Config(format!(
-                    "Unknown convention: {}. Available: autogpt, langchain, agent-skills",
-                    name
+                    "Unknown convention: {}. Available: {}",
+                    name,
+                    available.join(", ")
                 )));
             }
 
             conventions.push(name.clone());
             config.conventions = Some(conventions);
             manager.save_config()?;
-            println!("Enabled convention: {}", name);
-            Ok(())
+
+            let data = json!({ "name": name, "enabled": true, "changed": true });
+            output.result("convention enable", &data, |_| {
+                println!("Enabled convention: {}", name);
+            })
         }
         ConventionCommands::Disable { name } => {
+            let mut manager = manager.with_lock().await?;
             let config = manager.config_mut();
-            let mut conventions = config.get_conventions();
-
-            if let Some(pos) = conventions.iter().position(|c| c == &name) {
-                conventions.remove(pos);
-                config.conventions = Some(conventions);
-                manager.save_config()?;
-                println!("Disabled convention: {}", name);
-            } else {
-                println!("Convention '{}' is not enabled", name);
-            }
-            Ok(())
+            let mut conventions = config.project_conventions();
+
+            let changed = match conventions.iter().position(|c| c == &name) {
+                Some(pos) => {
+                    conventions.remove(pos);
+                    config.conventions = Some(conventions);
+                    manager.save_config()?;
+                    true
+                }
+                None => false,
+            };
+
+            let data = json!({ "name": name, "enabled": false, "changed": changed });
+            output.result("convention disable", &data, |_| {
+                if changed {
+                    println!("Disabled convention: {}", name);
+                } else {
+                    println!("Convention '{}' is not enabled", name);
+                }
+            })
         }
         ConventionCommands::Configure { name } => {
             println!("Configuring convention: {}", name);
@@ -303,11 +736,20 @@ pub async fn handle_convention(command: ConventionCommands) -> Result<()> {
 pub async fn handle_publish(
     path: String,
     reference: String,
-    registry: Option<String>,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
 ) -> Result<()> {
-    println!(
-        "Publishing skill from {} to {} (registry: {:?})",
-        path, reference, registry
-    );
-    todo!("Implement publish command")
+    let skill_manager = skill_manager(false, overrides, output)?;
+    let report = skill_manager
+        .publish(std::path::Path::new(&path), &reference)
+        .await?;
+
+    output.result("publish", &report, |report| {
+        println!(
+            "Published {} ({}), {} file(s)",
+            report.reference,
+            report.digest,
+            report.files.len()
+        );
+    })
 }
@@ -1,14 +1,53 @@
-use clap::{Parser, Subcommand};
+use std::sync::Arc;
+
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::layered::{ConfigLayer, ConfigOrigin, Settings, SETTING_KEYS};
+pub use output::{Output, OutputFormat};
 
 #[derive(Parser)]
 #[command(name = "skillset")]
 #[command(about = "A package manager for coding agent skills")]
 #[command(version)]
 pub struct Cli {
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub format: OutputFormat,
+    #[command(flatten)]
+    pub overrides: ConfigOverrides,
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Settings given on the command line, overriding every config file
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    /// Registry to resolve skill names against (or publish to)
+    #[arg(long, global = true)]
+    pub registry: Option<String>,
+    /// Comma-separated list of conventions to enable
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub conventions: Option<Vec<String>>,
+}
+
+impl ConfigOverrides {
+    pub fn layers(&self) -> Vec<ConfigLayer> {
+        if self.registry.is_none() && self.conventions.is_none() {
+            return Vec::new();
+        }
+
+        vec![ConfigLayer {
+            origin: ConfigOrigin::Cli,
+            settings: Settings {
+                registry: self.registry.clone(),
+                conventions: self.conventions.clone(),
+                ..Default::default()
+            },
+        }]
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Add a new skill from a source
@@ -19,45 +58,188 @@ pub enum Commands {
         /// Override convention detection
         #[arg(long, short)]
         convention: Option<String>,
+        /// Install for each of these conventions (repeatable or comma-separated)
+        #[arg(
+            long = "target",
+            short,
+            value_delimiter = ',',
+            conflicts_with = "convention"
+        )]
+        targets: Vec<String>,
         /// Override version (for use with explicit source references)
         #[arg(long, short)]
         version: Option<String>,
+        /// Install for the current user instead of the project
+        #[arg(long, short)]
+        global: bool,
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Remove an installed skill
     Remove {
         /// Skill name to remove
         name: String,
+        /// Remove a user-level skill
+        #[arg(long, short)]
+        global: bool,
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Install all skills declared in skillset.json
+    Install {
+        /// Install the user-level skills instead of the project's
+        #[arg(long, short)]
+        global: bool,
+        /// Install the workspace root and every member
+        #[arg(long, conflicts_with = "global")]
+        workspace: bool,
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// List installed skills
     List {
         /// Show detailed information
         #[arg(long, short)]
         verbose: bool,
+        /// Only list user-level skills
+        #[arg(long, short)]
+        global: bool,
     },
     /// Update skills to latest versions
     Update {
         /// Specific skill to update (optional)
         name: Option<String>,
+        /// Update user-level skills
+        #[arg(long, short)]
+        global: bool,
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Get information about a skill
     Info {
         /// Skill name
         name: String,
     },
+    /// Check installed skills against skillset.json and skillset.lock
+    #[command(visible_alias = "doctor")]
+    Verify {
+        /// Reinstall skills that are missing, modified or misplaced
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Manage the skillset manifest
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Print the JSON Schema for skillset.json
+    Schema,
+    /// Manage registry mirrors
+    Mirror {
+        #[command(subcommand)]
+        command: MirrorCommands,
+    },
+    /// Store credentials for an OCI registry
+    Login {
+        /// Registry host, e.g. ghcr.io
+        registry: String,
+        /// Username (prompted for when omitted)
+        #[arg(long, short)]
+        username: Option<String>,
+        /// Read the password or token from stdin instead of prompting
+        #[arg(long)]
+        password_stdin: bool,
+    },
+    /// Remove stored credentials for an OCI registry
+    Logout {
+        /// Registry host
+        registry: String,
+    },
     /// Manage agent conventions
     Convention {
         #[command(subcommand)]
         command: ConventionCommands,
     },
-    /// OCI registry operations
+    /// Convert a skill directory into another convention's layout
+    Convert {
+        /// Skill directory
+        path: std::path::PathBuf,
+        /// Convention to convert to
+        #[arg(long)]
+        to: String,
+        /// Directory to write the converted skill to (default: <name>-<convention>)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Serve the installed skills to agents
+    Serve {
+        /// Speak the Model Context Protocol on stdin and stdout
+        #[arg(long, required = true)]
+        mcp: bool,
+        /// Serve user-level skills
+        #[arg(long, short)]
+        global: bool,
+    },
+    /// Publish a skill to an OCI registry, shipping the files it installs
     Publish {
         /// Local skill path to publish
         path: String,
-        /// Target OCI reference
+        /// Target OCI reference, `host/repository:tag` or `name:tag` under the registry
         reference: String,
-        /// Registry to publish to
-        #[arg(long, short)]
-        registry: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MirrorCommands {
+    /// Copy skills from their upstream registry to a mirror
+    Sync {
+        /// Mirror to copy to, as host[/prefix]
+        mirror: String,
+        /// Skills to copy (defaults to every skill in the manifest)
+        names: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Check the manifest for unknown keys, typos and unknown conventions
+    Validate,
+    /// Print the effective value of a setting
+    Get {
+        /// Setting name
+        #[arg(value_parser = PossibleValuesParser::new(SETTING_KEYS))]
+        key: String,
+        /// Show which config source provided the value
+        #[arg(long)]
+        show_origin: bool,
+    },
+    /// Set a value in the project manifest or the user config
+    Set {
+        /// Setting name
+        #[arg(value_parser = PossibleValuesParser::new(SETTING_KEYS))]
+        key: String,
+        /// New value, comma-separated for lists
+        value: String,
+        /// Write to ~/.config/skillset/config.toml instead of the project
+        #[arg(long)]
+        user: bool,
+    },
+    /// List the effective value of every setting
+    List {
+        /// Show which config source provided each value
+        #[arg(long)]
+        show_origin: bool,
+    },
+    /// Rewrite the manifest in another format (json, toml or yaml). The
+    /// original is renamed to `<name>.bak`, since comments are not carried over.
+    Convert {
+        /// Target format
+        #[arg(long)]
+        to: String,
     },
 }
 
@@ -77,23 +259,76 @@ pub enum ConventionCommands {
 
 mod args;
 mod commands;
+pub mod output;
 
 pub async fn handle_command(cli: Cli) -> crate::error::Result<()> {
-    match cli.command {
+    let output = Arc::new(Output::new(cli.format));
+    let result = dispatch(cli.command, &cli.overrides, &output).await;
+    if let Err(error) = &result {
+        output.error(error);
+    }
+    result
+}
+
+async fn dispatch(
+    command: Commands,
+    overrides: &ConfigOverrides,
+    output: &Arc<Output>,
+) -> crate::error::Result<()> {
+    match command {
         Commands::Add {
             reference,
             convention,
+            targets,
             version,
-        } => commands::handle_add(reference, convention, version).await,
-        Commands::Remove { name } => commands::handle_remove(name).await,
-        Commands::List { verbose } => commands::handle_list(verbose).await,
-        Commands::Update { name } => commands::handle_update(name).await,
-        Commands::Info { name } => commands::handle_info(name).await,
-        Commands::Convention { command } => commands::handle_convention(command).await,
-        Commands::Publish {
-            path,
-            reference,
+            global,
+            dry_run,
+        } => {
+            commands::handle_add(
+                reference, convention, targets, version, global, dry_run, overrides, output,
+            )
+            .await
+        }
+        Commands::Remove {
+            name,
+            global,
+            dry_run,
+        } => commands::handle_remove(name, global, dry_run, overrides, output).await,
+        Commands::Install {
+            global,
+            workspace,
+            dry_run,
+        } => commands::handle_install(global, workspace, dry_run, overrides, output).await,
+        Commands::List { verbose, global } => {
+            commands::handle_list(verbose, global, overrides, output).await
+        }
+        Commands::Update {
+            name,
+            global,
+            dry_run,
+        } => commands::handle_update(name, global, dry_run, overrides, output).await,
+        Commands::Info { name } => commands::handle_info(name, overrides, output).await,
+        Commands::Verify { fix } => commands::handle_verify(fix, overrides, output).await,
+        Commands::Config { command } => commands::handle_config(command, overrides, output).await,
+        Commands::Schema => commands::handle_schema(output).await,
+        Commands::Mirror { command } => commands::handle_mirror(command, overrides, output).await,
+        Commands::Login {
             registry,
-        } => commands::handle_publish(path, reference, registry).await,
+            username,
+            password_stdin,
+        } => commands::handle_login(registry, username, password_stdin, overrides, output).await,
+        Commands::Logout { registry } => commands::handle_logout(registry, output).await,
+        Commands::Convention { command } => {
+            commands::handle_convention(command, overrides, output).await
+        }
+        Commands::Convert {
+            path,
+            to,
+            output: dest,
+        } => commands::handle_convert(path, to, dest, output).await,
+        Commands::Serve { mcp: _, global } => commands::handle_serve(global, overrides).await,
+        Commands::Publish { path, reference } => {
+            commands::handle_publish(path, reference, overrides, output).await
+        }
     }
 }
@@ -0,0 +1,175 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::{Result, SkillsetError};
+use crate::skill::report::{Event, Reporter};
+
+/// Bumped whenever a field is removed or changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON document once the command finishes
+    Json,
+    /// One JSON object per line: progress events, then the result
+    Ndjson,
+}
+
+/// Renders a command's progress and result in the selected format. Every
+/// command produces exactly one result or error document in the JSON formats.
+pub struct Output {
+    format: OutputFormat,
+    finished: AtomicBool,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == OutputFormat::Text
+    }
+
+    /// Emit the result of a successful command. `text` renders it for humans.
+    pub fn result<T: Serialize>(
+        &self,
+        command: &str,
+        data: &T,
+        text: impl FnOnce(&T),
+    ) -> Result<()> {
+        self.finish(command, true, data, text)
+    }
+
+    /// Emit a result describing a failure, e.g. a verify report with drift.
+    /// The command is still expected to return an error for the exit status.
+    pub fn failure<T: Serialize>(
+        &self,
+        command: &str,
+        data: &T,
+        text: impl FnOnce(&T),
+    ) -> Result<()> {
+        self.finish(command, false, data, text)
+    }
+
+    fn finish<T: Serialize>(
+        &self,
+        command: &str,
+        ok: bool,
+        data: &T,
+        text: impl FnOnce(&T),
+    ) -> Result<()> {
+        self.finished.store(true, Ordering::SeqCst);
+        if self.is_text() {
+            text(data);
+            return Ok(());
+        }
+
+        let mut document = json!({
+            "schema_version": SCHEMA_VERSION,
+            "command": command,
+            "ok": ok,
+            "data": data,
+        });
+        if self.format == OutputFormat::Ndjson {
+            document["type"] = json!("result");
+        }
+        self.print(&document);
+        Ok(())
+    }
+
+    /// Emit the error a command failed with, unless it already produced a
+    /// result. Text errors are left to the caller.
+    pub fn error(&self, error: &SkillsetError) {
+        if self.is_text() || self.finished.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut document = json!({
+            "schema_version": SCHEMA_VERSION,
+            "ok": false,
+            "error": error_object(error),
+        });
+        if self.format == OutputFormat::Ndjson {
+            document["type"] = json!("error");
+        }
+        self.print(&document);
+    }
+
+    fn print(&self, document: &Value) {
+        match self.format {
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(document).unwrap_or_default()
+            ),
+            _ => println!("{}", document),
+        }
+    }
+}
+
+impl Reporter for Output {
+    fn report(&self, event: &Event) {
+        match self.format {
+            // Waiting says why nothing happens rather than what happened,
+            // and warnings are about the input, so both go to stderr where
+            // they do not mix with the result
+            OutputFormat::Text | OutputFormat::Json
+                if matches!(event, Event::Waiting { .. } | Event::Warning(_)) =>
+            {
+                eprintln!("{}", event)
+            }
+            OutputFormat::Text => println!("{}", event),
+            OutputFormat::Ndjson => {
+                let mut line = serde_json::to_value(event).unwrap_or_default();
+                line["type"] = json!("event");
+                println!("{}", line);
+            }
+            OutputFormat::Json => {}
+        }
+    }
+}
+
+fn error_object(error: &SkillsetError) -> Value {
+    let mut object = json!({
+        "kind": error.kind(),
+        "message": error.to_string(),
+    });
+    if let SkillsetError::InvalidManifest(diagnostic) = error {
+        object["diagnostic"] = json!(diagnostic);
+    }
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_object_has_kind_and_details() {
+        let error = SkillsetError::InvalidManifest(crate::config::validate::Diagnostic {
+            file: "skillset.json".into(),
+            line: Some(3),
+            column: Some(5),
+            message: "unknown field `skils`".to_string(),
+            suggestion: Some("skills".to_string()),
+        });
+        let object = error_object(&error);
+
+        assert_eq!(object["kind"], "invalid_manifest");
+        assert_eq!(object["diagnostic"]["line"], 3);
+        assert_eq!(object["diagnostic"]["suggestion"], "skills");
+        assert_eq!(
+            error_object(&SkillsetError::SkillNotFound("web".to_string()))["kind"],
+            "skill_not_found"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{Result, SkillsetError};
+
+/// Manifest file names in lookup order
+pub const MANIFEST_NAMES: [&str; 4] = [
+    "skillset.json",
+    "skillset.toml",
+    "skillset.yaml",
+    "skillset.yml",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ManifestFormat {
+    /// Detect the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Json)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    pub fn manifest_name(&self) -> &'static str {
+        match self {
+            Self::Json => "skillset.json",
+            Self::Toml => "skillset.toml",
+            Self::Yaml => "skillset.yaml",
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T> {
+        match self {
+            Self::Json => Ok(serde_json::from_str(content)?),
+            Self::Toml => toml::from_str(content)
+                .map_err(|e| SkillsetError::SerializationError(format!("TOML: {}", e))),
+            Self::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| SkillsetError::SerializationError(format!("YAML: {}", e))),
+        }
+    }
+
+    pub fn render<T: Serialize>(&self, value: &T) -> Result<String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value)
+                .map_err(|e| SkillsetError::SerializationError(e.to_string())),
+            Self::Toml => toml::to_string_pretty(value)
+                .map_err(|e| SkillsetError::SerializationError(format!("TOML: {}", e))),
+            Self::Yaml => serde_yaml::to_string(value)
+                .map_err(|e| SkillsetError::SerializationError(format!("YAML: {}", e))),
+        }
+    }
+}
+
+/// Locate the manifest in `dir`, whichever format it is written in.
+/// Having more than one is an error since it is unclear which one wins.
+pub fn find_manifest(dir: &Path) -> Result<Option<PathBuf>> {
+    let found: Vec<PathBuf> = MANIFEST_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.exists())
+        .collect();
+
+    match found.len() {
+        0 => Ok(None),
+        1 => Ok(found.into_iter().next()),
+        _ => Err(SkillsetError::Config(format!(
+            "Multiple manifests found in {}: {}",
+            dir.display(),
+            found
+                .iter()
+                .filter_map(|p| p.file_name())
+                .map(|n| n.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// The manifest path for `dir`, or where a new JSON manifest would go
+pub fn manifest_path(dir: &Path) -> Result<PathBuf> {
+    Ok(find_manifest(dir)?.unwrap_or_else(|| dir.join(ManifestFormat::Json.manifest_name())))
+}
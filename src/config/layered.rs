@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::format::ManifestFormat;
+use crate::config::skillset::{RegistrySettings, SkillsetConfig};
+use crate::error::{Result, SkillsetError};
+
+/// Settings that can be layered, in the order `config list` shows them
+pub const SETTING_KEYS: [&str; 4] = ["registry", "conventions", "targets", "integrity"];
+
+pub const DEFAULT_REGISTRY: &str = "ghcr.io/skillset";
+
+pub fn default_conventions() -> Vec<String> {
+    vec![
+        "autogpt".to_string(),
+        "langchain".to_string(),
+        "agent-skills".to_string(),
+    ]
+}
+
+/// Whether archive sources must declare an `integrity` hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IntegrityPolicy {
+    #[default]
+    Optional,
+    Required,
+}
+
+impl fmt::Display for IntegrityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Optional => write!(f, "optional"),
+            Self::Required => write!(f, "required"),
+        }
+    }
+}
+
+impl std::str::FromStr for IntegrityPolicy {
+    type Err = SkillsetError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim() {
+            "optional" => Ok(Self::Optional),
+            "required" => Ok(Self::Required),
+            other => Err(SkillsetError::Config(format!(
+                "Invalid integrity setting: {}. Expected optional or required",
+                other
+            ))),
+        }
+    }
+}
+
+/// Where a setting's effective value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Cli,
+    Env(String),
+    Project(PathBuf),
+    Workspace(PathBuf),
+    User(PathBuf),
+    Default,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cli => write!(f, "command line"),
+            Self::Env(var) => write!(f, "env {}", var),
+            Self::Project(path) => write!(f, "{}", path.display()),
+            Self::Workspace(path) => write!(f, "{} (workspace)", path.display()),
+            Self::User(path) => write!(f, "{} (user)", path.display()),
+            Self::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// The layerable settings of one configuration source
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conventions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<IntegrityPolicy>,
+    /// Merged per scope rather than replaced wholesale, see
+    /// `SkillsetConfig::scope_registry`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scopes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, RegistrySettings>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    pub origin: ConfigOrigin,
+    pub settings: Settings,
+}
+
+/// Layers around a manifest: `above` take precedence over it (command line,
+/// environment), `below` are fallbacks (workspace root, user config). Both
+/// are ordered from highest to lowest precedence.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    pub above: Vec<ConfigLayer>,
+    pub below: Vec<ConfigLayer>,
+}
+
+/// `~/.config/skillset/config.toml`
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("skillset").join("config.toml"))
+}
+
+/// Load the user config as a fallback layer, if there is one
+pub fn user_layer() -> Result<Option<ConfigLayer>> {
+    let Some(path) = user_config_path() else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(ConfigLayer {
+        settings: load_settings(&path)?,
+        origin: ConfigOrigin::User(path),
+    }))
+}
+
+pub fn load_settings(path: &Path) -> Result<Settings> {
+    let content = std::fs::read_to_string(path)?;
+    ManifestFormat::from_path(path).parse(&content)
+}
+
+pub fn save_settings(path: &Path, settings: &Settings) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = ManifestFormat::from_path(path).render(settings)?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// One layer per `SKILLSET_*` variable that is set
+pub fn env_layers() -> Vec<ConfigLayer> {
+    let mut layers = Vec::new();
+
+    if let Ok(registry) = std::env::var("SKILLSET_REGISTRY") {
+        layers.push(ConfigLayer {
+            origin: ConfigOrigin::Env("SKILLSET_REGISTRY".to_string()),
+            settings: Settings {
+                registry: Some(registry),
+                ..Default::default()
+            },
+        });
+    }
+    if let Ok(conventions) = std::env::var("SKILLSET_CONVENTIONS") {
+        layers.push(ConfigLayer {
+            origin: ConfigOrigin::Env("SKILLSET_CONVENTIONS".to_string()),
+            settings: Settings {
+                conventions: Some(parse_list(&conventions)),
+                ..Default::default()
+            },
+        });
+    }
+    if let Ok(targets) = std::env::var("SKILLSET_TARGETS") {
+        layers.push(ConfigLayer {
+            origin: ConfigOrigin::Env("SKILLSET_TARGETS".to_string()),
+            settings: Settings {
+                targets: Some(parse_list(&targets)),
+                ..Default::default()
+            },
+        });
+    }
+    if let Ok(integrity) = std::env::var("SKILLSET_INTEGRITY") {
+        match integrity.parse() {
+            Ok(integrity) => layers.push(ConfigLayer {
+                origin: ConfigOrigin::Env("SKILLSET_INTEGRITY".to_string()),
+                settings: Settings {
+                    integrity: Some(integrity),
+                    ..Default::default()
+                },
+            }),
+            Err(e) => tracing::warn!("Ignoring SKILLSET_INTEGRITY: {}", e),
+        }
+    }
+
+    layers
+}
+
+/// Parse a comma-separated list, ignoring empty entries
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect()
+}
+
+impl Settings {
+    /// Read a single setting as a display string
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        match key {
+            "registry" => Ok(self.registry.clone()),
+            "conventions" => Ok(self.conventions.as_ref().map(|c| c.join(","))),
+            "targets" => Ok(self.targets.as_ref().map(|t| t.join(","))),
+            "integrity" => Ok(self.integrity.map(|i| i.to_string())),
+            _ => Err(unknown_key(key)),
+        }
+    }
+
+    /// Set a single setting from its string form
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "registry" => self.registry = Some(value.to_string()),
+            "conventions" => self.conventions = Some(parse_list(value)),
+            "targets" => self.targets = Some(parse_list(value)),
+            "integrity" => self.integrity = Some(value.parse()?),
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+fn unknown_key(key: &str) -> SkillsetError {
+    SkillsetError::Config(format!(
+        "Unknown setting: {}. Available: {}",
+        key,
+        SETTING_KEYS.join(", ")
+    ))
+}
+
+impl SkillsetConfig {
+    pub fn settings(&self) -> Settings {
+        Settings {
+            registry: self.registry.clone(),
+            conventions: self.conventions.clone(),
+            targets: self.targets.clone(),
+            integrity: self.integrity,
+            scopes: self.scopes.clone(),
+            registries: self.registries.clone(),
+        }
+    }
+
+    /// Set a setting in this manifest from its string form
+    pub fn set_setting(&mut self, key: &str, value: &str) -> Result<()> {
+        let mut settings = self.settings();
+        settings.set(key, value)?;
+        self.registry = settings.registry;
+        self.conventions = settings.conventions;
+        self.targets = settings.targets;
+        self.integrity = settings.integrity;
+        Ok(())
+    }
+
+    /// Effective value of a setting and where it came from. `own_origin` is
+    /// reported when the value comes from this manifest itself.
+    pub fn resolve_setting(
+        &self,
+        key: &str,
+        own_origin: &ConfigOrigin,
+    ) -> Result<(String, ConfigOrigin)> {
+        for layer in &self.layers.above {
+            if let Some(value) = layer.settings.get(key)? {
+                return Ok((value, layer.origin.clone()));
+            }
+        }
+        if let Some(value) = self.settings().get(key)? {
+            return Ok((value, own_origin.clone()));
+        }
+        for layer in &self.layers.below {
+            if let Some(value) = layer.settings.get(key)? {
+                return Ok((value, layer.origin.clone()));
+            }
+        }
+
+        let default = Settings {
+            registry: Some(DEFAULT_REGISTRY.to_string()),
+            conventions: Some(default_conventions()),
+            targets: Some(Vec::new()),
+            integrity: Some(IntegrityPolicy::default()),
+            ..Default::default()
+        };
+        let value = default.get(key)?.unwrap_or_default();
+        Ok((value, ConfigOrigin::Default))
+    }
+}
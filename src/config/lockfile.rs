@@ -0,0 +1,113 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LOCKFILE_NAME: &str = "skillset.lock";
+
+const LOCKFILE_VERSION: u32 = 1;
+
+/// Records exactly what was installed for each skill so that the project can
+/// be verified against the manifest and the filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    pub skills: BTreeMap<String, LockedSkill>,
+    /// Skills of workspace members, keyed by member path relative to the root
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub members: BTreeMap<String, BTreeMap<String, LockedSkill>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    pub version: String,
+    /// Source reference the skill was fetched from (e.g. `git:https://...`)
+    pub source: String,
+    pub convention: String,
+    /// Install location relative to the project root
+    pub path: PathBuf,
+    /// Checksum of the installed files, see `skill::checksum::hash_dir`
+    pub checksum: String,
+    /// Instruction files with a managed section for the skill, relative to
+    /// the project root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<PathBuf>,
+    /// Further conventions the skill is installed for when it has several
+    /// targets, the first one being recorded above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<LockedTarget>,
+}
+
+/// One more place a skill is installed, see `LockedSkill::targets`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedTarget {
+    pub convention: String,
+    pub path: PathBuf,
+    pub checksum: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<PathBuf>,
+}
+
+impl LockedSkill {
+    /// Every place the skill is installed, the first target first
+    pub fn installations(&self) -> Vec<LockedTarget> {
+        let first = LockedTarget {
+            convention: self.convention.clone(),
+            path: self.path.clone(),
+            checksum: self.checksum.clone(),
+            sections: self.sections.clone(),
+        };
+        std::iter::once(first)
+            .chain(self.targets.iter().cloned())
+            .collect()
+    }
+
+    /// Conventions the skill is installed for
+    pub fn conventions(&self) -> Vec<String> {
+        std::iter::once(&self.convention)
+            .chain(self.targets.iter().map(|target| &target.convention))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            skills: BTreeMap::new(),
+            members: BTreeMap::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Load the lockfile, returning an empty one if it does not exist yet
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let lockfile: Lockfile = serde_json::from_str(&content)?;
+        if lockfile.version > LOCKFILE_VERSION {
+            return Err(crate::error::SkillsetError::Config(format!(
+                "Unsupported lockfile version {} in {}",
+                lockfile.version,
+                path.display()
+            )));
+        }
+        Ok(lockfile)
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.render()?)?;
+        Ok(())
+    }
+
+    pub fn render(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| crate::error::SkillsetError::SerializationError(e.to_string()))
+    }
+}
@@ -1,4 +1,9 @@
+pub mod format;
+pub mod layered;
+pub mod lockfile;
 pub mod skillset;
+pub mod validate;
+pub mod workspace;
 
 #[cfg(test)]
 mod tests;
@@ -1,29 +1,159 @@
+use crate::config::format::{find_manifest, ManifestFormat};
+use crate::config::layered::{
+    default_conventions, ConfigLayers, IntegrityPolicy, DEFAULT_REGISTRY,
+};
 use crate::error::Result;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use schemars::JsonSchema;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct SkillsetConfig {
+    /// JSON Schema reference for editors, see `skillset schema`
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
     pub skills: HashMap<String, SkillConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub registry: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conventions: Option<Vec<String>>,
+    /// Conventions every skill is installed for unless it sets its own
+    /// `convention` or `targets`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets: Option<Vec<String>>,
+    /// Whether archive sources must declare an `integrity` hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<IntegrityPolicy>,
+    /// Registry (with namespace) per scope, e.g. `"@acme": "registry.acme.internal/skills"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scopes: BTreeMap<String, String>,
+    /// Connection settings per registry host
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, RegistrySettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WorkspaceConfig>,
+    /// Overrides and fallbacks from other config sources, never written back
+    #[serde(skip)]
+    pub layers: ConfigLayers,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Member directories relative to the workspace root, `dir/*` matches
+    /// every subdirectory of `dir`
+    pub members: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct RegistrySettings {
+    /// Accept invalid TLS certificates
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    /// Talk plain HTTP instead of HTTPS
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http: bool,
+    /// Where credentials come from, e.g. `env:ACME_TOKEN`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+    /// Mirrors (`host[/prefix]`) tried in order before the registry itself
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+}
+
+impl RegistrySettings {
+    /// Base URL of the registry API host
+    pub fn base_url(&self, host: &str) -> String {
+        let scheme = if self.http { "http" } else { "https" };
+        format!("{}://{}", scheme, host)
+    }
+}
+
+/// Host part of a registry such as `ghcr.io/skillset`
+pub fn registry_host(registry: &str) -> &str {
+    registry.split('/').next().unwrap_or(registry)
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(untagged)]
+#[schemars(deny_unknown_fields)]
 pub enum SkillConfig {
     Simple(String), // Just version
     Detailed {
         version: String,
         source: Option<String>,     // Override auto-resolution
         convention: Option<String>, // Override auto-detection
+        /// Conventions to install the skill for, each into its own location
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        targets: Option<Vec<String>>,
+        /// Hash archive sources are verified against, `sha256-<base64>`
+        /// (also sha384, sha512) or `sha256:<hex>`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        integrity: Option<String>,
     },
 }
 
+/// Map form of `SkillConfig::Detailed`. Deserializing through an untagged
+/// enum only reports that no variant matched, so maps are parsed into this
+/// instead and errors name the field at fault.
+#[derive(Deserialize)]
+struct DetailedSkill {
+    version: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    convention: Option<String>,
+    #[serde(default)]
+    targets: Option<Vec<String>>,
+    #[serde(default)]
+    integrity: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for SkillConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct SkillConfigVisitor;
+
+        impl<'de> Visitor<'de> for SkillConfigVisitor {
+            type Value = SkillConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(
+                    "a version string or a map with `version`, `source`, `convention`, `targets` \
+                     and `integrity`",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<SkillConfig, E> {
+                Ok(SkillConfig::Simple(value.to_string()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<SkillConfig, A::Error> {
+                let detailed =
+                    DetailedSkill::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(SkillConfig::Detailed {
+                    version: detailed.version,
+                    source: detailed.source,
+                    convention: detailed.convention,
+                    targets: detailed.targets,
+                    integrity: detailed.integrity,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(SkillConfigVisitor)
+    }
+}
+
 impl SkillConfig {
     pub fn get_version(&self) -> &str {
         match self {
@@ -60,33 +190,196 @@ impl SkillConfig {
             _ => None,
         }
     }
-}
 
-impl Default for SkillsetConfig {
-    fn default() -> Self {
-        Self {
-            skills: HashMap::new(),
-            registry: None,
-            conventions: None,
+    pub fn get_explicit_targets(&self) -> Option<&Vec<String>> {
+        match self {
+            Self::Detailed {
+                targets: Some(t), ..
+            } => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn get_integrity(&self) -> Option<&String> {
+        match self {
+            Self::Detailed {
+                integrity: Some(i), ..
+            } => Some(i),
+            _ => None,
         }
     }
 }
 
 impl SkillsetConfig {
-    /// Get the registry URL with runtime default fallback
+    /// Get the registry URL, honoring config layers, with runtime default fallback
     pub fn get_registry(&self) -> &str {
-        self.registry.as_deref().unwrap_or("ghcr.io/skillset")
+        let above = &self.layers.above;
+        let below = &self.layers.below;
+        above
+            .iter()
+            .find_map(|layer| layer.settings.registry.as_deref())
+            .or(self.registry.as_deref())
+            .or_else(|| {
+                below
+                    .iter()
+                    .find_map(|layer| layer.settings.registry.as_deref())
+            })
+            .unwrap_or(DEFAULT_REGISTRY)
     }
 
-    /// Get the list of enabled conventions with runtime default fallback
+    /// Get the list of enabled conventions, honoring config layers, with
+    /// runtime default fallback
     pub fn get_conventions(&self) -> Vec<String> {
-        self.conventions.clone().unwrap_or_else(|| {
-            vec![
-                "autogpt".to_string(),
-                "langchain".to_string(),
-                "agent-skills".to_string(),
-            ]
-        })
+        let above = &self.layers.above;
+        let below = &self.layers.below;
+        above
+            .iter()
+            .find_map(|layer| layer.settings.conventions.as_ref())
+            .or(self.conventions.as_ref())
+            .or_else(|| {
+                below
+                    .iter()
+                    .find_map(|layer| layer.settings.conventions.as_ref())
+            })
+            .cloned()
+            .unwrap_or_else(default_conventions)
+    }
+
+    /// The conventions this manifest enables itself, ignoring every layer.
+    /// What `convention enable/disable` edit, so settings from the
+    /// environment or the user config are not written into the project.
+    pub fn project_conventions(&self) -> Vec<String> {
+        self.conventions.clone().unwrap_or_else(default_conventions)
+    }
+
+    /// Conventions skills are installed for by default, honoring config
+    /// layers. Empty when each skill gets the one convention detected for it.
+    pub fn get_targets(&self) -> Vec<String> {
+        let above = &self.layers.above;
+        let below = &self.layers.below;
+        above
+            .iter()
+            .find_map(|layer| layer.settings.targets.as_ref())
+            .or(self.targets.as_ref())
+            .or_else(|| {
+                below
+                    .iter()
+                    .find_map(|layer| layer.settings.targets.as_ref())
+            })
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Conventions a skill is installed for: its own `targets`, its
+    /// `convention`, or the project's `targets`, in that order. Empty when
+    /// the convention is left to detection.
+    pub fn skill_targets(&self, skill: &SkillConfig) -> Vec<String> {
+        if let Some(targets) = skill.get_explicit_targets() {
+            return targets.clone();
+        }
+        match skill.get_explicit_convention() {
+            Some(convention) => vec![convention.clone()],
+            None => self.get_targets(),
+        }
+    }
+
+    /// Whether archive sources must declare an integrity hash, honoring
+    /// config layers
+    pub fn get_integrity_policy(&self) -> IntegrityPolicy {
+        let above = &self.layers.above;
+        let below = &self.layers.below;
+        above
+            .iter()
+            .find_map(|layer| layer.settings.integrity)
+            .or(self.integrity)
+            .or_else(|| below.iter().find_map(|layer| layer.settings.integrity))
+            .unwrap_or_default()
+    }
+
+    /// Registry a scope such as `@acme` maps to, honoring config layers.
+    /// Scopes may be written with or without the leading `@`.
+    pub fn scope_registry(&self, scope: &str) -> Option<&str> {
+        fn lookup<'a>(scopes: &'a BTreeMap<String, String>, bare: &str) -> Option<&'a str> {
+            scopes
+                .iter()
+                .find(|(key, _)| key.trim_start_matches('@') == bare)
+                .map(|(_, registry)| registry.as_str())
+        }
+
+        let bare = scope.trim_start_matches('@');
+        self.layers
+            .above
+            .iter()
+            .find_map(|layer| lookup(&layer.settings.scopes, bare))
+            .or_else(|| lookup(&self.scopes, bare))
+            .or_else(|| {
+                self.layers
+                    .below
+                    .iter()
+                    .find_map(|layer| lookup(&layer.settings.scopes, bare))
+            })
+    }
+
+    /// Connection settings for a registry host, honoring config layers.
+    /// Unconfigured hosts get the defaults (HTTPS, verified TLS, no auth).
+    pub fn registry_settings(&self, host: &str) -> RegistrySettings {
+        self.layers
+            .above
+            .iter()
+            .find_map(|layer| layer.settings.registries.get(host))
+            .or_else(|| self.registries.get(host))
+            .or_else(|| {
+                self.layers
+                    .below
+                    .iter()
+                    .find_map(|layer| layer.settings.registries.get(host))
+            })
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Connection settings of every configured host, higher precedence
+    /// layers replacing a host's settings from lower ones
+    pub fn merged_registries(&self) -> BTreeMap<String, RegistrySettings> {
+        let mut merged = BTreeMap::new();
+        let lower_first = self
+            .layers
+            .below
+            .iter()
+            .rev()
+            .map(|layer| &layer.settings.registries)
+            .chain(std::iter::once(&self.registries))
+            .chain(
+                self.layers
+                    .above
+                    .iter()
+                    .rev()
+                    .map(|layer| &layer.settings.registries),
+            );
+        for registries in lower_first {
+            merged.extend(registries.clone());
+        }
+        merged
+    }
+
+    /// Every registry skill names can resolve to: the default one followed
+    /// by each scope's
+    pub fn all_registries(&self) -> Vec<String> {
+        let mut registries = vec![self.get_registry().to_string()];
+        let layer_scopes = self
+            .layers
+            .above
+            .iter()
+            .chain(&self.layers.below)
+            .flat_map(|layer| layer.settings.scopes.keys());
+        for scope in self.scopes.keys().chain(layer_scopes) {
+            if let Some(registry) = self.scope_registry(scope) {
+                if !registries.iter().any(|r| r == registry) {
+                    registries.push(registry.to_string());
+                }
+            }
+        }
+        registries
     }
 
     pub fn resolve_skill_reference(
@@ -94,9 +387,15 @@ impl SkillsetConfig {
         skill_name: &str,
         skill_config: &SkillConfig,
     ) -> Result<String> {
-        // If explicit source is provided, use it
+        // If explicit source is provided, use it. Archives carry their
+        // integrity hash as the URL fragment.
         if let Some(source) = skill_config.get_explicit_source() {
-            return Ok(source.clone());
+            return Ok(match skill_config.get_integrity() {
+                Some(integrity) if crate::sources::archive::is_archive_reference(source) => {
+                    format!("{}#{}", source, integrity)
+                }
+                _ => source.clone(),
+            });
         }
 
         // Otherwise resolve from name and version
@@ -119,9 +418,8 @@ impl SkillsetConfig {
             format!("v{}", version)
         };
 
-        if skill_name.starts_with('@') {
+        if let Some(scoped_part) = skill_name.strip_prefix('@') {
             // Handle scoped names: @user/skill
-            let scoped_part = &skill_name[1..]; // Remove '@'
             let parts: Vec<&str> = scoped_part.splitn(2, '/').collect();
             if parts.len() != 2 {
                 return Err(crate::error::SkillsetError::InvalidSkillName(format!(
@@ -130,6 +428,17 @@ impl SkillsetConfig {
                 )));
             }
             let (user, name) = (parts[0], parts[1]);
+
+            // A scope mapped to its own registry replaces both domain and namespace
+            if let Some(registry) = self.scope_registry(user) {
+                return Ok(format!(
+                    "oci:{}/{}:{}",
+                    registry.trim_end_matches('/'),
+                    name,
+                    prefixed_version
+                ));
+            }
+
             Ok(format!(
                 "oci:{}/{}/{}:{}",
                 domain, user, name, prefixed_version
@@ -143,32 +452,30 @@ impl SkillsetConfig {
         }
     }
 
+    /// Load a manifest, picking JSON, TOML or YAML from the file extension
     pub fn load_from_file(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path)?;
-        let config: SkillsetConfig = serde_json::from_str(&content)
-            .map_err(|e| crate::error::SkillsetError::JsonSerialization(e))?;
-        Ok(config)
+        crate::config::validate::parse_manifest(path, &content)
     }
 
+    /// Save in the format matching the file extension
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| crate::error::SkillsetError::SerializationError(e.to_string()))?;
-        fs::write(path, content)?;
-        Ok(())
+        let content = ManifestFormat::from_path(path).render(self)?;
+        crate::skill::transaction::write_atomically(path, content.as_bytes())
     }
 
-    /// Try to load from JSON, fall back to TOML for backward compatibility
+    /// Load `path`, or the manifest next to it in another supported format
+    /// (e.g. `skillset.toml` when asked for `skillset.json`). Returns the
+    /// default configuration when there is none.
     pub fn load_from_file_flexible(path: &Path) -> Result<Self> {
         if path.exists() {
-            // Try JSON first
-            match Self::load_from_file(path) {
-                Ok(config) => Ok(config),
-                Err(_) => Err(crate::error::SkillsetError::Config(
-                    "Configuration file not found: skillset.json".to_string(),
-                )),
-            }
-        } else {
-            Ok(Self::default())
+            return Self::load_from_file(path);
+        }
+
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        match find_manifest(dir)? {
+            Some(manifest) => Self::load_from_file(&manifest),
+            None => Ok(Self::default()),
         }
     }
 }
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::config::skillset::{SkillConfig, SkillsetConfig};
     use std::collections::HashMap;
@@ -26,6 +27,7 @@ mod tests {
             skills: HashMap::new(),
             registry: Some("my-registry.example.com/custom".to_string()),
             conventions: None,
+            ..Default::default()
         };
 
         assert_eq!(config.get_registry(), "my-registry.example.com/custom");
@@ -41,6 +43,7 @@ mod tests {
             skills: HashMap::new(),
             registry: None,
             conventions: Some(vec!["autogpt".to_string()]), // Only autogpt
+            ..Default::default()
         };
 
         assert_eq!(config.get_registry(), "ghcr.io/skillset"); // Still uses default
@@ -53,17 +56,51 @@ mod tests {
             skills: HashMap::new(),
             registry: None,
             conventions: Some(vec![]), // Empty list
+            ..Default::default()
         };
 
         assert_eq!(config.get_conventions(), Vec::<String>::new());
     }
 
+    #[test]
+    fn test_skill_targets_fall_back_to_the_project() {
+        let config = SkillsetConfig {
+            targets: Some(vec!["claude-code".to_string(), "cursor".to_string()]),
+            ..Default::default()
+        };
+        let detailed =
+            |convention: Option<&str>, targets: Option<Vec<&str>>| SkillConfig::Detailed {
+                version: "1.0.0".to_string(),
+                source: None,
+                convention: convention.map(str::to_string),
+                targets: targets.map(|targets| targets.into_iter().map(str::to_string).collect()),
+                integrity: None,
+            };
+
+        assert_eq!(
+            config.skill_targets(&SkillConfig::Simple("1.0.0".to_string())),
+            vec!["claude-code", "cursor"]
+        );
+        assert_eq!(
+            config.skill_targets(&detailed(Some("autogpt"), None)),
+            vec!["autogpt"]
+        );
+        assert_eq!(
+            config.skill_targets(&detailed(Some("autogpt"), Some(vec!["agents-md"]))),
+            vec!["agents-md"]
+        );
+        assert!(SkillsetConfig::default()
+            .skill_targets(&SkillConfig::Simple("1.0.0".to_string()))
+            .is_empty());
+    }
+
     #[test]
     fn test_minimal_config_serialization() {
         let config = SkillsetConfig {
             skills: HashMap::new(),
             registry: None,
             conventions: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string_pretty(&config).unwrap();
@@ -86,6 +123,7 @@ mod tests {
             skills,
             registry: Some("custom-registry.com".to_string()),
             conventions: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string_pretty(&config).unwrap();
@@ -130,6 +168,7 @@ mod tests {
             skills: HashMap::new(),
             registry: None, // Use default
             conventions: None,
+            ..Default::default()
         };
 
         let skill_config = SkillConfig::Simple("1.0.0".to_string());
@@ -147,6 +186,7 @@ mod tests {
             skills: HashMap::new(),
             registry: Some("my-registry.com/ns".to_string()),
             conventions: None,
+            ..Default::default()
         };
 
         let skill_config = SkillConfig::Simple("1.0.0".to_string());
@@ -173,4 +213,292 @@ mod tests {
         // Should extract domain from default registry and user from scope
         assert_eq!(resolved, "oci:ghcr.io/johndoe/web-scraper:v2.1.0");
     }
+
+    #[test]
+    fn test_lockfile_round_trip() {
+        use crate::config::lockfile::{LockedSkill, Lockfile};
+
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join("skillset.lock");
+
+        // A missing lockfile loads as empty
+        let mut lockfile = Lockfile::load_from_file(&lock_path).unwrap();
+        assert!(lockfile.skills.is_empty());
+
+        lockfile.skills.insert(
+            "react-best-practices".to_string(),
+            LockedSkill {
+                version: "1.0.0".to_string(),
+                source: "git:https://github.com/vercel-labs/agent-skills".to_string(),
+                convention: "agent-skills".to_string(),
+                path: "skills/agent-skills/react-best-practices".into(),
+                checksum: "sha256:abcd".to_string(),
+                sections: Vec::new(),
+                targets: Vec::new(),
+            },
+        );
+        lockfile.save_to_file(&lock_path).unwrap();
+
+        let loaded = Lockfile::load_from_file(&lock_path).unwrap();
+        assert_eq!(loaded.version, 1);
+        assert_eq!(
+            loaded.skills.get("react-best-practices"),
+            lockfile.skills.get("react-best-practices")
+        );
+    }
+
+    #[test]
+    fn test_workspace_discovery_and_inheritance() {
+        use crate::config::workspace::Workspace;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(
+            root.join("skillset.json"),
+            r#"{
+  "skills": {},
+  "registry": "registry.example.com/team",
+  "conventions": ["agent-skills"],
+  "workspace": { "members": ["tools", "packages/*"] }
+}"#,
+        )
+        .unwrap();
+        for member in ["tools", "packages/web", "packages/api"] {
+            std::fs::create_dir_all(root.join(member)).unwrap();
+            std::fs::write(root.join(member).join("skillset.json"), r#"{"skills": {}}"#).unwrap();
+        }
+        // Directories without a manifest are not members
+        std::fs::create_dir_all(root.join("packages/docs")).unwrap();
+
+        let workspace = Workspace::discover(&root.join("packages/web"))
+            .unwrap()
+            .expect("member should find its workspace");
+        assert_eq!(workspace.root, root);
+        assert_eq!(
+            workspace.members,
+            vec![
+                root.join("tools"),
+                root.join("packages/api"),
+                root.join("packages/web")
+            ]
+        );
+        assert_eq!(
+            workspace.member_name(&root.join("packages/web")),
+            Some("packages/web".to_string())
+        );
+        assert!(Workspace::discover(&root.join("packages/docs"))
+            .unwrap()
+            .is_none());
+        // However the member's path is spelled
+        for spelling in ["./packages/web/", "packages/api/../web"] {
+            assert_eq!(
+                workspace.member_name(&root.join(spelling)),
+                Some("packages/web".to_string())
+            );
+        }
+
+        // Unset settings fall back to the root, explicit ones win
+        let mut member = SkillsetConfig {
+            conventions: Some(vec!["autogpt".to_string()]),
+            ..Default::default()
+        };
+        member
+            .layers
+            .below
+            .push(workspace.inherited_layer().unwrap());
+        assert_eq!(member.get_registry(), "registry.example.com/team");
+        assert_eq!(member.get_conventions(), vec!["autogpt"]);
+
+        // Inherited settings are never written into the member manifest
+        let json = serde_json::to_string_pretty(&member).unwrap();
+        assert!(!json.contains("registry.example.com"));
+    }
+
+    #[test]
+    fn test_discovery_skips_manifests_that_do_not_parse() {
+        use crate::config::workspace::Workspace;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("skillset.json"), "{ not json").unwrap();
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::write(root.join("app/skillset.json"), r#"{"skills": {}}"#).unwrap();
+
+        assert!(Workspace::discover(&root.join("app")).unwrap().is_none());
+    }
+
+    fn sample_config() -> SkillsetConfig {
+        let mut skills = HashMap::new();
+        skills.insert(
+            "react-best-practices".to_string(),
+            SkillConfig::Simple("1.0.0".to_string()),
+        );
+        skills.insert(
+            "@user/web-scraper".to_string(),
+            SkillConfig::Detailed {
+                version: "2.1.0".to_string(),
+                source: Some("git:https://github.com/user/web-scraper".to_string()),
+                convention: None,
+                targets: None,
+                integrity: None,
+            },
+        );
+
+        SkillsetConfig {
+            skills,
+            registry: Some("custom-registry.com".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_toml_and_yaml_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for name in ["skillset.toml", "skillset.yaml", "skillset.yml"] {
+            let path = temp_dir.path().join(name);
+            sample_config().save_to_file(&path).unwrap();
+
+            let loaded = SkillsetConfig::load_from_file(&path).unwrap();
+            assert_eq!(loaded.get_registry(), "custom-registry.com");
+            assert_eq!(loaded.skills["react-best-practices"].get_version(), "1.0.0");
+            assert_eq!(
+                loaded.skills["@user/web-scraper"].get_explicit_source(),
+                Some(&"git:https://github.com/user/web-scraper".to_string())
+            );
+        }
+
+        // Files are written in the format their extension asks for
+        let toml = std::fs::read_to_string(temp_dir.path().join("skillset.toml")).unwrap();
+        assert!(toml.contains("registry = \"custom-registry.com\""));
+        let yaml = std::fs::read_to_string(temp_dir.path().join("skillset.yaml")).unwrap();
+        assert!(yaml.contains("registry: custom-registry.com"));
+    }
+
+    #[test]
+    fn test_flexible_load_finds_other_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("skillset.json");
+
+        // Nothing on disk yet
+        let config = SkillsetConfig::load_from_file_flexible(&json_path).unwrap();
+        assert!(config.skills.is_empty());
+
+        std::fs::write(
+            temp_dir.path().join("skillset.toml"),
+            "registry = \"toml-registry.com\"\n\n[skills]\nreact-best-practices = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let config = SkillsetConfig::load_from_file_flexible(&json_path).unwrap();
+        assert_eq!(config.get_registry(), "toml-registry.com");
+
+        // Parse errors are reported instead of being hidden
+        std::fs::write(&json_path, "{ not json").unwrap();
+        assert!(SkillsetConfig::load_from_file_flexible(&json_path).is_err());
+
+        // With both files present it's ambiguous which one to use
+        assert!(crate::config::format::find_manifest(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_layer_precedence_and_origins() {
+        use crate::config::layered::{ConfigLayer, ConfigOrigin, Settings};
+        use std::path::PathBuf;
+
+        let project = ConfigOrigin::Project(PathBuf::from("skillset.json"));
+        let mut config = SkillsetConfig {
+            conventions: Some(vec!["langchain".to_string()]),
+            ..Default::default()
+        };
+        config.layers.below.push(ConfigLayer {
+            origin: ConfigOrigin::User(PathBuf::from("config.toml")),
+            settings: Settings {
+                registry: Some("user.example.com".to_string()),
+                conventions: Some(vec!["autogpt".to_string()]),
+                ..Default::default()
+            },
+        });
+
+        // The manifest wins over the user config, the user config over defaults
+        assert_eq!(config.get_registry(), "user.example.com");
+        assert_eq!(config.get_conventions(), vec!["langchain"]);
+        let (value, origin) = config.resolve_setting("conventions", &project).unwrap();
+        assert_eq!(value, "langchain");
+        assert_eq!(origin, project);
+
+        config.layers.above.push(ConfigLayer {
+            origin: ConfigOrigin::Cli,
+            settings: Settings {
+                registry: Some("cli.example.com".to_string()),
+                ..Default::default()
+            },
+        });
+        let (value, origin) = config.resolve_setting("registry", &project).unwrap();
+        assert_eq!(value, "cli.example.com");
+        assert_eq!(origin, ConfigOrigin::Cli);
+
+        assert!(config.resolve_setting("nope", &project).is_err());
+
+        // Editing the manifest's conventions starts from the manifest alone
+        config.layers.above.push(ConfigLayer {
+            origin: ConfigOrigin::Env("SKILLSET_CONVENTIONS".to_string()),
+            settings: Settings {
+                conventions: Some(vec!["cursor".to_string()]),
+                ..Default::default()
+            },
+        });
+        assert_eq!(config.get_conventions(), vec!["cursor"]);
+        assert_eq!(config.project_conventions(), vec!["langchain"]);
+        assert_eq!(
+            SkillsetConfig::default()
+                .resolve_setting("registry", &project)
+                .unwrap()
+                .1,
+            ConfigOrigin::Default
+        );
+    }
+
+    #[test]
+    fn test_scoped_skills_resolve_to_their_registry() {
+        use crate::config::skillset::RegistrySettings;
+
+        let config: SkillsetConfig = serde_json::from_str(
+            r#"{
+                "registry": "ghcr.io/skillset",
+                "scopes": { "@acme": "registry.acme.internal/skills/" },
+                "registries": { "registry.acme.internal": { "http": true, "auth": "env:ACME_TOKEN" } }
+            }"#,
+        )
+        .unwrap();
+        let skill_config = SkillConfig::Simple("1.0.0".to_string());
+
+        assert_eq!(
+            config
+                .resolve_skill_reference("@acme/linter", &skill_config)
+                .unwrap(),
+            "oci:registry.acme.internal/skills/linter:v1.0.0"
+        );
+        // Unmapped scopes keep using the default registry
+        assert_eq!(
+            config
+                .resolve_skill_reference("@johndoe/linter", &skill_config)
+                .unwrap(),
+            "oci:ghcr.io/johndoe/linter:v1.0.0"
+        );
+
+        let settings = config.registry_settings("registry.acme.internal");
+        assert_eq!(
+            settings.base_url("registry.acme.internal"),
+            "http://registry.acme.internal"
+        );
+        assert_eq!(settings.auth.as_deref(), Some("env:ACME_TOKEN"));
+        assert_eq!(
+            config.registry_settings("ghcr.io"),
+            RegistrySettings::default()
+        );
+        assert_eq!(
+            config.all_registries(),
+            vec!["ghcr.io/skillset", "registry.acme.internal/skills/"]
+        );
+    }
 }
@@ -0,0 +1,420 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::format::ManifestFormat;
+use crate::config::skillset::{SkillConfig, SkillsetConfig};
+use crate::conventions::builtin_conventions;
+use crate::error::{Result, SkillsetError};
+
+/// A problem found in a manifest, pointing at where it is when known
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean `{}`?)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON Schema describing `skillset.json`, for `skillset schema`
+pub fn schema() -> serde_json::Value {
+    let schema = schemars::schema_for!(SkillsetConfig);
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+/// Parse a manifest, every error carrying the line and column it was found
+/// at. Unknown keys are left for `unknown_fields` to report, so manifests
+/// written for newer versions still load.
+pub fn parse_manifest(path: &Path, content: &str) -> Result<SkillsetConfig> {
+    let diagnostic = |line, column, message: String| {
+        let suggestion = suggest_field(&message);
+        SkillsetError::InvalidManifest(Diagnostic {
+            file: path.to_path_buf(),
+            line,
+            column,
+            message,
+            suggestion,
+        })
+    };
+
+    match ManifestFormat::from_path(path) {
+        ManifestFormat::Json => serde_json::from_str(content).map_err(|e| {
+            let suffix = format!(" at line {} column {}", e.line(), e.column());
+            let message = e.to_string();
+            let message = message
+                .strip_suffix(&suffix)
+                .unwrap_or(&message)
+                .to_string();
+            diagnostic(Some(e.line()), Some(e.column()), message)
+        }),
+        ManifestFormat::Toml => toml::from_str(content).map_err(|e| {
+            let (line, column) = match e.span() {
+                Some(span) => {
+                    let (line, column) = line_column(content, span.start);
+                    (Some(line), Some(column))
+                }
+                None => (None, None),
+            };
+            diagnostic(line, column, e.message().to_string())
+        }),
+        ManifestFormat::Yaml => serde_yaml::from_str(content).map_err(|e| {
+            let message = e.to_string();
+            match e.location() {
+                Some(location) => {
+                    let suffix =
+                        format!(" at line {} column {}", location.line(), location.column());
+                    let message = message.strip_suffix(&suffix).unwrap_or(&message);
+                    diagnostic(
+                        Some(location.line()),
+                        Some(location.column()),
+                        message.to_string(),
+                    )
+                }
+                None => diagnostic(None, None, message),
+            }
+        }),
+    }
+}
+
+/// Check a manifest beyond what loading it needs: keys must be known,
+/// conventions must exist, versions must not be empty and integrity hashes
+/// must parse. Returns every problem found.
+pub fn validate_manifest(path: &Path) -> Result<Vec<Diagnostic>> {
+    let content = std::fs::read_to_string(path)?;
+    let config = match parse_manifest(path, &content) {
+        Ok(config) => config,
+        Err(SkillsetError::InvalidManifest(diagnostic)) => return Ok(vec![diagnostic]),
+        Err(e) => return Err(e),
+    };
+
+    let builtin = builtin_conventions();
+    let known: Vec<&str> = builtin.iter().map(|convention| convention.name()).collect();
+    let mut diagnostics = unknown_fields(path, &content)?;
+    let mut report = |at: &[&str], message: String, suggestion: Option<String>| {
+        diagnostics.push(diagnostic_at(path, &content, at, message, suggestion));
+    };
+
+    for (setting, conventions) in [
+        ("conventions", &config.conventions),
+        ("targets", &config.targets),
+    ] {
+        for convention in conventions.iter().flatten() {
+            if !known.contains(&convention.as_str()) {
+                report(
+                    &[setting, convention],
+                    format!("unknown convention `{}`", convention),
+                    did_you_mean(convention, &known),
+                );
+            }
+        }
+    }
+
+    let mut names: Vec<&String> = config.skills.keys().collect();
+    names.sort();
+    for name in names {
+        let skill = &config.skills[name];
+        if skill.get_version().trim().is_empty() {
+            report(
+                &["skills", name],
+                format!("skill `{}` has an empty version", name),
+                None,
+            );
+        }
+        if let Some(integrity) = skill.get_integrity() {
+            if crate::sources::archive::Integrity::parse(integrity).is_err() {
+                report(
+                    &["skills", name, "integrity"],
+                    format!("skill `{}` has an invalid integrity hash", name),
+                    None,
+                );
+            }
+        }
+        if let SkillConfig::Detailed {
+            convention: Some(convention),
+            ..
+        } = skill
+        {
+            if !known.contains(&convention.as_str()) {
+                report(
+                    &["skills", name, "convention", convention],
+                    format!("skill `{}` uses unknown convention `{}`", name, convention),
+                    did_you_mean(convention, &known),
+                );
+            }
+        }
+        for target in skill.get_explicit_targets().into_iter().flatten() {
+            if !known.contains(&target.as_str()) {
+                report(
+                    &["skills", name, "targets", target],
+                    format!("skill `{}` targets unknown convention `{}`", name, target),
+                    did_you_mean(target, &known),
+                );
+            }
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Keys of a manifest this version does not know, most likely typos.
+/// Loading ignores them, so commands report them as warnings.
+pub fn unknown_fields(path: &Path, content: &str) -> Result<Vec<Diagnostic>> {
+    let raw: serde_json::Value = ManifestFormat::from_path(path).parse(content)?;
+    let schema = schema();
+    let mut unknown = Vec::new();
+    unknown_keys(&raw, &schema, &schema, &mut Vec::new(), &mut unknown);
+    Ok(unknown
+        .into_iter()
+        .map(|(at, expected)| {
+            let key = at.last().cloned().unwrap_or_default();
+            let at: Vec<&str> = at.iter().map(String::as_str).collect();
+            let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+            diagnostic_at(
+                path,
+                content,
+                &at,
+                format!("unknown field `{}`", key),
+                did_you_mean(&key, &expected),
+            )
+        })
+        .collect())
+}
+
+/// A diagnostic pointing at the value found by following `at` in `content`
+fn diagnostic_at(
+    path: &Path,
+    content: &str,
+    at: &[&str],
+    message: String,
+    suggestion: Option<String>,
+) -> Diagnostic {
+    let (line, column) = match locate(content, at) {
+        Some(offset) => {
+            let (line, column) = line_column(content, offset);
+            (Some(line), Some(column))
+        }
+        None => (None, None),
+    };
+    Diagnostic {
+        file: path.to_path_buf(),
+        line,
+        column,
+        message,
+        suggestion,
+    }
+}
+
+/// Collect the keys of `value` that `schema` does not allow, with the path
+/// to each and the keys that were expected in its place
+fn unknown_keys(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    root: &serde_json::Value,
+    at: &mut Vec<String>,
+    found: &mut Vec<(Vec<String>, Vec<String>)>,
+) {
+    let schema = resolve(schema, root);
+    let serde_json::Value::Object(map) = value else {
+        return;
+    };
+    // Of `anyOf` alternatives, the one describing maps applies
+    if let Some(alternatives) = schema["anyOf"].as_array() {
+        if let Some(object) = alternatives
+            .iter()
+            .map(|alternative| resolve(alternative, root))
+            .find(|alternative| alternative["type"] == "object")
+        {
+            unknown_keys(value, object, root, at, found);
+        }
+        return;
+    }
+
+    let properties = schema["properties"].as_object();
+    for (key, value) in map {
+        at.push(key.clone());
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property) => unknown_keys(value, property, root, at, found),
+            None if schema["additionalProperties"] == false => {
+                let expected = properties
+                    .map(|properties| properties.keys().cloned().collect())
+                    .unwrap_or_default();
+                found.push((at.clone(), expected));
+            }
+            None if schema["additionalProperties"].is_object() => {
+                unknown_keys(value, &schema["additionalProperties"], root, at, found)
+            }
+            None => {}
+        }
+        at.pop();
+    }
+}
+
+/// The definition `schema` refers to, or `schema` itself
+fn resolve<'a>(
+    schema: &'a serde_json::Value,
+    root: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    match schema["$ref"]
+        .as_str()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+    {
+        Some(name) => &root["definitions"][name],
+        None => schema,
+    }
+}
+
+/// Byte offset of the last of `path`'s keys and values, each looked for
+/// after the one before so a name used earlier in the file is skipped
+fn locate(content: &str, path: &[&str]) -> Option<usize> {
+    let is_name = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '@' | '/' | '$');
+    let mut from = 0;
+    let mut found = None;
+    for token in path {
+        let start = content[from..]
+            .match_indices(token)
+            .find_map(|(offset, _)| {
+                let start = from + offset;
+                let end = start + token.len();
+                let before = content[..start].chars().next_back();
+                let after = content[end..].chars().next();
+                (!before.is_some_and(is_name) && !after.is_some_and(is_name)).then_some(start)
+            })?;
+        from = start + token.len();
+        found = Some(start);
+    }
+    found
+}
+
+/// Closest candidate to a misspelled `input`, if any is close enough
+pub fn did_you_mean(input: &str, candidates: &[&str]) -> Option<String> {
+    let threshold = (input.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (strsim::levenshtein(input, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Suggest a replacement for serde's "unknown field `x`, expected one of
+/// `a`, `b`" errors
+fn suggest_field(message: &str) -> Option<String> {
+    let rest = message
+        .split_once("unknown field ")
+        .or_else(|| message.split_once("unknown variant "))?
+        .1;
+    let mut quoted = rest.split('`').skip(1).step_by(2);
+    let unknown = quoted.next()?;
+    let expected: Vec<&str> = quoted.collect();
+    did_you_mean(unknown, &expected)
+}
+
+/// 1-based line and column of a byte offset
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(file_name: &str, content: &str) -> Vec<Diagnostic> {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(file_name);
+        std::fs::write(&path, content).unwrap();
+        validate_manifest(&path).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_detailed_key_is_located_and_suggested() {
+        let content = r#"{
+  "skills": {
+    "web": { "version": "1.0.0", "convetion": "langchain" }
+  }
+}"#;
+        // Loading leaves unknown keys alone, validating reports them
+        let config = parse_manifest(Path::new("skillset.json"), content).unwrap();
+        assert_eq!(config.skills["web"].get_version(), "1.0.0");
+
+        let diagnostics = validate("skillset.json", content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].column, Some(35));
+        assert!(diagnostics[0].message.contains("convetion"));
+        assert_eq!(diagnostics[0].suggestion.as_deref(), Some("convention"));
+    }
+
+    #[test]
+    fn test_unknown_top_level_key_in_toml() {
+        let content =
+            "registy = \"ghcr.io/acme\"\n\n[registries.\"ghcr.io\"]\nhttp = true\nmirors = []\n";
+        let diagnostics = validate("skillset.toml", content);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, Some(1));
+        assert_eq!(diagnostics[0].suggestion.as_deref(), Some("registry"));
+        assert_eq!(diagnostics[1].line, Some(5));
+        assert_eq!(diagnostics[1].suggestion.as_deref(), Some("mirrors"));
+    }
+
+    #[test]
+    fn test_problems_are_located_past_earlier_mentions() {
+        let content = r#"{
+  "conventions": ["langchian"],
+  "skills": {
+    "langchian": { "version": "1.0.0", "convention": "langchian" }
+  }
+}"#;
+        let diagnostics = validate("skillset.json", content);
+        let lines: Vec<_> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(4)]);
+        assert_eq!(diagnostics[1].column, Some(55));
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_conventions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("skillset.json");
+        std::fs::write(
+            &path,
+            r#"{
+  "skills": { "web": { "version": "1.0.0", "convention": "langchian" } },
+  "conventions": ["autogpt"]
+}"#,
+        )
+        .unwrap();
+
+        let diagnostics = validate_manifest(&path).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].suggestion.as_deref(), Some("langchain"));
+    }
+
+    #[test]
+    fn test_schema_describes_skills() {
+        let schema = schema();
+        assert!(schema["properties"]["skills"].is_object());
+        assert_eq!(schema["additionalProperties"], serde_json::json!(false));
+    }
+}
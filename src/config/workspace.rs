@@ -0,0 +1,164 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::format::{find_manifest, ManifestFormat};
+use crate::config::layered::{ConfigLayer, ConfigOrigin};
+use crate::config::skillset::SkillsetConfig;
+use crate::error::Result;
+
+/// A directory whose manifest declares `workspace.members`, sharing one
+/// lockfile and its registry/conventions settings with every member
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub config: SkillsetConfig,
+    pub members: Vec<PathBuf>,
+}
+
+impl Workspace {
+    pub fn load(root: &Path) -> Result<Self> {
+        let manifest = find_manifest(root)?.ok_or_else(|| {
+            crate::error::SkillsetError::Config(format!(
+                "No skillset manifest found in {}",
+                root.display()
+            ))
+        })?;
+        let config = SkillsetConfig::load_from_file(&manifest)?;
+        let Some(workspace) = &config.workspace else {
+            return Err(crate::error::SkillsetError::Config(format!(
+                "{} does not declare a workspace",
+                manifest.display()
+            )));
+        };
+
+        let mut members = Vec::new();
+        for pattern in &workspace.members {
+            for member in expand_member(root, pattern)? {
+                if find_manifest(&member)?.is_none() {
+                    return Err(crate::error::SkillsetError::Config(format!(
+                        "Workspace member {} has no skillset manifest",
+                        member.display()
+                    )));
+                }
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            }
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            config,
+            members,
+        })
+    }
+
+    /// Find the workspace that `dir` is the root or a member of, looking at
+    /// the nearest ancestor that declares a workspace. Manifests that do not
+    /// parse are not workspace roots; they are reported when loaded.
+    pub fn discover(dir: &Path) -> Result<Option<Self>> {
+        let dir = normalize(dir)?;
+        for ancestor in dir.ancestors() {
+            let Some(manifest) = find_manifest(ancestor)? else {
+                continue;
+            };
+            if !declares_workspace(&manifest) {
+                continue;
+            }
+
+            let workspace = Self::load(ancestor)?;
+            if ancestor == dir || workspace.member_name(&dir).is_some() {
+                return Ok(Some(workspace));
+            }
+            return Ok(None);
+        }
+
+        Ok(None)
+    }
+
+    /// Key used for a member in the root lockfile, its path relative to the
+    /// root. `None` if `dir` is not a member.
+    pub fn member_name(&self, dir: &Path) -> Option<String> {
+        let dir = normalize(dir).ok()?;
+        let is_member = self
+            .members
+            .iter()
+            .any(|member| normalize(member).is_ok_and(|member| member == dir));
+        if !is_member {
+            return None;
+        }
+        dir.strip_prefix(normalize(&self.root).ok()?)
+            .ok()
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Settings members fall back to when their manifest leaves them unset
+    pub fn inherited_layer(&self) -> Result<ConfigLayer> {
+        Ok(ConfigLayer {
+            origin: ConfigOrigin::Workspace(self.manifest_path()?),
+            settings: self.config.settings(),
+        })
+    }
+
+    pub fn manifest_path(&self) -> Result<PathBuf> {
+        crate::config::format::manifest_path(&self.root)
+    }
+}
+
+/// Whether the manifest at `path` has a `workspace` section, without
+/// holding the rest of it to the manifest schema
+fn declares_workspace(path: &Path) -> bool {
+    #[derive(serde::Deserialize)]
+    struct Probe {
+        workspace: Option<serde::de::IgnoredAny>,
+    }
+
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| {
+            ManifestFormat::from_path(path)
+                .parse::<Probe>(&content)
+                .ok()
+        })
+        .is_some_and(|probe| probe.workspace.is_some())
+}
+
+/// `path` made absolute, with `.` and `..` resolved without touching the
+/// filesystem, so differently spelled paths to one member compare equal
+fn normalize(path: &Path) -> Result<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    Ok(normalized)
+}
+
+fn expand_member(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let Some(parent) = pattern.strip_suffix("/*") else {
+        return Ok(vec![root.join(pattern)]);
+    };
+
+    let mut members = Vec::new();
+    let dir = root.join(parent);
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            // Only directories that actually hold a manifest count as members
+            if entry.file_type()?.is_dir() && find_manifest(&entry.path())?.is_some() {
+                members.push(entry.path());
+            }
+        }
+    }
+    members.sort();
+    Ok(members)
+}
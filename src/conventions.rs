@@ -1,6 +1,16 @@
-use crate::error::Result;
+use crate::error::{Result, SkillsetError};
+use crate::skill::package::{DEFAULT_EXCLUDE, IGNORE_FILE};
+use crate::skill::types::{InstallScope, InstallTarget};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub mod convert;
+pub mod mcp;
+pub mod sections;
+use convert::Format;
+use sections::ManagedSection;
 
 #[async_trait]
 pub trait Convention: Send + Sync {
@@ -8,50 +18,144 @@ pub trait Convention: Send + Sync {
     fn version(&self) -> &str;
     fn description(&self) -> &str;
     async fn detect(&self, path: &std::path::Path) -> Result<bool>;
+
+    /// Where `organize` places a skill, used to locate installed skills later.
+    /// Global installs default to `~/.skillset`, which suits conventions whose
+    /// agent finds the files through a config pointing at them. Conventions
+    /// whose agent loads skills from a directory use its user-level one, or
+    /// refuse `--global` when there is none.
+    fn install_path(&self, skill_name: &str, target: &InstallTarget) -> PathBuf {
+        let base = match target.scope {
+            InstallScope::Project => target.root.clone(),
+            InstallScope::Global => target.root.join(".skillset"),
+        };
+        base.join("skills").join(self.name()).join(skill_name)
+    }
+
+    /// Layouts this convention installs skills from. Skills published in
+    /// another layout are converted to the first one. Empty when any layout
+    /// is copied as it is.
+    fn formats(&self) -> &[Format] {
+        &[]
+    }
+
+    /// Gitignore-style patterns of files left out of skills installed for
+    /// this convention. `.git` is never installed, and packages add rules of
+    /// their own in `.skillsetignore` and `skill.yaml`.
+    fn exclude(&self) -> &[&str] {
+        DEFAULT_EXCLUDE
+    }
+
+    /// Whether people or other tools also keep skills where this convention
+    /// installs them for `scope`. Skillset then refuses to replace
+    /// directories it did not install, and `verify` leaves unknown ones alone.
+    fn shares_install_dir(&self, _scope: InstallScope) -> bool {
+        false
+    }
+
+    /// Text this convention merges into instruction files shared with
+    /// hand-written content, each kept between markers so it can be updated
+    /// or taken out later. `target` is where the skill is finally installed.
+    fn sections(
+        &self,
+        _skill_name: &str,
+        _source_path: &std::path::Path,
+        _target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        Ok(Vec::new())
+    }
+
     async fn organize(
         &self,
         skill_name: &str,
         source_path: &std::path::Path,
-        target_path: &std::path::Path,
+        target: &InstallTarget,
     ) -> Result<()>;
 }
 
+/// Enabled conventions, detected in the order they were registered
 pub struct ConventionRegistry {
-    conventions: HashMap<String, Box<dyn Convention>>,
+    conventions: Vec<Box<dyn Convention>>,
+}
+
+impl Default for ConventionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every convention shipped with skillset, enabled or not
+pub fn builtin_conventions() -> Vec<Box<dyn Convention>> {
+    vec![
+        Box::new(AutoGptConvention::new()),
+        Box::new(LangchainConvention::new()),
+        Box::new(CursorConvention::new()),
+        Box::new(CopilotConvention::new()),
+        Box::new(AgentsMdConvention::new()),
+        // Before the SKILL.md conventions, packages may document the server
+        // in one
+        Box::new(McpConvention::new()),
+        Box::new(CursorMcpConvention::new()),
+        Box::new(ClaudeDesktopConvention::new()),
+        // Before agent-skills, which also claims any SKILL.md
+        Box::new(ClaudeCodeConvention::new()),
+        Box::new(AgentSkillsConvention::new()),
+    ]
 }
 
 impl ConventionRegistry {
     pub fn new() -> Self {
         Self {
-            conventions: HashMap::new(),
+            conventions: Vec::new(),
         }
     }
 
+    /// Add `convention`, replacing one registered under the same name
     pub fn register(&mut self, convention: Box<dyn Convention>) {
-        let name = convention.name().to_string();
-        self.conventions.insert(name, convention);
+        self.conventions.retain(|c| c.name() != convention.name());
+        self.conventions.push(convention);
     }
 
     pub fn get(&self, name: &str) -> Option<&dyn Convention> {
-        self.conventions.get(name).map(|c| c.as_ref())
+        self.conventions
+            .iter()
+            .find(|c| c.name() == name)
+            .map(|c| c.as_ref())
     }
 
     pub fn list(&self) -> Vec<&str> {
-        self.conventions.keys().map(|k| k.as_str()).collect()
+        self.conventions.iter().map(|c| c.name()).collect()
     }
 
     pub async fn detect_convention(&self, path: &std::path::Path) -> Result<Option<String>> {
-        for (name, convention) in &self.conventions {
+        for convention in &self.conventions {
             if convention.detect(path).await? {
-                return Ok(Some(name.clone()));
+                return Ok(Some(convention.name().to_string()));
             }
         }
         Ok(None)
     }
 }
 
+/// Python skills also leave out bytecode and virtual environments
+const PYTHON_EXCLUDE: &[&str] = &[
+    ".github/",
+    ".gitignore",
+    ".gitattributes",
+    IGNORE_FILE,
+    "__pycache__/",
+    "*.pyc",
+    ".venv/",
+];
+
 pub struct AutoGptConvention;
 
+impl Default for AutoGptConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AutoGptConvention {
     pub fn new() -> Self {
         Self
@@ -72,6 +176,14 @@ impl Convention for AutoGptConvention {
         "Auto-GPT agent framework convention"
     }
 
+    fn formats(&self) -> &[Format] {
+        &[Format::AutoGpt]
+    }
+
+    fn exclude(&self) -> &[&str] {
+        PYTHON_EXCLUDE
+    }
+
     async fn detect(&self, path: &std::path::Path) -> Result<bool> {
         let skill_py = path.join("skill.py");
         let requirements_txt = path.join("requirements.txt");
@@ -82,9 +194,13 @@ impl Convention for AutoGptConvention {
         &self,
         skill_name: &str,
         source_path: &std::path::Path,
-        target_path: &std::path::Path,
+        target: &InstallTarget,
     ) -> Result<()> {
-        let final_path = target_path.join("skills").join("autogpt").join(skill_name);
+        // Auto-GPT loads plugins from its own checkout, not the home directory
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
         std::fs::create_dir_all(&final_path)?;
 
         // Copy skill files
@@ -96,6 +212,12 @@ impl Convention for AutoGptConvention {
 
 pub struct LangchainConvention;
 
+impl Default for LangchainConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LangchainConvention {
     pub fn new() -> Self {
         Self
@@ -116,6 +238,14 @@ impl Convention for LangchainConvention {
         "LangChain agent framework convention"
     }
 
+    fn formats(&self) -> &[Format] {
+        &[Format::LangchainTool]
+    }
+
+    fn exclude(&self) -> &[&str] {
+        PYTHON_EXCLUDE
+    }
+
     async fn detect(&self, path: &std::path::Path) -> Result<bool> {
         let tool_yaml = path.join("tool.yaml");
         let py_files = path.read_dir()?.any(|entry| {
@@ -133,12 +263,14 @@ impl Convention for LangchainConvention {
         &self,
         skill_name: &str,
         source_path: &std::path::Path,
-        target_path: &std::path::Path,
+        target: &InstallTarget,
     ) -> Result<()> {
-        let final_path = target_path
-            .join("skills")
-            .join("langchain")
-            .join(skill_name);
+        // Tools are imported by the project's code, there is no user-level
+        // place LangChain looks for them
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
         std::fs::create_dir_all(&final_path)?;
 
         copy_dir_all(source_path, &final_path)?;
@@ -149,6 +281,12 @@ impl Convention for LangchainConvention {
 
 pub struct AgentSkillsConvention;
 
+impl Default for AgentSkillsConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AgentSkillsConvention {
     pub fn new() -> Self {
         Self
@@ -169,6 +307,27 @@ impl Convention for AgentSkillsConvention {
         "Vercel Agent Skills convention (SKILL.md format)"
     }
 
+    fn formats(&self) -> &[Format] {
+        &[Format::SkillMd]
+    }
+
+    /// `~/.agents/skills/<name>` for global installs, the user-level
+    /// directory agents following the Agent Skills layout share
+    fn install_path(&self, skill_name: &str, target: &InstallTarget) -> PathBuf {
+        match target.scope {
+            InstallScope::Project => target
+                .root
+                .join("skills")
+                .join(self.name())
+                .join(skill_name),
+            InstallScope::Global => target.root.join(".agents").join("skills").join(skill_name),
+        }
+    }
+
+    fn shares_install_dir(&self, scope: InstallScope) -> bool {
+        scope == InstallScope::Global
+    }
+
     async fn detect(&self, path: &std::path::Path) -> Result<bool> {
         let skill_md = path.join("SKILL.md");
         let skill_yaml = path.join("skill.yaml");
@@ -185,12 +344,828 @@ impl Convention for AgentSkillsConvention {
         &self,
         skill_name: &str,
         source_path: &std::path::Path,
-        target_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+/// The YAML frontmatter between `---` lines at the top of a SKILL.md
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SkillFrontmatter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Every other key, kept for conventions that translate them
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl SkillFrontmatter {
+    /// Split a SKILL.md into its frontmatter and markdown body
+    pub fn parse(content: &str) -> Result<(Self, &str)> {
+        let missing = || SkillsetError::Convention("SKILL.md has no frontmatter".to_string());
+        let rest = content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+            .ok_or_else(missing)?;
+
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == "---" {
+                let yaml = &rest[..offset];
+                let body = &rest[offset + line.len()..];
+                let frontmatter = if yaml.trim().is_empty() {
+                    Self::default()
+                } else {
+                    serde_yaml::from_str(yaml).map_err(|e| {
+                        SkillsetError::Convention(format!("Invalid SKILL.md frontmatter: {}", e))
+                    })?
+                };
+                return Ok((frontmatter, body));
+            }
+            offset += line.len();
+        }
+        Err(missing())
+    }
+}
+
+pub struct ClaudeCodeConvention;
+
+impl Default for ClaudeCodeConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClaudeCodeConvention {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Claude Code only loads skills whose SKILL.md frontmatter has a
+    /// lowercase, hyphenated `name` of up to 64 characters and a
+    /// `description` of up to 1024
+    pub fn validate(skill_name: &str, skill_dir: &std::path::Path) -> Result<()> {
+        let invalid = |problem: &str| {
+            SkillsetError::Convention(format!(
+                "{} is not a valid Claude Code skill: {}",
+                skill_name, problem
+            ))
+        };
+
+        let content = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+            .map_err(|_| invalid("it has no SKILL.md"))?;
+        let (frontmatter, _) = SkillFrontmatter::parse(&content).map_err(|e| match e {
+            SkillsetError::Convention(problem) => invalid(&problem),
+            e => e,
+        })?;
+
+        let name = frontmatter
+            .name
+            .as_deref()
+            .ok_or_else(|| invalid("SKILL.md frontmatter has no `name`"))?;
+        let well_formed = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if name.is_empty() || name.len() > 64 || !well_formed {
+            return Err(invalid(&format!(
+                "`name: {}` must be 1 to 64 lowercase letters, digits and hyphens",
+                name
+            )));
+        }
+
+        let description = frontmatter.description.as_deref().unwrap_or("").trim();
+        if description.is_empty() {
+            return Err(invalid("SKILL.md frontmatter has no `description`"));
+        }
+        if description.chars().count() > 1024 {
+            return Err(invalid("`description` is longer than 1024 characters"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Convention for ClaudeCodeConvention {
+    fn name(&self) -> &str {
+        "claude-code"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "Claude Code skills (.claude/skills, SKILL.md with frontmatter)"
+    }
+
+    fn formats(&self) -> &[Format] {
+        &[Format::SkillMd]
+    }
+
+    async fn detect(&self, path: &std::path::Path) -> Result<bool> {
+        Ok(Self::validate("", path).is_ok())
+    }
+
+    /// Claude Code reads `.claude/skills` in the project and in the home
+    /// directory, so both scopes use it directly
+    fn install_path(&self, skill_name: &str, target: &InstallTarget) -> PathBuf {
+        target.root.join(".claude").join("skills").join(skill_name)
+    }
+
+    fn shares_install_dir(&self, _scope: InstallScope) -> bool {
+        true
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        Self::validate(skill_name, source_path)?;
+
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+pub struct CursorConvention;
+
+impl Default for CursorConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CursorConvention {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Turn a SKILL.md into a Cursor rule. `globs` and `alwaysApply` are
+    /// taken from the frontmatter when the skill sets them; otherwise the
+    /// agent applies the rule when it finds the description relevant.
+    pub fn to_rule(skill_name: &str, skill_md: &str) -> Result<String> {
+        let (frontmatter, body) = SkillFrontmatter::parse(skill_md).map_err(|e| match e {
+            SkillsetError::Convention(problem) => unconvertible_rule(skill_name, &problem),
+            e => e,
+        })?;
+        Ok(format!(
+            "{}{}",
+            Self::rule_header(skill_name, &frontmatter)?,
+            body
+        ))
+    }
+
+    /// Frontmatter of the rule made from a skill, serialized as YAML so
+    /// descriptions and globs with special characters stay valid
+    fn rule_header(skill_name: &str, frontmatter: &SkillFrontmatter) -> Result<String> {
+        let description = frontmatter
+            .description
+            .as_deref()
+            .unwrap_or("")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if description.is_empty() {
+            return Err(unconvertible_rule(
+                skill_name,
+                "SKILL.md frontmatter has no `description`",
+            ));
+        }
+
+        let always_apply = frontmatter
+            .extra
+            .get("alwaysApply")
+            .or_else(|| frontmatter.extra.get("always_apply"))
+            .and_then(serde_yaml::Value::as_bool)
+            .unwrap_or(false);
+
+        let mut header = serde_yaml::Mapping::new();
+        header.insert("description".into(), description.into());
+        header.insert("globs".into(), globs(frontmatter).into());
+        header.insert("alwaysApply".into(), always_apply.into());
+        yaml_frontmatter(&header)
+    }
+}
+
+fn unconvertible_rule(skill_name: &str, problem: &str) -> SkillsetError {
+    SkillsetError::Convention(format!(
+        "Cannot convert {} to a Cursor rule: {}",
+        skill_name, problem
+    ))
+}
+
+fn no_user_rules() -> SkillsetError {
+    SkillsetError::Convention(
+        "Cursor has no user-level rules directory, install cursor skills per project".to_string(),
+    )
+}
+
+#[async_trait]
+impl Convention for CursorConvention {
+    fn name(&self) -> &str {
+        "cursor"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "Cursor project rules (.cursor/rules, .mdc files)"
+    }
+
+    fn formats(&self) -> &[Format] {
+        &[Format::SkillMd, Format::CursorRule]
+    }
+
+    /// Skills that already ship Cursor rules. SKILL.md skills are converted
+    /// when installed with this convention explicitly.
+    async fn detect(&self, path: &std::path::Path) -> Result<bool> {
+        Ok(path.read_dir()?.any(|entry| {
+            entry.is_ok_and(|entry| entry.path().extension().is_some_and(|ext| ext == "mdc"))
+        }))
+    }
+
+    /// Scripts and resources go in a directory next to the rule, which
+    /// Cursor searches recursively, so rules a skill ships stay with them
+    fn install_path(&self, skill_name: &str, target: &InstallTarget) -> PathBuf {
+        target.root.join(".cursor").join("rules").join(skill_name)
+    }
+
+    fn shares_install_dir(&self, _scope: InstallScope) -> bool {
+        true
+    }
+
+    /// A SKILL.md becomes `.cursor/rules/<name>.mdc`, pointing at the
+    /// directory its resources are copied to
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        if target.scope == InstallScope::Global {
+            return Err(no_user_rules());
+        }
+        if !source_path.join("SKILL.md").exists() {
+            return Ok(Vec::new());
+        }
+        let installed = self.install_path(skill_name, target);
+        let relative = installed.strip_prefix(&target.root).unwrap_or(&installed);
+        let (frontmatter, body) = instructions(skill_name, source_path, relative)?;
+
+        Ok(vec![ManagedSection {
+            path: target
+                .root
+                .join(".cursor")
+                .join("rules")
+                .join(format!("{}.mdc", skill_name)),
+            header: Self::rule_header(skill_name, &frontmatter)?,
+            body,
+        }])
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        if target.scope == InstallScope::Global {
+            return Err(no_user_rules());
+        }
+
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        // The rule next to the directory replaces the SKILL.md
+        let skill_md = final_path.join("SKILL.md");
+        if skill_md.exists() {
+            std::fs::remove_file(skill_md)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Instructions for a skill in a shared file: its SKILL.md without the
+/// frontmatter, or its description when the body is empty, pointing at the
+/// installed copy when the skill has more files than the SKILL.md
+fn instructions(
+    skill_name: &str,
+    source_path: &std::path::Path,
+    installed: &std::path::Path,
+) -> Result<(SkillFrontmatter, String)> {
+    let content = std::fs::read_to_string(source_path.join("SKILL.md")).map_err(|_| {
+        SkillsetError::Convention(format!(
+            "{} has no SKILL.md to take instructions from",
+            skill_name
+        ))
+    })?;
+    let (frontmatter, body) = match SkillFrontmatter::parse(&content) {
+        Ok((frontmatter, body)) => (frontmatter, body.trim().to_string()),
+        // Plain markdown is used as it is
+        Err(_) => (SkillFrontmatter::default(), content.trim().to_string()),
+    };
+
+    let mut text = if body.is_empty() {
+        format!(
+            "## {}\n\n{}",
+            skill_name,
+            frontmatter.description.as_deref().unwrap_or("").trim()
+        )
+    } else {
+        body
+    };
+    let has_resources = std::fs::read_dir(source_path)?
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.file_name() != "SKILL.md" && entry.file_name() != ".git");
+    if has_resources {
+        text.push_str(&format!(
+            "\n\nScripts and resources for this skill are in `{}/`.",
+            installed.display()
+        ));
+    }
+    Ok((frontmatter, text))
+}
+
+/// The `globs` a SKILL.md sets, a string or a list, joined with commas
+fn globs(frontmatter: &SkillFrontmatter) -> String {
+    match frontmatter.extra.get("globs") {
+        Some(serde_yaml::Value::String(globs)) => globs.clone(),
+        Some(serde_yaml::Value::Sequence(globs)) => globs
+            .iter()
+            .filter_map(serde_yaml::Value::as_str)
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => String::new(),
+    }
+}
+
+/// Frontmatter block holding `fields`, serialized as YAML so values with
+/// quotes, colons or a leading `*` stay valid
+fn yaml_frontmatter(fields: &serde_yaml::Mapping) -> Result<String> {
+    let yaml = serde_yaml::to_string(fields)
+        .map_err(|e| SkillsetError::SerializationError(e.to_string()))?;
+    Ok(format!("---\n{}---\n", yaml))
+}
+
+fn per_project_only(convention: &str) -> SkillsetError {
+    SkillsetError::Convention(format!(
+        "{} skills are per project, install them without --global",
+        convention
+    ))
+}
+
+/// Skills merged into a root `AGENTS.md`, read by Codex, Jules, Amp and
+/// other agents. Their files are copied under `skills/agents-md`.
+pub struct AgentsMdConvention;
+
+impl Default for AgentsMdConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentsMdConvention {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Convention for AgentsMdConvention {
+    fn name(&self) -> &str {
+        "agents-md"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "AGENTS.md instructions (managed sections in the root AGENTS.md)"
+    }
+
+    fn formats(&self) -> &[Format] {
+        &[Format::SkillMd]
+    }
+
+    /// Only used when asked for, SKILL.md skills are detected as agent-skills
+    async fn detect(&self, _path: &std::path::Path) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let installed = self.install_path(skill_name, target);
+        let relative = installed.strip_prefix(&target.root).unwrap_or(&installed);
+        let (_, body) = instructions(skill_name, source_path, relative)?;
+
+        Ok(vec![ManagedSection {
+            path: target.root.join("AGENTS.md"),
+            header: String::new(),
+            body,
+        }])
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+/// Skills merged into GitHub Copilot's custom instructions. Skills with
+/// `globs` get their own `.github/instructions/<name>.instructions.md`
+/// applied to matching files, the others a section of
+/// `.github/copilot-instructions.md`. Their files are copied under
+/// `skills/copilot`.
+pub struct CopilotConvention;
+
+impl Default for CopilotConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopilotConvention {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Convention for CopilotConvention {
+    fn name(&self) -> &str {
+        "copilot"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "GitHub Copilot custom instructions (.github/copilot-instructions.md)"
+    }
+
+    fn formats(&self) -> &[Format] {
+        &[Format::SkillMd]
+    }
+
+    /// Only used when asked for, SKILL.md skills are detected as agent-skills
+    async fn detect(&self, _path: &std::path::Path) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let installed = self.install_path(skill_name, target);
+        let relative = installed.strip_prefix(&target.root).unwrap_or(&installed);
+        let (frontmatter, body) = instructions(skill_name, source_path, relative)?;
+
+        let github = target.root.join(".github");
+        let globs = globs(&frontmatter);
+        let section = if globs.is_empty() {
+            ManagedSection {
+                path: github.join("copilot-instructions.md"),
+                header: String::new(),
+                body,
+            }
+        } else {
+            ManagedSection {
+                path: github
+                    .join("instructions")
+                    .join(format!("{}.instructions.md", skill_name)),
+                header: yaml_frontmatter(&serde_yaml::Mapping::from_iter([(
+                    "applyTo".into(),
+                    globs.into(),
+                )]))?,
+                body,
+            }
+        };
+        Ok(vec![section])
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+/// The `mcpServers` entry for the MCP server packaged at `source_path`,
+/// merged into the config at `config`
+fn mcp_server(
+    convention: &dyn Convention,
+    skill_name: &str,
+    source_path: &std::path::Path,
+    target: &InstallTarget,
+    config: PathBuf,
+    style: mcp::EnvStyle,
+) -> Result<Vec<ManagedSection>> {
+    let package = mcp::McpPackage::read(source_path)?.ok_or_else(|| {
+        SkillsetError::Convention(format!(
+            "{} is not an MCP server, it has no {}",
+            skill_name,
+            mcp::MANIFEST_FILE
+        ))
+    })?;
+    let current = match std::fs::read_to_string(&config) {
+        Ok(content) => mcp::server(&content, skill_name)?,
+        Err(_) => None,
+    };
+    let installed = convention.install_path(skill_name, target);
+    let entry = package.entry(&installed, style, current.as_ref())?;
+    if style != mcp::EnvStyle::Value {
+        for missing in package.missing_inputs() {
+            tracing::warn!(
+                "{} needs {} set in the environment to start",
+                skill_name,
+                missing
+            );
+        }
+    }
+
+    Ok(vec![ManagedSection {
+        path: config,
+        header: String::new(),
+        body: serde_json::to_string_pretty(&entry)?,
+    }])
+}
+
+/// MCP servers in the project's `.mcp.json`, read by Claude Code and other
+/// agents. Packages are copied under `skills/mcp` for servers run from
+/// their own files.
+pub struct McpConvention;
+
+impl Default for McpConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl McpConvention {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Convention for McpConvention {
+    fn name(&self) -> &str {
+        "mcp"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "MCP servers (entries in the project's .mcp.json)"
+    }
+
+    async fn detect(&self, path: &std::path::Path) -> Result<bool> {
+        Ok(path.join(mcp::MANIFEST_FILE).is_file())
+    }
+
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        mcp_server(
+            self,
+            skill_name,
+            source_path,
+            target,
+            target.root.join(".mcp.json"),
+            mcp::EnvStyle::Expand,
+        )
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        if target.scope == InstallScope::Global {
+            return Err(per_project_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+/// MCP servers in Cursor's `.cursor/mcp.json`, in the project or the home
+/// directory
+pub struct CursorMcpConvention;
+
+impl Default for CursorMcpConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CursorMcpConvention {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Convention for CursorMcpConvention {
+    fn name(&self) -> &str {
+        "cursor-mcp"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "Cursor MCP servers (entries in .cursor/mcp.json)"
+    }
+
+    /// Only used when asked for, MCP packages are detected as mcp
+    async fn detect(&self, _path: &std::path::Path) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        mcp_server(
+            self,
+            skill_name,
+            source_path,
+            target,
+            target.root.join(".cursor").join("mcp.json"),
+            mcp::EnvStyle::CursorExpand,
+        )
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
     ) -> Result<()> {
-        let final_path = target_path
-            .join("skills")
-            .join("agent-skills")
-            .join(skill_name);
+        let final_path = self.install_path(skill_name, target);
+        std::fs::create_dir_all(&final_path)?;
+
+        copy_dir_all(source_path, &final_path)?;
+
+        Ok(())
+    }
+}
+
+/// MCP servers in the Claude desktop app's config. The app does not expand
+/// variables, so the values the server needs are written into the config,
+/// taken from the environment or asked for.
+pub struct ClaudeDesktopConvention;
+
+impl Default for ClaudeDesktopConvention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClaudeDesktopConvention {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `claude_desktop_config.json`, relative to the home directory
+    pub fn config_path() -> PathBuf {
+        let dir = if cfg!(target_os = "macos") {
+            PathBuf::from("Library").join("Application Support")
+        } else if cfg!(windows) {
+            PathBuf::from("AppData").join("Roaming")
+        } else {
+            PathBuf::from(".config")
+        };
+        dir.join("Claude").join("claude_desktop_config.json")
+    }
+}
+
+fn per_user_only(convention: &str) -> SkillsetError {
+    SkillsetError::Convention(format!(
+        "{} skills are per user, install them with --global",
+        convention
+    ))
+}
+
+#[async_trait]
+impl Convention for ClaudeDesktopConvention {
+    fn name(&self) -> &str {
+        "claude-desktop"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn description(&self) -> &str {
+        "Claude desktop app MCP servers (entries in claude_desktop_config.json)"
+    }
+
+    /// Only used when asked for, MCP packages are detected as mcp
+    async fn detect(&self, _path: &std::path::Path) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn sections(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<Vec<ManagedSection>> {
+        if target.scope == InstallScope::Project {
+            return Err(per_user_only(self.name()));
+        }
+        mcp_server(
+            self,
+            skill_name,
+            source_path,
+            target,
+            target.root.join(Self::config_path()),
+            mcp::EnvStyle::Value,
+        )
+    }
+
+    async fn organize(
+        &self,
+        skill_name: &str,
+        source_path: &std::path::Path,
+        target: &InstallTarget,
+    ) -> Result<()> {
+        if target.scope == InstallScope::Project {
+            return Err(per_user_only(self.name()));
+        }
+        let final_path = self.install_path(skill_name, target);
         std::fs::create_dir_all(&final_path)?;
 
         copy_dir_all(source_path, &final_path)?;
@@ -200,18 +1175,122 @@ impl Convention for AgentSkillsConvention {
 }
 
 fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
-    std::fs::create_dir_all(dst)?;
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let file_type = entry.file_type()?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
+    crate::skill::package::copy_tree(src, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_install_path_depends_on_scope() {
+        let convention = AgentSkillsConvention::new();
+
+        let project = InstallTarget::project(PathBuf::from("/work/project"));
+        assert_eq!(
+            convention.install_path("react-best-practices", &project),
+            Path::new("/work/project/skills/agent-skills/react-best-practices")
+        );
+
+        let global = InstallTarget {
+            scope: InstallScope::Global,
+            root: PathBuf::from("/home/user"),
+        };
+        assert_eq!(
+            convention.install_path("react-best-practices", &global),
+            Path::new("/home/user/.agents/skills/react-best-practices")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_python_frameworks_refuse_global_installs() {
+        let source = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        let global = InstallTarget {
+            scope: InstallScope::Global,
+            root: home.path().to_path_buf(),
+        };
+
+        for convention in [
+            Box::new(AutoGptConvention::new()) as Box<dyn Convention>,
+            Box::new(LangchainConvention::new()),
+        ] {
+            let error = convention
+                .organize("tool", source.path(), &global)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("without --global"), "{}", error);
+        }
+        assert_eq!(std::fs::read_dir(home.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_claude_code_installs_into_dot_claude_for_both_scopes() {
+        let convention = ClaudeCodeConvention::new();
+
+        let project = InstallTarget::project(PathBuf::from("/work/project"));
+        assert_eq!(
+            convention.install_path("pdf", &project),
+            Path::new("/work/project/.claude/skills/pdf")
+        );
+
+        let global = InstallTarget {
+            scope: InstallScope::Global,
+            root: PathBuf::from("/home/user"),
+        };
+        assert_eq!(
+            convention.install_path("pdf", &global),
+            Path::new("/home/user/.claude/skills/pdf")
+        );
+    }
+
+    #[test]
+    fn test_claude_code_validates_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_md = dir.path().join("SKILL.md");
+        let validate = |content: &str| {
+            std::fs::write(&skill_md, content).unwrap();
+            ClaudeCodeConvention::validate("pdf", dir.path())
+        };
 
-        if file_type.is_dir() {
-            copy_dir_all(&src_path, &dst_path)?;
-        } else if file_type.is_file() {
-            std::fs::copy(&src_path, &dst_path)?;
+        validate("---\nname: pdf\ndescription: Fill PDF forms\n---\n# PDF\n").unwrap();
+
+        for (content, problem) in [
+            ("# PDF\n", "no frontmatter"),
+            ("---\ndescription: Fill PDF forms\n---\n", "no `name`"),
+            ("---\nname: PDF Forms\ndescription: x\n---\n", "lowercase"),
+            ("---\nname: pdf\n---\n", "no `description`"),
+            ("---\nname: [pdf\n---\n", "Invalid SKILL.md frontmatter"),
+        ] {
+            let error = validate(content).unwrap_err().to_string();
+            assert!(error.contains(problem), "{}", error);
+            assert!(error.contains("pdf is not a valid Claude Code skill"));
         }
     }
-    Ok(())
+
+    #[test]
+    fn test_skill_md_becomes_a_cursor_rule() {
+        let skill_md = "---\nname: react\ndescription: React best\n  practices\nglobs:\n  - \"*.tsx\"\n  - \"*.jsx\"\n---\n# React\nSee scripts/lint.sh\n";
+        assert_eq!(
+            CursorConvention::to_rule("react", skill_md).unwrap(),
+            "---\ndescription: React best practices\nglobs: '*.tsx,*.jsx'\nalwaysApply: false\n---\n# React\nSee scripts/lint.sh\n"
+        );
+
+        // The frontmatter stays valid YAML whatever the skill says
+        let tricky = "---\ndescription: \"Style: strict\"\nglobs: \"*\"\n---\nBody\n";
+        let rule = CursorConvention::to_rule("tricky", tricky).unwrap();
+        let (frontmatter, body) = SkillFrontmatter::parse(&rule).unwrap();
+        assert_eq!(frontmatter.description.as_deref(), Some("Style: strict"));
+        assert_eq!(frontmatter.extra["globs"], serde_yaml::Value::from("*"));
+        assert_eq!(body, "Body\n");
+
+        let always = "---\ndescription: House style\nalwaysApply: true\n---\nBody\n";
+        assert!(CursorConvention::to_rule("style", always)
+            .unwrap()
+            .contains("globs: ''\nalwaysApply: true\n"));
+
+        let error = CursorConvention::to_rule("bare", "# Bare\n").unwrap_err();
+        assert!(error.to_string().contains("Cannot convert bare"));
+    }
 }
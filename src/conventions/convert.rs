@@ -0,0 +1,480 @@
+//! Converting skills between the layouts conventions install from.
+//!
+//! A skill is read into a `SkillDocument` (name, description, instructions
+//! and any other frontmatter) from whichever layout it was published in,
+//! then written out in the layout the target convention expects. Scripts and
+//! other resources are carried over as they are.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Convention, SkillFrontmatter};
+use crate::error::{Result, SkillsetError};
+use crate::skill::package::{copy_entry, PackageFilter};
+use crate::skill::types::InstallTarget;
+
+/// A layout skills are published in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `SKILL.md` with YAML frontmatter
+    SkillMd,
+    /// A single Cursor `.mdc` rule
+    CursorRule,
+    /// `tool.yaml` next to a Python tool
+    LangchainTool,
+    /// `skill.py` with a `requirements.txt`
+    AutoGpt,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::SkillMd => "SKILL.md",
+            Self::CursorRule => "Cursor rule",
+            Self::LangchainTool => "LangChain tool",
+            Self::AutoGpt => "Auto-GPT skill",
+        };
+        f.write_str(label)
+    }
+}
+
+impl Format {
+    /// The layout of the skill at `path`, if it is one skillset can read
+    pub fn detect(path: &Path) -> Option<Self> {
+        if path.join("SKILL.md").is_file() {
+            Some(Self::SkillMd)
+        } else if path.join("tool.yaml").is_file() {
+            Some(Self::LangchainTool)
+        } else if path.join("skill.py").is_file() && path.join("requirements.txt").is_file() {
+            Some(Self::AutoGpt)
+        } else if !rules(path).is_empty() {
+            Some(Self::CursorRule)
+        } else {
+            None
+        }
+    }
+}
+
+/// What a skill says, independent of its layout
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillDocument {
+    pub name: String,
+    pub description: String,
+    /// Frontmatter other than `name` and `description`, e.g. `globs`
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+    /// Markdown instructions
+    pub body: String,
+}
+
+/// `tool.yaml` of a LangChain tool
+#[derive(Debug, Serialize, Deserialize)]
+struct ToolManifest {
+    name: String,
+    #[serde(default)]
+    description: String,
+    /// Python file defining the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    module: Option<String>,
+}
+
+/// Instructions of a LangChain tool, returned by the generated stub
+const INSTRUCTIONS_FILE: &str = "INSTRUCTIONS.md";
+
+/// Read the skill at `source` and write it to `dest` in the layout `to`.
+/// Skills already in that layout are copied as they are.
+pub fn convert(skill_name: &str, source: &Path, to: Format, dest: &Path) -> Result<Format> {
+    let from = Format::detect(source).ok_or_else(|| {
+        SkillsetError::Convention(format!(
+            "Cannot convert {}: no SKILL.md, tool.yaml, .mdc rule or skill.py found",
+            skill_name
+        ))
+    })?;
+    if from == to {
+        copy_resources(source, dest, &[])?;
+        return Ok(from);
+    }
+
+    let document = read(from, skill_name, source)?;
+    let consumed = consumed_files(from, source);
+    copy_resources(source, dest, &consumed)?;
+    write(&document, to, dest)?;
+    Ok(from)
+}
+
+/// A skill written out for another convention by `convert_skill`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertedSkill {
+    pub name: String,
+    /// Layout the skill was published in, when skillset recognized it
+    pub from: Option<String>,
+    /// Convention it was converted for
+    pub to: String,
+    pub path: PathBuf,
+}
+
+/// Write the skill at `source` to `dest` the way `convention` would install
+/// it, e.g. as a `.mdc` rule for Cursor. Text a convention merges into
+/// instruction files is written to those files inside `dest`, without
+/// markers.
+pub async fn convert_skill(
+    convention: &dyn Convention,
+    source: &Path,
+    dest: &Path,
+) -> Result<ConvertedSkill> {
+    if dest.exists() {
+        return Err(SkillsetError::Convention(format!(
+            "{} already exists, choose another --output",
+            dest.display()
+        )));
+    }
+    let source = source.canonicalize()?;
+    let dir_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            SkillsetError::Convention(format!("{} is not a skill directory", source.display()))
+        })?;
+    let from = Format::detect(&source);
+    // The name the skill gives itself wins over its directory's
+    let name = from
+        .and_then(|from| read(from, &dir_name, &source).ok())
+        .map(|document| document.name)
+        .unwrap_or(dir_name);
+
+    // Only what the package ships, as when installing
+    let scratch = tempfile::tempdir()?;
+    let package = scratch.path().join("package").join(&name);
+    PackageFilter::new(&source, convention.exclude())?.copy_to(&package)?;
+    let mut layout = package.clone();
+    if let (Some(&to), Some(from)) = (convention.formats().first(), from) {
+        if !convention.formats().contains(&from) && !convention.detect(&package).await? {
+            layout = scratch.path().join("converted").join(&name);
+            convert(&name, &package, to, &layout)?;
+        }
+    }
+
+    // Install into a throwaway project and keep what the convention made
+    let target = InstallTarget::project(scratch.path().join("project"));
+    convention.organize(&name, &layout, &target).await?;
+    let installed = convention.install_path(&name, &target);
+    copy_resources(&installed, dest, &[])?;
+    for section in convention.sections(&name, &layout, &target)? {
+        // A file kept next to the skill's directory, like a Cursor rule,
+        // belongs to the converted skill
+        let path = match section.path.file_name() {
+            Some(file_name) if section.path.parent() == installed.parent() => dest.join(file_name),
+            _ => dest.join(
+                section
+                    .path
+                    .strip_prefix(&target.root)
+                    .unwrap_or(&section.path),
+            ),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, format!("{}{}\n", section.header, section.body))?;
+    }
+
+    Ok(ConvertedSkill {
+        name,
+        from: from.map(|from| from.to_string()),
+        to: convention.name().to_string(),
+        path: dest.to_path_buf(),
+    })
+}
+
+/// Read a skill published in the layout `format`
+pub fn read(format: Format, skill_name: &str, source: &Path) -> Result<SkillDocument> {
+    match format {
+        Format::SkillMd => {
+            let content = std::fs::read_to_string(source.join("SKILL.md"))?;
+            let (frontmatter, body) = match SkillFrontmatter::parse(&content) {
+                Ok((frontmatter, body)) => (frontmatter, body),
+                // Plain markdown has nothing but instructions
+                Err(_) => (SkillFrontmatter::default(), content.as_str()),
+            };
+            Ok(SkillDocument {
+                name: frontmatter.name.unwrap_or_else(|| skill_name.to_string()),
+                description: frontmatter.description.unwrap_or_default(),
+                extra: frontmatter.extra,
+                body: body.trim_start_matches(['\r', '\n']).to_string(),
+            })
+        }
+        Format::CursorRule => {
+            let rules = rules(source);
+            let [rule] = rules.as_slice() else {
+                return Err(SkillsetError::Convention(format!(
+                    "Cannot convert {}: it has {} .mdc rules, expected one",
+                    skill_name,
+                    rules.len()
+                )));
+            };
+            let name = rule
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| skill_name.to_string());
+            read_rule(&name, &std::fs::read_to_string(rule)?)
+        }
+        Format::LangchainTool => {
+            let content = std::fs::read_to_string(source.join("tool.yaml"))?;
+            let manifest: ToolManifest = serde_yaml::from_str(&content).map_err(|e| {
+                SkillsetError::Convention(format!("Invalid tool.yaml in {}: {}", skill_name, e))
+            })?;
+            let body = [INSTRUCTIONS_FILE, "README.md"]
+                .iter()
+                .find_map(|file| std::fs::read_to_string(source.join(file)).ok())
+                .unwrap_or_default();
+            Ok(SkillDocument {
+                name: manifest.name,
+                description: manifest.description,
+                extra: BTreeMap::new(),
+                body,
+            })
+        }
+        Format::AutoGpt => Err(SkillsetError::Convention(format!(
+            "Cannot convert {}: Auto-GPT skills are Python code without instructions to carry over",
+            skill_name
+        ))),
+    }
+}
+
+/// Write `document` into `dest` in the layout `format`
+pub fn write(document: &SkillDocument, format: Format, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+    match format {
+        Format::SkillMd => {
+            std::fs::write(dest.join("SKILL.md"), render_skill_md(document)?)?;
+        }
+        Format::CursorRule => {
+            let skill_md = render_skill_md(document)?;
+            let rule = super::CursorConvention::to_rule(&document.name, &skill_md)?;
+            std::fs::write(dest.join(format!("{}.mdc", document.name)), rule)?;
+        }
+        Format::LangchainTool => {
+            let module = format!("{}.py", python_identifier(&document.name));
+            let manifest = ToolManifest {
+                name: document.name.clone(),
+                description: document.description.clone(),
+                module: Some(module.clone()),
+            };
+            std::fs::write(dest.join("tool.yaml"), yaml(&manifest)?)?;
+            std::fs::write(dest.join(INSTRUCTIONS_FILE), &document.body)?;
+            std::fs::write(dest.join(module), tool_stub(document))?;
+        }
+        Format::AutoGpt => {
+            return Err(SkillsetError::Convention(format!(
+                "Cannot convert {} to an Auto-GPT skill, which has to be written as Python code",
+                document.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `.mdc` files at the top of a skill, sorted
+fn rules(path: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut rules: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "mdc") && path.is_file())
+        .collect();
+    rules.sort();
+    rules
+}
+
+/// Rule frontmatter is YAML, but Cursor writes globs unquoted
+/// (`globs: *.rs`), which is not, so it is read line by line when it does not
+/// parse
+fn read_rule(name: &str, content: &str) -> Result<SkillDocument> {
+    let mut document = SkillDocument {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let Some(rest) = content.strip_prefix("---\n") else {
+        document.body = content.to_string();
+        return Ok(document);
+    };
+    let Some(end) = rest.find("\n---\n") else {
+        return Err(SkillsetError::Convention(format!(
+            "{}.mdc has unterminated frontmatter",
+            name
+        )));
+    };
+
+    let fields: Vec<(String, serde_yaml::Value)> =
+        match serde_yaml::from_str::<serde_yaml::Mapping>(&rest[..end]) {
+            Ok(mapping) => mapping
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.as_str()?.to_string(), value)))
+                .collect(),
+            Err(_) => rest[..end]
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| {
+                    let value = value.trim().trim_matches('"');
+                    let value = match value {
+                        "true" | "false" => serde_yaml::Value::from(value == "true"),
+                        _ => serde_yaml::Value::from(value),
+                    };
+                    (key.trim().to_string(), value)
+                })
+                .collect(),
+        };
+    for (key, value) in fields {
+        match (key.as_str(), value) {
+            ("description", serde_yaml::Value::String(description)) => {
+                document.description = description;
+            }
+            ("globs", serde_yaml::Value::String(globs)) if globs.is_empty() => {}
+            ("globs", globs @ (serde_yaml::Value::String(_) | serde_yaml::Value::Sequence(_))) => {
+                document.extra.insert("globs".to_string(), globs);
+            }
+            ("alwaysApply", serde_yaml::Value::Bool(always_apply)) => {
+                document
+                    .extra
+                    .insert("alwaysApply".to_string(), always_apply.into());
+            }
+            _ => {}
+        }
+    }
+    document.body = rest[end + 5..].to_string();
+    Ok(document)
+}
+
+fn render_skill_md(document: &SkillDocument) -> Result<String> {
+    let mut frontmatter = serde_yaml::Mapping::new();
+    frontmatter.insert("name".into(), document.name.clone().into());
+    frontmatter.insert("description".into(), document.description.clone().into());
+    for (key, value) in &document.extra {
+        frontmatter.insert(key.clone().into(), value.clone());
+    }
+    Ok(format!(
+        "---\n{}---\n{}",
+        yaml(&frontmatter)?,
+        document.body
+    ))
+}
+
+fn yaml<T: Serialize>(value: &T) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|e| SkillsetError::SerializationError(e.to_string()))
+}
+
+/// A LangChain tool handing the skill's instructions to the agent
+fn tool_stub(document: &SkillDocument) -> String {
+    let docstring = document
+        .description
+        .replace('\\', "\\\\")
+        .replace("\"\"\"", "\\\"\\\"\\\"");
+    format!(
+        r#""""{name} skill, converted from SKILL.md by skillset"""
+from pathlib import Path
+
+from langchain_core.tools import tool
+
+INSTRUCTIONS = (Path(__file__).parent / "{instructions}").read_text()
+
+
+@tool
+def {function}(task: str) -> str:
+    """{docstring}"""
+    return INSTRUCTIONS
+"#,
+        name = document.name,
+        instructions = INSTRUCTIONS_FILE,
+        function = python_identifier(&document.name),
+        docstring = docstring,
+    )
+}
+
+fn python_identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("skill_{}", identifier)
+    } else {
+        identifier
+    }
+}
+
+/// Files the layout `format` is made of, which conversion replaces
+fn consumed_files(format: Format, source: &Path) -> Vec<String> {
+    match format {
+        Format::SkillMd => vec!["SKILL.md".to_string()],
+        Format::CursorRule => rules(source)
+            .iter()
+            .filter_map(|rule| rule.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect(),
+        Format::LangchainTool => vec!["tool.yaml".to_string(), INSTRUCTIONS_FILE.to_string()],
+        Format::AutoGpt => Vec::new(),
+    }
+}
+
+/// Copy everything but `skip` and version control metadata
+fn copy_resources(source: &Path, dest: &Path, skip: &[String]) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" || skip.contains(&name) {
+            continue;
+        }
+        copy_entry(source, Path::new(&name), dest)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_md_round_trips_through_other_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir_all(source.join("scripts")).unwrap();
+        std::fs::write(
+            source.join("SKILL.md"),
+            "---\nname: helper\ndescription: Helps with things\nglobs: \"*.rs\"\n---\n# Helper\n",
+        )
+        .unwrap();
+        std::fs::write(source.join("scripts/run.sh"), "echo helper\n").unwrap();
+        let original = read(Format::SkillMd, "helper", &source).unwrap();
+
+        for format in [Format::CursorRule, Format::LangchainTool] {
+            let converted = dir.path().join(format.to_string());
+            assert_eq!(
+                convert("helper", &source, format, &converted).unwrap(),
+                Format::SkillMd
+            );
+            assert_eq!(Format::detect(&converted), Some(format));
+            assert!(converted.join("scripts/run.sh").exists());
+            assert!(!converted.join("SKILL.md").exists());
+
+            let back = dir.path().join(format!("{} back", format));
+            convert("helper", &converted, Format::SkillMd, &back).unwrap();
+            let document = read(Format::SkillMd, "helper", &back).unwrap();
+            assert_eq!(document.name, original.name);
+            assert_eq!(document.description, original.description);
+            assert_eq!(document.body, original.body);
+        }
+
+        let tool = std::fs::read_to_string(dir.path().join("LangChain tool/helper.py")).unwrap();
+        assert!(tool.contains("def helper(task: str) -> str:"));
+        assert!(convert(
+            "helper",
+            &source,
+            Format::AutoGpt,
+            &dir.path().join("autogpt")
+        )
+        .is_err());
+    }
+}
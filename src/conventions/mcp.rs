@@ -0,0 +1,333 @@
+//! MCP servers packaged as skills.
+//!
+//! A package has an `mcp.json` describing the server the way it appears
+//! under `mcpServers` in an agent's config, plus the environment variables it
+//! needs. Installing merges one entry, named after the skill, into that
+//! config. Every other entry and key in the file is left as it was.
+
+use std::io::IsTerminal;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::error::{Result, SkillsetError};
+
+/// The package manifest, at the root of the skill
+pub const MANIFEST_FILE: &str = "mcp.json";
+
+/// Key the servers sit under in every config skillset writes
+const SERVERS_KEY: &str = "mcpServers";
+
+/// Replaced with the installed skill directory in the server entry, for
+/// servers run from files shipped in the package
+const SKILL_DIR: &str = "${SKILL_DIR}";
+
+/// `mcp.json` in a skill package
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpPackage {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Environment variables the server reads, filled in on install
+    #[serde(default)]
+    pub inputs: Vec<McpInput>,
+    /// `command`, `args`, `env`, `url` and whatever else the server entry has
+    #[serde(flatten)]
+    pub server: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// How a config refers to the variables a server needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvStyle {
+    /// `${NAME}`, expanded by the agent when it starts the server
+    Expand,
+    /// `${env:NAME}`, Cursor's spelling of the same
+    CursorExpand,
+    /// The value itself, for configs that are never expanded
+    Value,
+}
+
+impl McpPackage {
+    /// Read the manifest of the package at `path`, if it has one
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        let manifest = path.join(MANIFEST_FILE);
+        if !manifest.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&manifest)?;
+        let package: Self = serde_json::from_str(&content).map_err(|e| {
+            SkillsetError::Convention(format!("Invalid {}: {}", manifest.display(), e))
+        })?;
+        if !package.server.contains_key("command") && !package.server.contains_key("url") {
+            return Err(SkillsetError::Convention(format!(
+                "{} needs a command or a url to start the server with",
+                manifest.display()
+            )));
+        }
+        Ok(Some(package))
+    }
+
+    /// The entry to put under `mcpServers`, pointing at `installed` and with
+    /// the inputs filled in as `style` asks. Values already in the `current`
+    /// entry are kept instead of being asked for again.
+    pub fn entry(
+        &self,
+        installed: &Path,
+        style: EnvStyle,
+        current: Option<&Value>,
+    ) -> Result<Value> {
+        let mut server = Value::Object(self.server.clone());
+        substitute(&mut server, &installed.display().to_string());
+        let Value::Object(mut server) = server else {
+            unreachable!("the entry is built from an object");
+        };
+
+        let mut env = match server.remove("env") {
+            Some(Value::Object(env)) => env,
+            Some(_) => {
+                return Err(SkillsetError::Convention(
+                    "The env of an MCP server has to be an object".to_string(),
+                ))
+            }
+            None => Map::new(),
+        };
+        for input in &self.inputs {
+            let value = match style {
+                EnvStyle::Expand => Some(format!("${{{}}}", input.name)),
+                EnvStyle::CursorExpand => Some(format!("${{env:{}}}", input.name)),
+                EnvStyle::Value => {
+                    let kept = current
+                        .and_then(|current| current.get("env"))
+                        .and_then(|env| env.get(&input.name))
+                        .and_then(Value::as_str);
+                    match kept {
+                        Some(kept) => Some(kept.to_string()),
+                        None => input.value()?,
+                    }
+                }
+            };
+            if let Some(value) = value {
+                env.insert(input.name.clone(), Value::String(value));
+            }
+        }
+        if !env.is_empty() {
+            server.insert("env".to_string(), Value::Object(env));
+        }
+        Ok(Value::Object(server))
+    }
+
+    /// Required inputs that are not set in the environment, which agents
+    /// expanding references will not be able to start the server without
+    pub fn missing_inputs(&self) -> Vec<&str> {
+        self.inputs
+            .iter()
+            .filter(|input| input.required && std::env::var_os(&input.name).is_none())
+            .map(|input| input.name.as_str())
+            .collect()
+    }
+}
+
+impl McpInput {
+    /// The value from the environment, or asked for on a terminal. Optional
+    /// inputs nobody gave a value for are left out.
+    fn value(&self) -> Result<Option<String>> {
+        if let Ok(value) = std::env::var(&self.name) {
+            return Ok(Some(value));
+        }
+        if !std::io::stdin().is_terminal() {
+            if !self.required {
+                return Ok(None);
+            }
+            return Err(SkillsetError::Convention(format!(
+                "The MCP server needs {}, set it in the environment",
+                self.name
+            )));
+        }
+
+        let prompt = match &self.description {
+            Some(description) => format!("{} ({}): ", self.name, description),
+            None => format!("{}: ", self.name),
+        };
+        let value = rpassword::prompt_password(prompt)?;
+        if value.is_empty() && !self.required {
+            return Ok(None);
+        }
+        Ok(Some(value))
+    }
+}
+
+fn substitute(value: &mut Value, skill_dir: &str) {
+    match value {
+        Value::String(text) if text.contains(SKILL_DIR) => {
+            *text = text.replace(SKILL_DIR, skill_dir);
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| substitute(item, skill_dir)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| substitute(field, skill_dir)),
+        _ => {}
+    }
+}
+
+/// Whether `path` is a JSON config holding servers rather than an
+/// instruction file with marked sections
+pub fn is_config(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+fn parse(content: &str) -> Result<Map<String, Value>> {
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(content) {
+        Ok(Value::Object(config)) => Ok(config),
+        Ok(_) => Err(SkillsetError::Convention(
+            "MCP config is not a JSON object".to_string(),
+        )),
+        Err(e) => Err(SkillsetError::Convention(format!(
+            "MCP config is not valid JSON: {}",
+            e
+        ))),
+    }
+}
+
+/// The server called `name` in the config in `content`
+pub fn server(content: &str, name: &str) -> Result<Option<Value>> {
+    let config = parse(content)?;
+    Ok(config
+        .get(SERVERS_KEY)
+        .and_then(|servers| servers.get(name))
+        .cloned())
+}
+
+/// Put `entry` in as the server called `name`, or take it out when `entry`
+/// is `None`. Returns `content` untouched when nothing changes, so files are
+/// not reformatted needlessly.
+pub fn merge(content: &str, name: &str, entry: Option<&str>) -> Result<String> {
+    let mut config = parse(content)?;
+    let servers = match config
+        .entry(SERVERS_KEY)
+        .or_insert_with(|| Value::Object(Map::new()))
+    {
+        Value::Object(servers) => servers,
+        _ => {
+            return Err(SkillsetError::Convention(format!(
+                "{} in the MCP config is not an object",
+                SERVERS_KEY
+            )))
+        }
+    };
+
+    match entry {
+        Some(entry) => {
+            let entry: Value = serde_json::from_str(entry)?;
+            if servers.get(name) == Some(&entry) {
+                return Ok(content.to_string());
+            }
+            servers.insert(name.to_string(), entry);
+        }
+        None => {
+            if servers.shift_remove(name).is_none() {
+                return Ok(content.to_string());
+            }
+        }
+    }
+    Ok(format!("{}\n", serde_json::to_string_pretty(&config)?))
+}
+
+/// Whether no servers and nothing else are left, so the file can be deleted
+pub fn is_empty(content: &str) -> Result<bool> {
+    let config = parse(content)?;
+    Ok(config
+        .iter()
+        .all(|(key, value)| key == SERVERS_KEY && value.as_object().is_some_and(Map::is_empty)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_servers_are_merged_next_to_hand_written_ones() {
+        let original = "{\n  \"mcpServers\": {\n    \"local\": {\n      \"command\": \"./serve\"\n    }\n  },\n  \"theme\": \"dark\"\n}\n";
+
+        let added = merge(original, "github", Some(r#"{"command": "npx"}"#)).unwrap();
+        let config: Value = serde_json::from_str(&added).unwrap();
+        assert_eq!(config["mcpServers"]["local"]["command"], "./serve");
+        assert_eq!(config["mcpServers"]["github"]["command"], "npx");
+        assert_eq!(config["theme"], "dark");
+        assert!(server(&added, "github").unwrap().is_some());
+
+        // Unchanged entries leave the file as it is
+        let again = merge(&added, "github", Some(r#"{"command": "npx"}"#)).unwrap();
+        assert_eq!(again, added);
+
+        let removed = merge(&added, "github", None).unwrap();
+        assert_eq!(removed, original);
+        assert!(!is_empty(&removed).unwrap());
+        assert!(is_empty("{\n  \"mcpServers\": {}\n}\n").unwrap());
+    }
+
+    #[test]
+    fn test_entry_fills_in_inputs_and_the_skill_dir() {
+        let package: McpPackage = serde_json::from_str(
+            r#"{
+                "name": "notes",
+                "command": "node",
+                "args": ["${SKILL_DIR}/server.js"],
+                "env": { "LOG_LEVEL": "info" },
+                "inputs": [{ "name": "SKILLSET_TEST_NOTES_TOKEN" }]
+            }"#,
+        )
+        .unwrap();
+
+        let entry = package
+            .entry(
+                Path::new("/project/skills/mcp/notes"),
+                EnvStyle::Expand,
+                None,
+            )
+            .unwrap();
+        assert_eq!(entry["args"][0], "/project/skills/mcp/notes/server.js");
+        assert_eq!(entry["env"]["LOG_LEVEL"], "info");
+        assert_eq!(
+            entry["env"]["SKILLSET_TEST_NOTES_TOKEN"],
+            "${SKILLSET_TEST_NOTES_TOKEN}"
+        );
+        assert!(entry.get("inputs").is_none());
+
+        let cursor = package
+            .entry(Path::new("/project"), EnvStyle::CursorExpand, None)
+            .unwrap();
+        assert_eq!(
+            cursor["env"]["SKILLSET_TEST_NOTES_TOKEN"],
+            "${env:SKILLSET_TEST_NOTES_TOKEN}"
+        );
+        assert_eq!(package.missing_inputs(), vec!["SKILLSET_TEST_NOTES_TOKEN"]);
+
+        // Values already in the config are not asked for again
+        let current = serde_json::json!({ "env": { "SKILLSET_TEST_NOTES_TOKEN": "secret" } });
+        let desktop = package
+            .entry(Path::new("/project"), EnvStyle::Value, Some(&current))
+            .unwrap();
+        assert_eq!(desktop["env"]["SKILLSET_TEST_NOTES_TOKEN"], "secret");
+    }
+}
@@ -0,0 +1,208 @@
+//! Managed sections in instruction files shared with hand-written content.
+//!
+//! Each skill's text sits between a begin and an end marker naming the
+//! skill. Only the text between markers is ever changed; everything outside
+//! them is left exactly as it was.
+
+use std::path::PathBuf;
+
+use crate::error::{Result, SkillsetError};
+
+/// Text a convention keeps in a shared file for one skill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedSection {
+    /// The file, inside the install target
+    pub path: PathBuf,
+    /// Written at the top when the file is created, e.g. frontmatter
+    pub header: String,
+    pub body: String,
+}
+
+pub fn begin_marker(name: &str) -> String {
+    format!("<!-- skillset:begin {} -->", name)
+}
+
+pub fn end_marker(name: &str) -> String {
+    format!("<!-- skillset:end {} -->", name)
+}
+
+/// Byte range of `name`'s section in `content`, markers and the newline after
+/// the end marker included
+fn find(content: &str, name: &str) -> Result<Option<(usize, usize)>> {
+    let begin = begin_marker(name);
+    let end = end_marker(name);
+    let Some(start) = content.find(&begin) else {
+        return Ok(None);
+    };
+    let Some(end_offset) = content[start..].find(&end) else {
+        return Err(SkillsetError::Convention(format!(
+            "{} has no end marker, fix or remove the section by hand",
+            begin
+        )));
+    };
+
+    let mut stop = start + end_offset + end.len();
+    if content[stop..].starts_with("\r\n") {
+        stop += 2;
+    } else if content[stop..].starts_with('\n') {
+        stop += 1;
+    }
+    Ok(Some((start, stop)))
+}
+
+fn block(name: &str, body: &str) -> String {
+    format!(
+        "{}\n{}\n{}\n",
+        begin_marker(name),
+        body.trim_end(),
+        end_marker(name)
+    )
+}
+
+/// Put `section` in place of `name`'s section, appending it when there is
+/// none yet, or take the section out when `section` is `None`
+pub fn merge(content: &str, name: &str, section: Option<&ManagedSection>) -> Result<String> {
+    let found = find(content, name)?;
+    let merged = match (found, section) {
+        // A file holding nothing but the header and this section is the
+        // skill's own, so its header is rewritten too
+        (Some((start, stop)), Some(section))
+            if !section.header.is_empty()
+                && strip_frontmatter(&content[..start]).trim().is_empty()
+                && content[stop..].trim().is_empty() =>
+        {
+            format!("{}{}", section.header, block(name, &section.body))
+        }
+        (Some((start, stop)), Some(section)) => {
+            format!(
+                "{}{}{}",
+                &content[..start],
+                block(name, &section.body),
+                &content[stop..]
+            )
+        }
+        (Some((start, stop)), None) => {
+            // Drop the blank line that separated the section from the rest
+            let before = &content[..start];
+            let after = &content[stop..];
+            if after.trim().is_empty() {
+                let before = before.trim_end_matches('\n');
+                if before.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}\n", before)
+                }
+            } else {
+                format!("{}{}", before, after.strip_prefix('\n').unwrap_or(after))
+            }
+        }
+        (None, Some(section)) if content.trim().is_empty() => {
+            format!("{}{}", section.header, block(name, &section.body))
+        }
+        (None, Some(section)) => {
+            let separator = if content.ends_with("\n\n") {
+                ""
+            } else if content.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            format!("{}{}{}", content, separator, block(name, &section.body))
+        }
+        (None, None) => content.to_string(),
+    };
+    Ok(merged)
+}
+
+/// Whether nothing but whitespace and frontmatter is left, so the file can
+/// be deleted
+pub fn is_empty(content: &str) -> bool {
+    strip_frontmatter(content).trim().is_empty()
+}
+
+/// `content` after its frontmatter, or all of it when it has none
+fn strip_frontmatter(content: &str) -> &str {
+    content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n").map(|end| &rest[end + 5..]))
+        .unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(body: &str) -> ManagedSection {
+        ManagedSection {
+            path: PathBuf::from("AGENTS.md"),
+            header: String::new(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sections_leave_hand_written_text_alone() {
+        let original = "# Agents\n\nBe nice.\n";
+
+        let added = merge(original, "web", Some(&section("Use React."))).unwrap();
+        assert_eq!(
+            added,
+            "# Agents\n\nBe nice.\n\n<!-- skillset:begin web -->\nUse React.\n<!-- skillset:end web -->\n"
+        );
+
+        let replaced = merge(&added, "web", Some(&section("Use Vue."))).unwrap();
+        assert!(replaced.contains("Use Vue.") && !replaced.contains("Use React."));
+        assert!(replaced.starts_with(original));
+
+        let removed = merge(&replaced, "web", None).unwrap();
+        assert_eq!(removed, original);
+        assert!(!is_empty(&removed));
+    }
+
+    #[test]
+    fn test_frontmatter_only_files_are_empty() {
+        let created = merge(
+            "",
+            "web",
+            Some(&ManagedSection {
+                header: "---\napplyTo: \"*.tsx\"\n---\n".to_string(),
+                ..section("Use React.")
+            }),
+        )
+        .unwrap();
+        assert!(created.starts_with("---\napplyTo: \"*.tsx\"\n---\n<!-- skillset:begin web -->"));
+        assert!(is_empty(&merge(&created, "web", None).unwrap()));
+
+        // A changed header replaces the old one in a file of its own
+        let updated = merge(
+            &created,
+            "web",
+            Some(&ManagedSection {
+                header: "---\napplyTo: \"*.vue\"\n---\n".to_string(),
+                ..section("Use Vue.")
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            updated,
+            "---\napplyTo: \"*.vue\"\n---\n<!-- skillset:begin web -->\nUse Vue.\n<!-- skillset:end web -->\n"
+        );
+
+        // Hand-written text next to the section keeps the header as it is
+        let edited = format!("{}\nKeep this.\n", created);
+        let updated = merge(
+            &edited,
+            "web",
+            Some(&ManagedSection {
+                header: "---\napplyTo: \"*.vue\"\n---\n".to_string(),
+                ..section("Use Vue.")
+            }),
+        )
+        .unwrap();
+        assert!(updated.starts_with("---\napplyTo: \"*.tsx\"\n---\n"));
+        assert!(updated.contains("Use Vue.") && updated.ends_with("Keep this.\n"));
+
+        let broken = "<!-- skillset:begin web -->\nUse React.\n";
+        assert!(merge(broken, "web", None).is_err());
+    }
+}
@@ -45,4 +45,37 @@ pub enum SkillsetError {
 
     #[error("Invalid skill name: {0}")]
     InvalidSkillName(String),
+
+    #[error("Invalid manifest {0}")]
+    InvalidManifest(crate::config::validate::Diagnostic),
+
+    #[error("Verification failed: {0}")]
+    Verification(String),
+
+    #[error("Lock error: {0}")]
+    Lock(String),
+}
+
+impl SkillsetError {
+    /// Stable identifier for the kind of error, used in JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config",
+            Self::Source(_) => "source",
+            Self::Convention(_) => "convention",
+            Self::Io(_) => "io",
+            Self::Git(_) => "git",
+            Self::SerializationError(_) | Self::JsonSerialization(_) => "serialization",
+            Self::Http(_) => "http",
+            Self::Cache(_) => "cache",
+            Self::Oci(_) => "oci",
+            Self::SkillNotFound(_) => "skill_not_found",
+            Self::ConventionNotFound(_) => "convention_not_found",
+            Self::SourceNotFound(_) => "source_not_found",
+            Self::InvalidSkillName(_) => "invalid_skill_name",
+            Self::InvalidManifest(_) => "invalid_manifest",
+            Self::Verification(_) => "verification",
+            Self::Lock(_) => "lock",
+        }
+    }
 }
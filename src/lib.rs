@@ -3,7 +3,9 @@ pub mod cli;
 pub mod config;
 pub mod conventions;
 pub mod error;
+pub mod lock;
 pub mod registry;
+pub mod server;
 pub mod skill;
 pub mod sources;
 
@@ -0,0 +1,223 @@
+//! Advisory file locks coordinating concurrent skillset processes
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant};
+
+use crate::error::{Result, SkillsetError};
+use crate::skill::report::{Event, Reporter};
+
+/// Seconds to wait for a lock before giving up
+pub const TIMEOUT_VAR: &str = "SKILLSET_LOCK_TIMEOUT";
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An exclusive lock on a file, released when the last handle is dropped.
+/// The file records the pid of the holder so waiting processes can say who
+/// they are waiting for.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+    _file: File,
+}
+
+/// Locks held by this process. Locking the same file twice from one process
+/// shares the lock instead of waiting on itself.
+fn held() -> &'static Mutex<HashMap<PathBuf, Weak<FileLock>>> {
+    static HELD: OnceLock<Mutex<HashMap<PathBuf, Weak<FileLock>>>> = OnceLock::new();
+    HELD.get_or_init(Default::default)
+}
+
+impl FileLock {
+    /// Lock `path`, creating it if needed. Waits for other processes up to
+    /// `SKILLSET_LOCK_TIMEOUT` seconds, reporting `Event::Waiting` once.
+    /// Blocks the thread, see `acquire_async` for async code.
+    pub fn acquire(path: &Path, reporter: &dyn Reporter) -> Result<Arc<Self>> {
+        Self::acquire_with_timeout(path, timeout_from_env(), reporter)
+    }
+
+    /// `acquire` on a blocking thread, so waiting does not hold up the async
+    /// runtime
+    pub async fn acquire_async(path: PathBuf, reporter: Arc<dyn Reporter>) -> Result<Arc<Self>> {
+        tokio::task::spawn_blocking(move || Self::acquire(&path, reporter.as_ref()))
+            .await
+            .map_err(|e| SkillsetError::Lock(format!("Task execution failed: {}", e)))?
+    }
+
+    pub fn acquire_with_timeout(
+        path: &Path,
+        timeout: Duration,
+        reporter: &dyn Reporter,
+    ) -> Result<Arc<Self>> {
+        if let Some(lock) = held_here(path) {
+            return Ok(lock);
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let started = Instant::now();
+        let mut announced = false;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+            // Another thread of this process may have taken it meanwhile
+            if let Some(lock) = held_here(path) {
+                return Ok(lock);
+            }
+
+            let holder = holder(&mut file);
+            if started.elapsed() >= timeout {
+                return Err(SkillsetError::Lock(format!(
+                    "timed out after {}s waiting for {}{}",
+                    timeout.as_secs(),
+                    path.display(),
+                    held_by(holder)
+                )));
+            }
+            if !announced {
+                reporter.report(&Event::Waiting {
+                    path: path.to_path_buf(),
+                    pid: holder,
+                });
+                announced = true;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        // Record ourselves as the holder for anyone who has to wait
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+
+        let lock = Arc::new(Self {
+            path: path.to_path_buf(),
+            _file: file,
+        });
+        let mut held = held().lock().unwrap();
+        held.retain(|_, lock| lock.strong_count() > 0);
+        held.insert(path.to_path_buf(), Arc::downgrade(&lock));
+        Ok(lock)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The lock on `path` this process already holds. The map is only locked
+/// while looking, never while waiting on the file.
+fn held_here(path: &Path) -> Option<Arc<FileLock>> {
+    held().lock().unwrap().get(path).and_then(Weak::upgrade)
+}
+
+fn holder(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.rewind().ok()?;
+    file.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
+
+pub(crate) fn held_by(holder: Option<u32>) -> String {
+    holder
+        .map(|pid| format!(" held by pid {}", pid))
+        .unwrap_or_default()
+}
+
+fn timeout_from_env() -> Duration {
+    std::env::var(TIMEOUT_VAR)
+        .ok()
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill::report::SilentReporter;
+
+    #[test]
+    fn test_lock_is_shared_within_a_process_and_times_out_across() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("entry.lock");
+
+        let first = FileLock::acquire_with_timeout(&path, Duration::ZERO, &SilentReporter).unwrap();
+        let second =
+            FileLock::acquire_with_timeout(&path, Duration::ZERO, &SilentReporter).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::process::id().to_string()
+        );
+
+        // Another handle on the file stands in for another process
+        let other = File::open(&path).unwrap();
+        drop((first, second));
+        other.try_lock().unwrap();
+        let error =
+            FileLock::acquire_with_timeout(&path, Duration::ZERO, &SilentReporter).unwrap_err();
+        assert_eq!(error.kind(), "lock");
+        assert!(error
+            .to_string()
+            .contains(&format!("held by pid {}", std::process::id())));
+    }
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<Event>>);
+
+    impl Reporter for Events {
+        fn report(&self, event: &Event) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_waiting_is_reported_and_does_not_hold_up_other_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let busy = dir.path().join("busy.lock");
+        let free = dir.path().join("free.lock");
+        std::fs::write(&busy, "42").unwrap();
+        let other = File::open(&busy).unwrap();
+        other.try_lock().unwrap();
+
+        let events = Arc::new(Events::default());
+        let waiting = {
+            let (busy, events) = (busy.clone(), events.clone());
+            std::thread::spawn(move || {
+                FileLock::acquire_with_timeout(&busy, Duration::from_secs(2), events.as_ref())
+            })
+        };
+        std::thread::sleep(POLL_INTERVAL * 2);
+
+        // Locking something else goes ahead while the other thread waits
+        let started = Instant::now();
+        FileLock::acquire_with_timeout(&free, Duration::ZERO, &SilentReporter).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        drop(other);
+        waiting.join().unwrap().unwrap();
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            vec![Event::Waiting {
+                path: busy,
+                pid: Some(42)
+            }]
+        );
+    }
+}
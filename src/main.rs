@@ -1,17 +1,23 @@
 use clap::Parser;
-use skillset::cli::Cli;
-use tracing_subscriber;
+use skillset::cli::{Cli, OutputFormat};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+    // Initialize tracing, on stderr so logs never mix with results or the
+    // MCP protocol on stdout
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
     // Parse CLI arguments
     let cli = Cli::parse();
+    let format = cli.format;
 
     // Execute command
-    skillset::run(cli)
-        .await
-        .map_err(|e| anyhow::anyhow!("{}", e))
+    match skillset::run(cli).await {
+        Ok(()) => Ok(()),
+        // JSON formats already printed a structured error
+        Err(_) if format != OutputFormat::Text => std::process::exit(1),
+        Err(e) => Err(anyhow::anyhow!("{}", e)),
+    }
 }
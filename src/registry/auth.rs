@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::config::skillset::RegistrySettings;
+use crate::error::{Result, SkillsetError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// `~/.docker/config.json`, or `$DOCKER_CONFIG/config.json` when set
+pub fn docker_config_path() -> Option<PathBuf> {
+    match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => Some(PathBuf::from(dir).join("config.json")),
+        None => dirs::home_dir().map(|home| home.join(".docker").join("config.json")),
+    }
+}
+
+/// Where `skillset login` stores credentials, in the docker config format
+pub fn credentials_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("skillset").join("credentials.json"))
+}
+
+/// The parts of a docker config file we read. Everything else is ignored.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AuthFile {
+    #[serde(default)]
+    auths: BTreeMap<String, AuthEntry>,
+    #[serde(
+        default,
+        rename = "credHelpers",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    cred_helpers: BTreeMap<String, String>,
+    #[serde(
+        default,
+        rename = "credsStore",
+        skip_serializing_if = "Option::is_none"
+    )]
+    creds_store: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AuthEntry {
+    /// base64 of `username:password`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth: Option<String>,
+    #[serde(
+        default,
+        rename = "identitytoken",
+        skip_serializing_if = "Option::is_none"
+    )]
+    identity_token: Option<String>,
+}
+
+impl AuthFile {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            SkillsetError::Config(format!(
+                "Invalid credentials file {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    fn entry(&self, host: &str) -> Option<&AuthEntry> {
+        self.auths
+            .iter()
+            .find(|(key, _)| same_registry(auth_key_host(key), host))
+            .map(|(_, entry)| entry)
+    }
+
+    /// Credentials for `host` from this file: a per-host helper first, then
+    /// a stored entry, then the default credential store
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        if let Some((_, helper)) = self
+            .cred_helpers
+            .iter()
+            .find(|(key, _)| same_registry(key, host))
+        {
+            return run_helper(helper, host);
+        }
+        if let Some(entry) = self.entry(host) {
+            if let Some(credentials) = entry.credentials()? {
+                return Ok(Some(credentials));
+            }
+        }
+        match &self.creds_store {
+            Some(store) => run_helper(store, host),
+            None => Ok(None),
+        }
+    }
+}
+
+impl AuthEntry {
+    fn credentials(&self) -> Result<Option<Credentials>> {
+        if let Some(token) = &self.identity_token {
+            return Ok(Some(Credentials {
+                username: "<token>".to_string(),
+                password: token.clone(),
+            }));
+        }
+        let Some(auth) = &self.auth else {
+            return Ok(None);
+        };
+
+        let decoded = STANDARD
+            .decode(auth)
+            .map_err(|e| SkillsetError::Config(format!("Invalid stored credentials: {}", e)))?;
+        let decoded = String::from_utf8_lossy(&decoded);
+        Ok(decoded
+            .split_once(':')
+            .map(|(username, password)| Credentials {
+                username: username.to_string(),
+                password: password.to_string(),
+            }))
+    }
+}
+
+/// Docker keys entries by URL for Docker Hub (`https://index.docker.io/v1/`)
+fn auth_key_host(key: &str) -> &str {
+    let key = key
+        .strip_prefix("https://")
+        .or_else(|| key.strip_prefix("http://"))
+        .unwrap_or(key);
+    key.split('/').next().unwrap_or(key)
+}
+
+/// Docker Hub answers to several names: references use `docker.io`, pulls
+/// go to `registry-1.docker.io` and docker logs in to `index.docker.io`
+const DOCKER_HUB_HOSTS: &[&str] = &["docker.io", "registry-1.docker.io", "index.docker.io"];
+
+fn same_registry(a: &str, b: &str) -> bool {
+    a == b || (DOCKER_HUB_HOSTS.contains(&a) && DOCKER_HUB_HOSTS.contains(&b))
+}
+
+/// Find credentials for a registry host. A registry's `auth` setting wins;
+/// otherwise `skillset login` credentials, then the docker config.
+pub fn resolve_credentials(host: &str, settings: &RegistrySettings) -> Result<Option<Credentials>> {
+    if let Some(reference) = &settings.auth {
+        return credentials_from_reference(reference, host);
+    }
+
+    if let Some(path) = credentials_path() {
+        if let Some(credentials) = AuthFile::load(&path)?.credentials(host)? {
+            return Ok(Some(credentials));
+        }
+    }
+    match docker_config_path() {
+        Some(path) => AuthFile::load(&path)?.credentials(host),
+        None => Ok(None),
+    }
+}
+
+/// Resolve an `auth` setting: `env:VAR` (holding `user:password` or a bare
+/// token), `helper:<name>` for `docker-credential-<name>`, or `docker` for
+/// the docker config only
+fn credentials_from_reference(reference: &str, host: &str) -> Result<Option<Credentials>> {
+    if let Some(var) = reference.strip_prefix("env:") {
+        let value = std::env::var(var).map_err(|_| {
+            SkillsetError::Config(format!(
+                "Registry {} authenticates with ${}, which is not set",
+                host, var
+            ))
+        })?;
+        let credentials = match value.split_once(':') {
+            Some((username, password)) => Credentials {
+                username: username.to_string(),
+                password: password.to_string(),
+            },
+            None => Credentials {
+                username: "token".to_string(),
+                password: value,
+            },
+        };
+        return Ok(Some(credentials));
+    }
+    if let Some(helper) = reference.strip_prefix("helper:") {
+        return run_helper(helper, host);
+    }
+    if reference == "docker" {
+        return match docker_config_path() {
+            Some(path) => AuthFile::load(&path)?.credentials(host),
+            None => Ok(None),
+        };
+    }
+
+    Err(SkillsetError::Config(format!(
+        "Unknown auth reference for {}: {}. Expected env:<VAR>, helper:<name> or docker",
+        host, reference
+    )))
+}
+
+/// Ask `docker-credential-<helper>` for the credentials of `host`
+fn run_helper(helper: &str, host: &str) -> Result<Option<Credentials>> {
+    #[derive(Deserialize)]
+    struct HelperOutput {
+        #[serde(rename = "Username")]
+        username: String,
+        #[serde(rename = "Secret")]
+        secret: String,
+    }
+
+    let program = format!("docker-credential-{}", helper);
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SkillsetError::Config(format!("Failed to run {}: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(host.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stdout);
+        // Helpers report unknown hosts this way rather than with empty output
+        if message.contains("credentials not found") {
+            return Ok(None);
+        }
+        return Err(SkillsetError::Config(format!(
+            "{} failed for {}: {}",
+            program,
+            host,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let output: HelperOutput = serde_json::from_slice(&output.stdout)?;
+    Ok(Some(Credentials {
+        username: output.username,
+        password: output.secret,
+    }))
+}
+
+/// Check `credentials` against the registry, then store them. Returns the
+/// file written.
+pub async fn login(
+    host: &str,
+    settings: &RegistrySettings,
+    credentials: &Credentials,
+) -> Result<PathBuf> {
+    crate::registry::client::OciClient::for_registry(host, settings)?
+        .with_credentials(credentials.clone())
+        .ping()
+        .await?;
+    store_credentials(host, credentials)
+}
+
+/// Save credentials for `skillset login`. Returns the file written.
+pub fn store_credentials(host: &str, credentials: &Credentials) -> Result<PathBuf> {
+    let path = credentials_path()
+        .ok_or_else(|| SkillsetError::Config("No config directory found".to_string()))?;
+    let mut file = AuthFile::load(&path)?;
+    let auth = STANDARD.encode(format!("{}:{}", credentials.username, credentials.password));
+    file.auths.insert(
+        host.to_string(),
+        AuthEntry {
+            auth: Some(auth),
+            identity_token: None,
+        },
+    );
+    write_private(&path, &file)?;
+    Ok(path)
+}
+
+/// Forget credentials saved by `skillset login`. Returns whether any were.
+pub fn remove_credentials(host: &str) -> Result<bool> {
+    let Some(path) = credentials_path() else {
+        return Ok(false);
+    };
+    let mut file = AuthFile::load(&path)?;
+    let keys: Vec<String> = file
+        .auths
+        .keys()
+        .filter(|key| same_registry(auth_key_host(key), host))
+        .cloned()
+        .collect();
+    if keys.is_empty() {
+        return Ok(false);
+    }
+
+    for key in keys {
+        file.auths.remove(&key);
+    }
+    write_private(&path, &file)?;
+    Ok(true)
+}
+
+/// Write a credentials file readable only by the user
+fn write_private(path: &Path, file: &AuthFile) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| SkillsetError::SerializationError(e.to_string()))?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Created private, so the credentials are never readable by others
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // A file left by an older version may still be readable by others
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// A parsed `WWW-Authenticate` header, e.g.
+/// `Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:a/b:pull"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub scheme: String,
+    pub params: BTreeMap<String, String>,
+}
+
+impl Challenge {
+    pub fn parse(header: &str) -> Option<Self> {
+        let header = header.trim();
+        let (scheme, rest) = header.split_once(' ').unwrap_or((header, ""));
+        if scheme.is_empty() {
+            return None;
+        }
+
+        let mut params = BTreeMap::new();
+        let mut rest = rest.trim();
+        while !rest.is_empty() {
+            let (key, after) = rest.split_once('=')?;
+            let after = after.trim_start();
+            let (value, remaining) = match after.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted.find('"')?;
+                    (&quoted[..end], &quoted[end + 1..])
+                }
+                None => after.split_once(',').unwrap_or((after, "")),
+            };
+            params.insert(key.trim().to_ascii_lowercase(), value.to_string());
+            rest = remaining.trim_start().trim_start_matches(',').trim_start();
+        }
+
+        Some(Self {
+            scheme: scheme.to_ascii_lowercase(),
+            params,
+        })
+    }
+}
+
+/// Bearer tokens by registry and scope, shared by every client of a run so
+/// each scope is only negotiated once until it expires
+#[derive(Debug, Default)]
+pub struct TokenCache {
+    tokens: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl TokenCache {
+    pub fn get(&self, key: &str) -> Option<String> {
+        let tokens = self.tokens.lock().ok()?;
+        tokens
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(token, _)| token.clone())
+    }
+
+    pub fn insert(&self, key: String, token: String, lifetime: Duration) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(key, (token, Instant::now() + lifetime));
+        }
+    }
+
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bearer_challenge() {
+        let challenge = Challenge::parse(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:acme/web:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(challenge.scheme, "bearer");
+        assert_eq!(challenge.params["realm"], "https://ghcr.io/token");
+        assert_eq!(challenge.params["scope"], "repository:acme/web:pull,push");
+
+        let challenge = Challenge::parse("Basic realm=registry").unwrap();
+        assert_eq!(challenge.scheme, "basic");
+        assert_eq!(challenge.params["realm"], "registry");
+    }
+
+    #[test]
+    fn test_docker_config_entries_and_helpers() {
+        let file: AuthFile = serde_json::from_str(
+            r#"{
+                "auths": {
+                    "https://index.docker.io/v1/": { "auth": "dXNlcjpwYXNz" },
+                    "ghcr.io": { "identitytoken": "abc" }
+                },
+                "credHelpers": { "gcr.io": "gcloud" },
+                "experimental": "enabled"
+            }"#,
+        )
+        .unwrap();
+
+        // Docker Hub entries match the hosts references and pulls use
+        for host in ["docker.io", "registry-1.docker.io", "index.docker.io"] {
+            assert_eq!(
+                file.credentials(host).unwrap(),
+                Some(Credentials {
+                    username: "user".to_string(),
+                    password: "pass".to_string(),
+                })
+            );
+        }
+        assert_eq!(
+            file.credentials("ghcr.io").unwrap().unwrap().password,
+            "abc"
+        );
+        assert_eq!(file.credentials("quay.io").unwrap(), None);
+        assert_eq!(file.cred_helpers["gcr.io"], "gcloud");
+    }
+
+    #[test]
+    fn test_env_auth_reference() {
+        std::env::set_var("SKILLSET_TEST_REGISTRY_TOKEN", "robot:s3cret");
+        let credentials =
+            credentials_from_reference("env:SKILLSET_TEST_REGISTRY_TOKEN", "registry.acme")
+                .unwrap()
+                .unwrap();
+        assert_eq!(credentials.username, "robot");
+        assert_eq!(credentials.password, "s3cret");
+
+        assert!(credentials_from_reference("env:SKILLSET_TEST_UNSET_TOKEN", "r").is_err());
+        assert!(credentials_from_reference("vault:foo", "r").is_err());
+    }
+
+    #[test]
+    fn test_token_cache_expires() {
+        let cache = TokenCache::default();
+        cache.insert(
+            "a".to_string(),
+            "token".to_string(),
+            Duration::from_secs(60),
+        );
+        cache.insert("b".to_string(), "old".to_string(), Duration::ZERO);
+        assert_eq!(cache.get("a").as_deref(), Some("token"));
+        assert_eq!(cache.get("b"), None);
+    }
+}
@@ -1,46 +1,406 @@
-use crate::error::Result;
-use reqwest::Client;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::skillset::RegistrySettings;
+use crate::error::{Result, SkillsetError};
+use crate::registry::auth::{resolve_credentials, Challenge, Credentials, TokenCache};
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Manifest media types we can read, in order of preference
+pub const MANIFEST_MEDIA_TYPES: [&str; 2] = [
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+
+/// The parts of an image manifest needed to copy and unpack a skill
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    #[serde(default)]
+    pub media_type: Option<String>,
+    #[serde(default)]
+    pub config: Option<Descriptor>,
+    #[serde(default)]
+    pub layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+/// A manifest exactly as the registry served it, with its digest
+#[derive(Debug, Clone)]
+pub struct PulledManifest {
+    pub bytes: Vec<u8>,
+    pub digest: String,
+    pub media_type: String,
+}
+
+impl PulledManifest {
+    pub fn parse(&self) -> Result<Manifest> {
+        Ok(serde_json::from_slice(&self.bytes)?)
+    }
+}
+
+/// Connection settings and bearer tokens for every registry a run talks to
+#[derive(Debug, Clone, Default)]
+pub struct Registries {
+    pub settings: BTreeMap<String, RegistrySettings>,
+    pub tokens: Arc<TokenCache>,
+}
+
+impl Registries {
+    pub fn new(settings: BTreeMap<String, RegistrySettings>) -> Self {
+        Self {
+            settings,
+            tokens: Arc::default(),
+        }
+    }
+
+    pub fn settings(&self, host: &str) -> RegistrySettings {
+        self.settings.get(host).cloned().unwrap_or_default()
+    }
+
+    pub fn client(&self, host: &str) -> Result<OciClient> {
+        Ok(OciClient::for_registry(host, &self.settings(host))?.with_tokens(self.tokens.clone()))
+    }
+}
 
 pub struct OciClient {
     client: Client,
+    host: String,
     registry_url: String,
+    settings: RegistrySettings,
+    tokens: Arc<TokenCache>,
+    /// Used instead of looking credentials up, e.g. by `skillset login`
+    credentials: Option<Credentials>,
 }
 
 impl OciClient {
     pub fn new(registry_url: &str) -> Result<Self> {
+        let host = registry_url
+            .split_once("://")
+            .map_or(registry_url, |(_, host)| host);
         Ok(Self {
             client: Client::new(),
-            registry_url: registry_url.to_string(),
+            host: host.trim_end_matches('/').to_string(),
+            registry_url: registry_url.trim_end_matches('/').to_string(),
+            settings: RegistrySettings::default(),
+            tokens: Arc::default(),
+            credentials: None,
+        })
+    }
+
+    /// Client for a registry host using its configured connection settings
+    pub fn for_registry(host: &str, settings: &RegistrySettings) -> Result<Self> {
+        let client = Client::builder()
+            .danger_accept_invalid_certs(settings.insecure)
+            .build()?;
+
+        Ok(Self {
+            client,
+            host: host.to_string(),
+            registry_url: settings.base_url(host),
+            settings: settings.clone(),
+            tokens: Arc::default(),
+            credentials: None,
+        })
+    }
+
+    /// Share bearer tokens with other clients
+    pub fn with_tokens(mut self, tokens: Arc<TokenCache>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Check that the registry accepts our credentials
+    pub async fn ping(&self) -> Result<()> {
+        let url = format!("{}/v2/", self.registry_url);
+        let response = self.send("", |client| client.get(&url)).await?;
+        check_status(response, &url).await?;
+        Ok(())
+    }
+
+    /// Fetch a manifest by tag or digest. When requested by digest the
+    /// content is verified against it.
+    pub async fn pull_manifest(&self, repository: &str, reference: &str) -> Result<PulledManifest> {
+        let url = format!(
+            "{}/v2/{}/manifests/{}",
+            self.registry_url, repository, reference
+        );
+        let response = self
+            .send(&pull_scope(repository), |client| {
+                client
+                    .get(&url)
+                    .header(header::ACCEPT, MANIFEST_MEDIA_TYPES.join(", "))
+            })
+            .await?;
+        let response = check_status(response, &url).await?;
+
+        let media_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or(MANIFEST_MEDIA_TYPES[0])
+            .to_string();
+        let bytes = response.bytes().await?.to_vec();
+        let digest = sha256_digest(&bytes);
+
+        if reference.starts_with("sha256:") {
+            verify_digest(reference, &digest, &url)?;
+        }
+
+        Ok(PulledManifest {
+            bytes,
+            digest,
+            media_type,
         })
     }
 
-    pub async fn pull_manifest(&self, reference: &str) -> Result<String> {
-        // Parse reference to get repository and tag
-        let (repo, tag) = self.parse_reference(reference)?;
+    /// Download a blob, verifying it matches its digest
+    pub async fn pull_blob(&self, repository: &str, digest: &str) -> Result<Vec<u8>> {
+        let url = format!("{}/v2/{}/blobs/{}", self.registry_url, repository, digest);
+        let response = self
+            .send(&pull_scope(repository), |client| client.get(&url))
+            .await?;
+        let response = check_status(response, &url).await?;
+
+        let bytes = response.bytes().await?.to_vec();
+        verify_digest(digest, &sha256_digest(&bytes), &url)?;
+        Ok(bytes)
+    }
+
+    /// Upload a blob unless the registry already has it
+    pub async fn push_blob(&self, repository: &str, digest: &str, data: Vec<u8>) -> Result<()> {
+        let scope = push_scope(repository);
+        let url = format!("{}/v2/{}/blobs/{}", self.registry_url, repository, digest);
+        let existing = self.send(&scope, |client| client.head(&url)).await?;
+        if existing.status().is_success() {
+            return Ok(());
+        }
+
+        // Monolithic upload: start a session, then PUT the whole blob to it
+        let uploads = format!("{}/v2/{}/blobs/uploads/", self.registry_url, repository);
+        let response = self.send(&scope, |client| client.post(&uploads)).await?;
+        let response = check_status(response, &uploads).await?;
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| {
+                SkillsetError::Oci(format!("{} returned no upload location", uploads))
+            })?;
 
-        // Construct the manifest URL
-        let manifest_url = format!("{}/v2/{}/manifests/{}", self.registry_url, repo, tag);
+        let location = if location.starts_with('/') {
+            format!("{}{}", self.registry_url, location)
+        } else {
+            location.to_string()
+        };
+        let separator = if location.contains('?') { '&' } else { '?' };
+        let upload_url = format!("{}{}digest={}", location, separator, digest);
 
-        // TODO: Implement OCI authentication and manifest fetching
-        println!("Would fetch manifest from: {}", manifest_url);
-        Ok("manifest".to_string())
+        let response = self
+            .send(&scope, |client| {
+                client
+                    .put(&upload_url)
+                    .header(header::CONTENT_TYPE, "application/octet-stream")
+                    .body(data.clone())
+            })
+            .await?;
+        check_status(response, &upload_url).await?;
+        Ok(())
     }
 
-    pub async fn pull_blob(&self, digest: &str) -> Result<Vec<u8>> {
-        // TODO: Implement blob downloading
-        todo!("Implement OCI blob downloading")
+    /// Upload a manifest under a tag or digest, byte for byte so its digest
+    /// is unchanged
+    pub async fn push_manifest(
+        &self,
+        repository: &str,
+        reference: &str,
+        manifest: &PulledManifest,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/v2/{}/manifests/{}",
+            self.registry_url, repository, reference
+        );
+        let response = self
+            .send(&push_scope(repository), |client| {
+                client
+                    .put(&url)
+                    .header(header::CONTENT_TYPE, &manifest.media_type)
+                    .body(manifest.bytes.clone())
+            })
+            .await?;
+        check_status(response, &url).await?;
+        Ok(())
     }
 
-    fn parse_reference(&self, reference: &str) -> Result<(String, String)> {
-        // Parse "repository:tag" format
-        let parts: Vec<&str> = reference.split(':').collect();
-        if parts.len() != 2 {
-            return Err(crate::error::SkillsetError::Oci(format!(
-                "Invalid reference format: {}",
-                reference
+    /// Send a request, answering an authentication challenge if the registry
+    /// responds with one. Bearer tokens are cached per registry and scope.
+    async fn send<F>(&self, scope: &str, build: F) -> Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let key = format!("{}|{}", self.host, scope);
+        if let Some(token) = self.tokens.get(&key) {
+            let response = build(&self.client).bearer_auth(&token).send().await?;
+            if response.status() != StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
+            self.tokens.remove(&key);
+        }
+
+        let response = build(&self.client).send().await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let Some(challenge) = response
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Challenge::parse)
+        else {
+            return Ok(response);
+        };
+
+        let credentials = match &self.credentials {
+            Some(credentials) => Some(credentials.clone()),
+            None => resolve_credentials(&self.host, &self.settings)?,
+        };
+        match challenge.scheme.as_str() {
+            "basic" => match credentials {
+                Some(credentials) => Ok(build(&self.client)
+                    .basic_auth(&credentials.username, Some(&credentials.password))
+                    .send()
+                    .await?),
+                None => Ok(response),
+            },
+            "bearer" => {
+                let (token, lifetime) = self
+                    .fetch_token(&challenge, scope, credentials.as_ref())
+                    .await?;
+                self.tokens.insert(key, token.clone(), lifetime);
+                Ok(build(&self.client).bearer_auth(&token).send().await?)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Exchange credentials (or nothing, for anonymous pulls) for a bearer
+    /// token at the realm named by the challenge
+    async fn fetch_token(
+        &self,
+        challenge: &Challenge,
+        scope: &str,
+        credentials: Option<&Credentials>,
+    ) -> Result<(String, Duration)> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            #[serde(default)]
+            token: Option<String>,
+            #[serde(default)]
+            access_token: Option<String>,
+            #[serde(default)]
+            expires_in: Option<u64>,
+        }
+
+        let realm = challenge.params.get("realm").ok_or_else(|| {
+            SkillsetError::Oci(format!(
+                "{} sent a bearer challenge without a realm",
+                self.host
+            ))
+        })?;
+        let mut query = Vec::new();
+        if let Some(service) = challenge.params.get("service") {
+            query.push(("service", service.as_str()));
+        }
+        let scope = challenge
+            .params
+            .get("scope")
+            .map(String::as_str)
+            .unwrap_or(scope);
+        if !scope.is_empty() {
+            query.push(("scope", scope));
+        }
+
+        let mut request = self.client.get(realm).query(&query);
+        if let Some(credentials) = credentials {
+            request = request.basic_auth(&credentials.username, Some(&credentials.password));
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(SkillsetError::Oci(format!(
+                "Token request to {} failed with {}{}",
+                realm,
+                response.status(),
+                if credentials.is_none() {
+                    format!(", run `skillset login {}`", self.host)
+                } else {
+                    String::new()
+                }
             )));
         }
 
-        Ok((parts[0].to_string(), parts[1].to_string()))
+        let body: TokenResponse = response.json().await?;
+        let token = body.token.or(body.access_token).ok_or_else(|| {
+            SkillsetError::Oci(format!("Token response from {} has no token", realm))
+        })?;
+        // The spec's default lifetime when none is given
+        let lifetime = Duration::from_secs(body.expires_in.unwrap_or(60));
+        Ok((token, lifetime))
     }
 }
+
+fn pull_scope(repository: &str) -> String {
+    format!("repository:{}:pull", repository)
+}
+
+fn push_scope(repository: &str) -> String {
+    format!("repository:{}:pull,push", repository)
+}
+
+/// `sha256:<hex>` digest of some content
+pub fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+fn verify_digest(expected: &str, actual: &str, url: &str) -> Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(SkillsetError::Oci(format!(
+            "Digest mismatch for {}: expected {}, got {}",
+            url, expected, actual
+        )))
+    }
+}
+
+async fn check_status(response: reqwest::Response, url: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let message = match status {
+        StatusCode::NOT_FOUND => "not found".to_string(),
+        StatusCode::UNAUTHORIZED => "authentication required".to_string(),
+        StatusCode::FORBIDDEN => "access denied".to_string(),
+        _ => response.text().await.unwrap_or_default(),
+    };
+    Err(SkillsetError::Oci(format!(
+        "{} returned {}: {}",
+        url, status, message
+    )))
+}
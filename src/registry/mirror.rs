@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use crate::config::skillset::RegistrySettings;
+use crate::error::{Result, SkillsetError};
+use crate::registry::client::{Descriptor, PulledManifest, Registries};
+use crate::registry::reference::OciReference;
+
+/// One place an artifact can be pulled from: a mirror or the registry itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub repository: String,
+    pub settings: RegistrySettings,
+}
+
+/// A manifest and every blob it references, already digest-verified
+#[derive(Debug, Clone)]
+pub struct PulledArtifact {
+    pub manifest: PulledManifest,
+    pub blobs: Vec<(Descriptor, Vec<u8>)>,
+    /// Host the artifact was pulled from
+    pub host: String,
+}
+
+/// Where `reference` lives on a mirror given as `host[/prefix]`
+pub fn mirror_endpoint(
+    mirror: &str,
+    reference: &OciReference,
+    registries: &BTreeMap<String, RegistrySettings>,
+) -> Endpoint {
+    let mirror = mirror.trim_end_matches('/');
+    let (host, repository) = match mirror.split_once('/') {
+        Some((host, prefix)) => (host, format!("{}/{}", prefix, reference.repository)),
+        None => (mirror, reference.repository.clone()),
+    };
+
+    Endpoint {
+        host: host.to_string(),
+        repository,
+        settings: registries.get(host).cloned().unwrap_or_default(),
+    }
+}
+
+pub fn upstream_endpoint(
+    reference: &OciReference,
+    registries: &BTreeMap<String, RegistrySettings>,
+) -> Endpoint {
+    Endpoint {
+        host: reference.host.clone(),
+        repository: reference.repository.clone(),
+        settings: registries.get(&reference.host).cloned().unwrap_or_default(),
+    }
+}
+
+/// The registry's mirrors in configured order, followed by the registry itself
+pub fn endpoints(
+    reference: &OciReference,
+    registries: &BTreeMap<String, RegistrySettings>,
+) -> Vec<Endpoint> {
+    let upstream = upstream_endpoint(reference, registries);
+    let mut endpoints: Vec<Endpoint> = upstream
+        .settings
+        .mirrors
+        .iter()
+        .map(|mirror| mirror_endpoint(mirror, reference, registries))
+        .collect();
+    endpoints.push(upstream);
+    endpoints
+}
+
+/// Pull from the first endpoint that serves the artifact. Everything pulled
+/// is checked against its digest, and when the reference pins a manifest
+/// digest a mirror serving anything else is skipped.
+pub async fn pull(reference: &OciReference, registries: &Registries) -> Result<PulledArtifact> {
+    let mut failures = Vec::new();
+
+    for endpoint in endpoints(reference, &registries.settings) {
+        match pull_from(&endpoint, reference, registries).await {
+            Ok(artifact) => return Ok(artifact),
+            Err(e) => failures.push(format!("{}: {}", endpoint.host, e)),
+        }
+    }
+
+    Err(SkillsetError::Oci(format!(
+        "Could not pull {} from any registry:\n  {}",
+        reference,
+        failures.join("\n  ")
+    )))
+}
+
+pub async fn pull_from(
+    endpoint: &Endpoint,
+    reference: &OciReference,
+    registries: &Registries,
+) -> Result<PulledArtifact> {
+    let client = registries.client(&endpoint.host)?;
+    let manifest = client
+        .pull_manifest(&endpoint.repository, reference.manifest_reference())
+        .await?;
+
+    if let Some(expected) = &reference.digest {
+        if &manifest.digest != expected {
+            return Err(SkillsetError::Oci(format!(
+                "served manifest {} but {} was expected",
+                manifest.digest, expected
+            )));
+        }
+    }
+
+    let parsed = manifest.parse()?;
+    let mut blobs = Vec::new();
+    for descriptor in parsed.config.into_iter().chain(parsed.layers) {
+        let data = client
+            .pull_blob(&endpoint.repository, &descriptor.digest)
+            .await?;
+        blobs.push((descriptor, data));
+    }
+
+    Ok(PulledArtifact {
+        manifest,
+        blobs,
+        host: endpoint.host.clone(),
+    })
+}
+
+/// Copy an artifact from its upstream registry to a mirror, keeping its tag
+/// and digest. Returns the manifest digest.
+pub async fn sync(
+    reference: &OciReference,
+    mirror: &str,
+    registries: &Registries,
+) -> Result<String> {
+    let upstream = upstream_endpoint(reference, &registries.settings);
+    let artifact = pull_from(&upstream, reference, registries).await?;
+
+    let target = mirror_endpoint(mirror, reference, &registries.settings);
+    let client = registries.client(&target.host)?;
+    for (descriptor, data) in artifact.blobs {
+        client
+            .push_blob(&target.repository, &descriptor.digest, data)
+            .await?;
+    }
+
+    let tag = reference
+        .tag
+        .as_deref()
+        .unwrap_or(&artifact.manifest.digest);
+    client
+        .push_manifest(&target.repository, tag, &artifact.manifest)
+        .await?;
+
+    Ok(artifact.manifest.digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_try_mirrors_first() {
+        let mut registries = BTreeMap::new();
+        registries.insert(
+            "ghcr.io".to_string(),
+            RegistrySettings {
+                mirrors: vec![
+                    "mirror.internal/ghcr".to_string(),
+                    "localhost:5000".to_string(),
+                ],
+                ..Default::default()
+            },
+        );
+        registries.insert(
+            "localhost:5000".to_string(),
+            RegistrySettings {
+                http: true,
+                ..Default::default()
+            },
+        );
+
+        let reference = OciReference::parse("ghcr.io/skillset/web:v1").unwrap();
+        let endpoints = endpoints(&reference, &registries);
+        let locations: Vec<(&str, &str)> = endpoints
+            .iter()
+            .map(|e| (e.host.as_str(), e.repository.as_str()))
+            .collect();
+
+        assert_eq!(
+            locations,
+            vec![
+                ("mirror.internal", "ghcr/skillset/web"),
+                ("localhost:5000", "skillset/web"),
+                ("ghcr.io", "skillset/web"),
+            ]
+        );
+        assert!(endpoints[1].settings.http);
+    }
+}
@@ -1,5 +1,8 @@
+pub mod auth;
 pub mod client;
+pub mod mirror;
 pub mod publish;
+pub mod reference;
 
 pub use client::OciClient;
 pub use publish::OciPublisher;
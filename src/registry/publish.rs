@@ -1,27 +1,68 @@
+use serde_json::json;
+
 use crate::error::Result;
-use std::path::Path;
+use crate::registry::client::{sha256_digest, PulledManifest, Registries, MANIFEST_MEDIA_TYPES};
+use crate::registry::reference::OciReference;
+use crate::skill::package::PackageFilter;
+
+/// Media type of the empty config blob every published skill carries
+pub const CONFIG_MEDIA_TYPE: &str = "application/vnd.skillset.config.v1+json";
 
+/// Media type of the single layer holding the skill's files
+pub const LAYER_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+/// Pushes skills as OCI artifacts that `OciSource` can install: an empty
+/// config and one gzipped tarball of the files the package ships
 pub struct OciPublisher {
-    // TODO: Add OCI publishing client configuration
+    registries: Registries,
 }
 
 impl OciPublisher {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(registries: Registries) -> Self {
+        Self { registries }
     }
 
+    /// Push the files `package` ships to `reference`, tagged with its tag
+    /// when it has one. Returns the manifest digest.
     pub async fn publish_skill(
         &self,
-        skill_path: &Path,
-        reference: &str,
-        registry: &str,
+        package: &PackageFilter,
+        reference: &OciReference,
     ) -> Result<String> {
-        // 1. Read skill metadata
-        // 2. Create OCI artifact manifest
-        // 3. Upload skill files as layers
-        // 4. Push manifest to registry
-        // 5. Return published reference
+        let layer = package.archive()?;
+        let config = b"{}".to_vec();
+        let manifest = json!({
+            "schemaVersion": 2,
+            "mediaType": MANIFEST_MEDIA_TYPES[0],
+            "config": {
+                "mediaType": CONFIG_MEDIA_TYPE,
+                "digest": sha256_digest(&config),
+                "size": config.len(),
+            },
+            "layers": [{
+                "mediaType": LAYER_MEDIA_TYPE,
+                "digest": sha256_digest(&layer),
+                "size": layer.len(),
+            }],
+        });
+        let bytes = serde_json::to_vec(&manifest)?;
+        let manifest = PulledManifest {
+            digest: sha256_digest(&bytes),
+            bytes,
+            media_type: MANIFEST_MEDIA_TYPES[0].to_string(),
+        };
+
+        let client = self.registries.client(&reference.host)?;
+        for blob in [config, layer] {
+            client
+                .push_blob(&reference.repository, &sha256_digest(&blob), blob)
+                .await?;
+        }
+        let tag = reference.tag.as_deref().unwrap_or(&manifest.digest);
+        client
+            .push_manifest(&reference.repository, tag, &manifest)
+            .await?;
 
-        todo!("Implement OCI skill publishing")
+        Ok(manifest.digest)
     }
 }
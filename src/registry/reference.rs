@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::error::{Result, SkillsetError};
+
+/// A parsed `host/repository[:tag][@digest]` reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OciReference {
+    pub host: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl OciReference {
+    pub fn parse(reference: &str) -> Result<Self> {
+        let reference = reference.strip_prefix("oci:").unwrap_or(reference);
+        let invalid = || SkillsetError::Oci(format!("Invalid OCI reference: {}", reference));
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) => (name, Some(digest.to_string())),
+            None => (reference, None),
+        };
+        let (host, path) = name.split_once('/').ok_or_else(invalid)?;
+
+        // A colon after the last slash separates the tag; one before it
+        // belongs to the host's port
+        let (repository, tag) = match path.rsplit_once(':') {
+            Some((repository, tag)) if !tag.contains('/') => {
+                (repository.to_string(), Some(tag.to_string()))
+            }
+            _ => (path.to_string(), None),
+        };
+
+        if host.is_empty() || repository.is_empty() || (tag.is_none() && digest.is_none()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    /// Tag or digest to request the manifest by, preferring the digest
+    pub fn manifest_reference(&self) -> &str {
+        self.digest
+            .as_deref()
+            .or(self.tag.as_deref())
+            .unwrap_or("latest")
+    }
+
+    /// Last path segment of the repository, used as the skill name
+    pub fn name(&self) -> &str {
+        self.repository
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.repository)
+    }
+
+    pub fn with_digest(&self, digest: &str) -> Self {
+        Self {
+            digest: Some(digest.to_string()),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for OciReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.host, self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag_digest_and_port() {
+        let reference = OciReference::parse("oci:ghcr.io/skillset/web:v1.0.0").unwrap();
+        assert_eq!(reference.host, "ghcr.io");
+        assert_eq!(reference.repository, "skillset/web");
+        assert_eq!(reference.tag.as_deref(), Some("v1.0.0"));
+        assert_eq!(reference.name(), "web");
+
+        let reference = OciReference::parse("localhost:5000/acme/web@sha256:abc").unwrap();
+        assert_eq!(reference.host, "localhost:5000");
+        assert_eq!(reference.tag, None);
+        assert_eq!(reference.manifest_reference(), "sha256:abc");
+        assert_eq!(reference.to_string(), "localhost:5000/acme/web@sha256:abc");
+
+        assert!(OciReference::parse("ghcr.io/skillset/web").is_err());
+        assert!(OciReference::parse("web:v1").is_err());
+    }
+}
@@ -0,0 +1,529 @@
+//! `skillset serve --mcp`: the installed skills over the Model Context
+//! Protocol, for agents that do not read skill directories from disk.
+//!
+//! Messages are JSON-RPC 2.0, one per line on stdin and stdout. Each skill's
+//! files are offered as resources and its instructions as a prompt, next to
+//! tools that list, search and add skills. Every request builds a fresh
+//! `SkillManager`, so changes made by other skillset runs show up right away
+//! and the project lock is only held while a skill is being added.
+
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::conventions::SkillFrontmatter;
+use crate::error::{Result, SkillsetError};
+use crate::skill::manager::SkillManager;
+use crate::skill::report::InstalledSkill;
+
+/// The protocol revision this server speaks
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const URI_SCHEME: &str = "skill://";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Builds the manager each request is answered from
+pub type ManagerFactory = Box<dyn Fn() -> Result<SkillManager> + Send + Sync>;
+
+pub struct McpServer {
+    manager: ManagerFactory,
+}
+
+/// A failed request, answered with a JSON-RPC error
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<SkillsetError> for RpcError {
+    fn from(error: SkillsetError) -> Self {
+        Self {
+            code: INTERNAL_ERROR,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// An installed skill's directory and the file holding its instructions
+struct SkillDir {
+    skill: InstalledSkill,
+    dir: PathBuf,
+    instructions: Option<PathBuf>,
+}
+
+impl SkillDir {
+    /// The instructions with any frontmatter taken off, and the description
+    /// the frontmatter gives
+    fn read_instructions(&self) -> Result<Option<(Option<String>, String)>> {
+        let Some(path) = &self.instructions else {
+            return Ok(None);
+        };
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(match SkillFrontmatter::parse(&content) {
+            Ok((frontmatter, body)) => (frontmatter.description, body.to_string()),
+            Err(_) => (None, content),
+        }))
+    }
+}
+
+impl McpServer {
+    pub fn new(manager: ManagerFactory) -> Self {
+        Self { manager }
+    }
+
+    /// Answer requests from `input` on `output` until `input` is closed
+    pub async fn serve<R, W>(&self, input: R, mut output: W) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = input.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(message).await,
+                Err(e) => Some(error_response(
+                    Value::Null,
+                    RpcError {
+                        code: PARSE_ERROR,
+                        message: e.to_string(),
+                    },
+                )),
+            };
+            if let Some(response) = response {
+                let mut encoded = serde_json::to_vec(&response)?;
+                encoded.push(b'\n');
+                output.write_all(&encoded).await?;
+                output.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The response to one message, or `None` for notifications
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "resources": {}, "prompts": {}, "tools": {} },
+                "serverInfo": { "name": "skillset", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "resources/list" => self.list_resources(),
+            "resources/read" => self.read_resource(&params),
+            "prompts/list" => self.list_prompts(),
+            "prompts/get" => self.get_prompt(&params),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unknown method {}", method),
+            }),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn skill_dirs(&self) -> Result<Vec<SkillDir>> {
+        let manager = (self.manager)()?;
+        let root = manager.install_root().to_path_buf();
+        Ok(manager
+            .installed_skills()
+            .into_iter()
+            .filter_map(|skill| {
+                let dir = root.join(skill.path.as_ref()?);
+                if !dir.is_dir() {
+                    return None;
+                }
+                let instructions = [
+                    "SKILL.md".to_string(),
+                    "INSTRUCTIONS.md".to_string(),
+                    format!("{}.mdc", skill.name),
+                    "README.md".to_string(),
+                ]
+                .into_iter()
+                .map(|file| dir.join(file))
+                .find(|path| path.is_file());
+                Some(SkillDir {
+                    skill,
+                    dir,
+                    instructions,
+                })
+            })
+            .collect())
+    }
+
+    fn skill_dir(&self, name: &str) -> std::result::Result<SkillDir, RpcError> {
+        self.skill_dirs()?
+            .into_iter()
+            .find(|skill| skill.skill.name == name)
+            .ok_or_else(|| RpcError::invalid_params(format!("No installed skill {}", name)))
+    }
+
+    fn list_resources(&self) -> std::result::Result<Value, RpcError> {
+        let mut resources = Vec::new();
+        for skill in self.skill_dirs()? {
+            for file in files(&skill.dir)? {
+                let relative = file
+                    .strip_prefix(&skill.dir)
+                    .unwrap_or(&file)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                resources.push(json!({
+                    "uri": format!("{}{}/{}", URI_SCHEME, skill.skill.name, relative),
+                    "name": format!("{}/{}", skill.skill.name, relative),
+                    "mimeType": mime_type(&file),
+                }));
+            }
+        }
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn read_resource(&self, params: &Value) -> std::result::Result<Value, RpcError> {
+        let uri = string_param(params, "uri")?;
+        let (name, relative) = uri
+            .strip_prefix(URI_SCHEME)
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(|| RpcError::invalid_params(format!("Unknown resource {}", uri)))?;
+        let relative = Path::new(relative);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(RpcError::invalid_params(format!(
+                "Unknown resource {}",
+                uri
+            )));
+        }
+
+        let path = self.skill_dir(name)?.dir.join(relative);
+        let content = std::fs::read(&path)
+            .map_err(|_| RpcError::invalid_params(format!("Unknown resource {}", uri)))?;
+        let mut contents = json!({ "uri": uri, "mimeType": mime_type(&path) });
+        match String::from_utf8(content) {
+            Ok(text) => contents["text"] = json!(text),
+            Err(e) => {
+                contents["blob"] =
+                    json!(base64::engine::general_purpose::STANDARD.encode(e.into_bytes()))
+            }
+        }
+        Ok(json!({ "contents": [contents] }))
+    }
+
+    fn list_prompts(&self) -> std::result::Result<Value, RpcError> {
+        let mut prompts = Vec::new();
+        for skill in self.skill_dirs()? {
+            let Some((description, _)) = skill.read_instructions()? else {
+                continue;
+            };
+            prompts.push(json!({
+                "name": skill.skill.name,
+                "description": description,
+            }));
+        }
+        Ok(json!({ "prompts": prompts }))
+    }
+
+    fn get_prompt(&self, params: &Value) -> std::result::Result<Value, RpcError> {
+        let name = string_param(params, "name")?;
+        let skill = self.skill_dir(name)?;
+        let (description, body) = skill
+            .read_instructions()?
+            .ok_or_else(|| RpcError::invalid_params(format!("{} has no instructions", name)))?;
+        Ok(json!({
+            "description": description,
+            "messages": [{
+                "role": "user",
+                "content": { "type": "text", "text": body },
+            }],
+        }))
+    }
+
+    async fn call_tool(&self, params: &Value) -> std::result::Result<Value, RpcError> {
+        let name = string_param(params, "name")?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        let result = match name {
+            "list_skills" => self.list_skills(),
+            "search_skills" => self.search_skills(string_param(&arguments, "query")?),
+            "add_skill" => {
+                let optional = |key: &str| {
+                    arguments
+                        .get(key)
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                };
+                self.add_skill(
+                    string_param(&arguments, "reference")?,
+                    optional("convention"),
+                    optional("version"),
+                )
+                .await
+            }
+            _ => return Err(RpcError::invalid_params(format!("Unknown tool {}", name))),
+        };
+
+        // Failures are reported to the agent as the tool's result
+        Ok(match result {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": e.to_string() }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn list_skills(&self) -> Result<String> {
+        let skills = (self.manager)()?.installed_skills();
+        Ok(serde_json::to_string_pretty(&skills)?)
+    }
+
+    /// Installed skills whose name, description or instructions mention
+    /// every word of `query`
+    fn search_skills(&self, query: &str) -> Result<String> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut found = Vec::new();
+        for skill in self.skill_dirs()? {
+            let (description, body) = skill.read_instructions()?.unwrap_or_default();
+            let haystack = format!(
+                "{}\n{}\n{}",
+                skill.skill.name,
+                description.as_deref().unwrap_or(""),
+                body
+            )
+            .to_lowercase();
+            if words.iter().all(|word| haystack.contains(word.as_str())) {
+                found.push(json!({
+                    "name": skill.skill.name,
+                    "description": description,
+                    "path": skill.skill.path,
+                }));
+            }
+        }
+        Ok(serde_json::to_string_pretty(&found)?)
+    }
+
+    async fn add_skill(
+        &self,
+        reference: &str,
+        convention: Option<String>,
+        version: Option<String>,
+    ) -> Result<String> {
+        let mut manager = (self.manager)()?.with_lock().await?;
+        let skill = manager
+            .add(reference, convention, Vec::new(), version)
+            .await?;
+        Ok(serde_json::to_string_pretty(&skill)?)
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn string_param<'a>(params: &'a Value, key: &str) -> std::result::Result<&'a str, RpcError> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::invalid_params(format!("Missing {}", key)))
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_skills",
+            "description": "List the skills installed in this project",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "search_skills",
+            "description": "Find installed skills whose name, description or instructions mention every word of the query",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "add_skill",
+            "description": "Install a skill and add it to the manifest",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "reference": {
+                        "type": "string",
+                        "description": "A skill name, name@version, or a git:, oci: or archive reference",
+                    },
+                    "convention": { "type": "string" },
+                    "version": { "type": "string" },
+                },
+                "required": ["reference"],
+            },
+        },
+    ])
+}
+
+/// Every file under `dir`, in a stable order, leaving out `.git`
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut entries = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() && entry.file_name() != ".git" {
+            found.extend(files(&path)?);
+        } else if file_type.is_file() {
+            found.push(path);
+        }
+    }
+    Ok(found)
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("md" | "mdc") => "text/markdown",
+        Some("json") => "application/json",
+        Some("yaml" | "yml") => "application/yaml",
+        Some("py") => "text/x-python",
+        Some("sh") => "text/x-shellscript",
+        Some("png") => "image/png",
+        Some("pdf") => "application/pdf",
+        _ => "text/plain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> (tempfile::TempDir, McpServer) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("skillset.json"),
+            r#"{ "skills": { "pdf": { "version": "1.0.0", "source": "git:https://example.com/pdf" } } }"#,
+        )
+        .unwrap();
+        let skill = dir.path().join("skills/agent-skills/pdf");
+        std::fs::create_dir_all(skill.join("references")).unwrap();
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Fill in PDF forms\n---\nUse scripts/fill.py.\n",
+        )
+        .unwrap();
+        std::fs::write(skill.join("references/fields.md"), "# Fields\n").unwrap();
+        std::fs::write(
+            dir.path().join("skillset.lock"),
+            r#"{ "version": 1, "skills": { "pdf": {
+                "version": "1.0.0", "source": "git:https://example.com/pdf",
+                "convention": "agent-skills", "path": "skills/agent-skills/pdf",
+                "checksum": "sha256:0" } } }"#,
+        )
+        .unwrap();
+
+        let root = dir.path().to_path_buf();
+        let server = McpServer::new(Box::new(move || SkillManager::new(root.clone())));
+        (dir, server)
+    }
+
+    async fn call(server: &McpServer, method: &str, params: Value) -> Value {
+        let response = server
+            .handle(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .await
+            .unwrap();
+        assert!(response.get("error").is_none(), "{}", response);
+        response["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_skills_are_resources_and_prompts() {
+        let (_dir, server) = project();
+
+        let resources = call(&server, "resources/list", json!({})).await;
+        let uris: Vec<&str> = resources["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|resource| resource["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uris,
+            vec!["skill://pdf/SKILL.md", "skill://pdf/references/fields.md"]
+        );
+
+        let read = call(
+            &server,
+            "resources/read",
+            json!({ "uri": "skill://pdf/references/fields.md" }),
+        )
+        .await;
+        assert_eq!(read["contents"][0]["text"], "# Fields\n");
+
+        let prompt = call(&server, "prompts/get", json!({ "name": "pdf" })).await;
+        assert_eq!(prompt["description"], "Fill in PDF forms");
+        assert_eq!(
+            prompt["messages"][0]["content"]["text"],
+            "Use scripts/fill.py.\n"
+        );
+
+        let found = call(
+            &server,
+            "tools/call",
+            json!({ "name": "search_skills", "arguments": { "query": "PDF forms" } }),
+        )
+        .await;
+        assert!(found["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("\"name\": \"pdf\""));
+    }
+
+    #[tokio::test]
+    async fn test_resources_outside_a_skill_cannot_be_read() {
+        let (_dir, server) = project();
+
+        let response = server
+            .handle(json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "resources/read",
+                "params": { "uri": "skill://pdf/../../../skillset.json" },
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        // Notifications are not answered
+        assert!(server
+            .handle(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await
+            .is_none());
+    }
+}
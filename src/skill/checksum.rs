@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+
+/// Compute a stable checksum over a directory tree.
+///
+/// Entries are visited in sorted order and both relative paths and file
+/// contents are hashed, so renames are detected as well as edits. Whether a
+/// file is executable and where symlinks point are hashed too.
+pub fn hash_dir(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_entries(path, path, &mut hasher)?;
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+fn hash_entries(root: &Path, dir: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        // Normalize separators so checksums match across platforms
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        if file_type.is_dir() {
+            hasher.update(b"d\0");
+            hasher.update(relative.as_bytes());
+            hasher.update(b"\0");
+            hash_entries(root, &path, hasher)?;
+        } else if file_type.is_symlink() {
+            let target = std::fs::read_link(&path)?;
+            hasher.update(b"l\0");
+            hasher.update(relative.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(target.to_string_lossy().replace('\\', "/").as_bytes());
+            hasher.update(b"\0");
+        } else if file_type.is_file() {
+            let content = std::fs::read(&path)?;
+            hasher.update(if is_executable(&entry.metadata()?) {
+                b"x\0"
+            } else {
+                b"f\0"
+            });
+            hasher.update(relative.as_bytes());
+            hasher.update(b"\0");
+            hasher.update((content.len() as u64).to_le_bytes());
+            hasher.update(&content);
+        }
+    }
+
+    Ok(())
+}
+
+/// Only the executable bits count. The rest of the mode depends on the umask
+/// of whoever installed the skill, and would make checksums differ between
+/// machines.
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hash_is_stable() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("SKILL.md"), "# Skill").unwrap();
+        std::fs::create_dir(temp_dir.path().join("scripts")).unwrap();
+        std::fs::write(temp_dir.path().join("scripts/run.sh"), "echo hi").unwrap();
+
+        let first = hash_dir(temp_dir.path()).unwrap();
+        let second = hash_dir(temp_dir.path()).unwrap();
+        assert_eq!(first, second);
+        assert!(first.starts_with("sha256:"));
+    }
+
+    #[test]
+    fn test_hash_detects_edits_and_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("SKILL.md");
+        std::fs::write(&file, "# Skill").unwrap();
+        let original = hash_dir(temp_dir.path()).unwrap();
+
+        std::fs::write(&file, "# Edited skill").unwrap();
+        let edited = hash_dir(temp_dir.path()).unwrap();
+        assert_ne!(original, edited);
+
+        std::fs::write(&file, "# Skill").unwrap();
+        std::fs::rename(&file, temp_dir.path().join("README.md")).unwrap();
+        let renamed = hash_dir(temp_dir.path()).unwrap();
+        assert_ne!(original, renamed);
+
+        #[cfg(unix)]
+        detects_modes_and_links();
+    }
+
+    /// Executable bits and symlinks installed as they are
+    #[cfg(unix)]
+    fn detects_modes_and_links() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let temp_dir = TempDir::new().unwrap();
+        let script = temp_dir.path().join("run.sh");
+        std::fs::write(&script, "echo hi").unwrap();
+        std::fs::write(temp_dir.path().join("other.sh"), "echo other").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let link = temp_dir.path().join("latest.sh");
+        symlink("run.sh", &link).unwrap();
+        let original = hash_dir(temp_dir.path()).unwrap();
+
+        // Only the executable bits matter, not the umask
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o775)).unwrap();
+        assert_eq!(hash_dir(temp_dir.path()).unwrap(), original);
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert_ne!(hash_dir(temp_dir.path()).unwrap(), original);
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        std::fs::remove_file(&link).unwrap();
+        symlink("other.sh", &link).unwrap();
+        assert_ne!(hash_dir(temp_dir.path()).unwrap(), original);
+
+        std::fs::remove_file(&link).unwrap();
+        assert_ne!(hash_dir(temp_dir.path()).unwrap(), original);
+        symlink("run.sh", &link).unwrap();
+        assert_eq!(hash_dir(temp_dir.path()).unwrap(), original);
+    }
+}
@@ -1,54 +1,426 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::config::skillset::SkillsetConfig;
-use crate::conventions::ConventionRegistry;
+use crate::cache::CachePaths;
+use crate::config::format::{find_manifest, manifest_path, ManifestFormat};
+use crate::config::layered::{self, ConfigLayer, IntegrityPolicy};
+use crate::config::lockfile::{LockedSkill, LockedTarget, Lockfile, LOCKFILE_NAME};
+use crate::config::skillset::{SkillConfig, SkillsetConfig};
+use crate::config::workspace::Workspace;
+use crate::conventions::convert::{self, Format};
+use crate::conventions::mcp;
+use crate::conventions::sections::{self, ManagedSection};
+use crate::conventions::{Convention, ConventionRegistry};
 use crate::error::Result;
-use crate::skill::FetchedSkill;
+use crate::lock::FileLock;
+use crate::registry::client::Registries;
+use crate::registry::mirror;
+use crate::registry::publish::OciPublisher;
+use crate::registry::reference::OciReference;
+use crate::skill::checksum::hash_dir;
+use crate::skill::package::{PackageFilter, DEFAULT_EXCLUDE};
+use crate::skill::plan::{FileDiff, Plan, PlanStep};
+use crate::skill::reference::{is_simplified_skill_reference, parse_skill_reference};
+use crate::skill::report::{
+    Event, InstallReport, InstalledSkill, MirrorReport, PublishReport, Reporter, SilentReporter,
+    SkillInfo, SkippedSkill, SyncedSkill,
+};
+use crate::skill::transaction::{write_atomically, Transaction};
+use crate::skill::verify::{self, VerifyReport};
+use crate::skill::{FetchedSkill, InstallScope, InstallTarget};
+use crate::sources::oci::OciSource;
 use crate::sources::SourceRegistry;
 
 pub struct SkillManager {
     convention_registry: ConventionRegistry,
     config: SkillsetConfig,
-    project_path: PathBuf,
+    lockfile: Lockfile,
+    /// Directory holding the skillset manifest
+    manifest_dir: PathBuf,
+    /// skillset.lock, shared with the root for workspace members
+    lock_path: PathBuf,
+    /// This project's key in the root lockfile when it is a workspace member
+    workspace_member: Option<String>,
+    target: InstallTarget,
     source_registry: SourceRegistry,
+    /// Where sources cache what they fetch, a scratch directory for dry runs
+    cache: CachePaths,
+    reporter: Arc<dyn Reporter>,
+    dry_run: Option<DryRun>,
+    pending: Option<Pending>,
+    /// Held for the manager's lifetime once `with_lock` is used
+    project_lock: Option<Arc<FileLock>>,
+    /// The files of each skill that are installed, converted to another
+    /// convention's layout where needed, and the copies dry runs organize.
+    /// Kept until the manager is dropped as dry runs hash them when locking.
+    prepared: Mutex<Vec<tempfile::TempDir>>,
+}
+
+/// Next to the lockfile, so workspace members share the root's lock
+const PROJECT_LOCK_NAME: &str = ".skillset-lock";
+
+/// A command in progress: its staged changes, the state to restore if it
+/// fails and the progress to report once it succeeded
+struct Pending {
+    _lock: Arc<FileLock>,
+    transaction: Transaction,
+    config: SkillsetConfig,
+    lockfile: Lockfile,
+    save_config: AtomicBool,
+    save_lockfile: AtomicBool,
+    events: Vec<Event>,
+}
+
+/// Changes a dry run would have made, recorded instead of applied
+struct DryRun {
+    /// Cache the skills are fetched into, deleted with the manager
+    _scratch: tempfile::TempDir,
+    steps: Mutex<Vec<PlanStep>>,
+    /// Content the manifest, lockfile and instruction files would be
+    /// written with
+    files: Mutex<BTreeMap<PathBuf, String>>,
+}
+
+/// Where an organized skill's files are now for one convention, and the
+/// instruction files it has a section in
+struct Placement {
+    convention: String,
+    path: PathBuf,
+    sections: Vec<PathBuf>,
 }
 
 impl SkillManager {
     pub fn new(project_path: PathBuf) -> Result<Self> {
-        let config = Self::load_config(&project_path)?;
-        let mut convention_registry = ConventionRegistry::new();
+        let workspace = Workspace::discover(&project_path)?;
+        Self::with_workspace(project_path, workspace.as_ref())
+    }
 
-        // Sources manage their own caching
-        let source_registry = SourceRegistry::new()?;
+    /// Manage a project that may belong to an already loaded workspace
+    pub fn with_workspace(project_path: PathBuf, workspace: Option<&Workspace>) -> Result<Self> {
+        let target = InstallTarget::project(project_path.clone());
+        let member = workspace.and_then(|ws| Some((ws, ws.member_name(&project_path)?)));
 
-        // Register only enabled conventions
-        let enabled_conventions = config.get_conventions();
+        let Some((workspace, member_name)) = member else {
+            return Self::with_target(project_path, target);
+        };
 
-        if enabled_conventions.contains(&"autogpt".to_string()) {
-            convention_registry.register(Box::new(crate::conventions::AutoGptConvention::new()));
-        }
-        if enabled_conventions.contains(&"langchain".to_string()) {
-            convention_registry.register(Box::new(crate::conventions::LangchainConvention::new()));
-        }
-        if enabled_conventions.contains(&"agent-skills".to_string()) {
-            convention_registry
-                .register(Box::new(crate::conventions::AgentSkillsConvention::new()));
+        // Members inherit settings from the root and record into its lockfile
+        let mut config = Self::load_config(&project_path)?;
+        config.layers.below.push(workspace.inherited_layer()?);
+        let lock_path = workspace.root.join(LOCKFILE_NAME);
+        let root_lockfile = Lockfile::load_from_file(&lock_path)?;
+        let lockfile = Lockfile {
+            skills: root_lockfile
+                .members
+                .get(&member_name)
+                .cloned()
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+
+        Self::from_parts(
+            config,
+            lockfile,
+            project_path,
+            lock_path,
+            Some(member_name),
+            target,
+        )
+    }
+
+    /// Manage user-level skills, recorded in the skillset config directory
+    /// and installed into the home directory
+    pub fn new_global() -> Result<Self> {
+        Self::with_target(Self::global_manifest_dir()?, InstallTarget::global()?)
+    }
+
+    pub fn global_manifest_dir() -> Result<PathBuf> {
+        dirs::config_dir()
+            .map(|dir| dir.join("skillset"))
+            .ok_or_else(|| {
+                crate::error::SkillsetError::Config("No config directory found".to_string())
+            })
+    }
+
+    fn with_target(manifest_dir: PathBuf, target: InstallTarget) -> Result<Self> {
+        let config = Self::load_config(&manifest_dir)?;
+        let lock_path = manifest_dir.join(LOCKFILE_NAME);
+        let lockfile = Lockfile::load_from_file(&lock_path)?;
+        Self::from_parts(config, lockfile, manifest_dir, lock_path, None, target)
+    }
+
+    fn from_parts(
+        mut config: SkillsetConfig,
+        lockfile: Lockfile,
+        manifest_dir: PathBuf,
+        lock_path: PathBuf,
+        workspace_member: Option<String>,
+        target: InstallTarget,
+    ) -> Result<Self> {
+        // Environment variables override the manifest, the user config backs it up
+        config.layers.above.extend(layered::env_layers());
+        if let Some(user_layer) = layered::user_layer()? {
+            config.layers.below.push(user_layer);
         }
+        let convention_registry = Self::build_convention_registry(&config);
+        let cache = CachePaths::new()?;
+        let reporter: Arc<dyn Reporter> = Arc::new(SilentReporter);
+        let source_registry = Self::build_source_registry(&config, &cache, &reporter)?;
 
         Ok(Self {
             convention_registry,
             config,
-            project_path,
+            lockfile,
+            manifest_dir,
+            lock_path,
+            workspace_member,
+            target,
             source_registry,
+            cache,
+            reporter,
+            dry_run: None,
+            pending: None,
+            prepared: Mutex::new(Vec::new()),
+            project_lock: None,
         })
     }
 
-    fn load_config(project_path: &PathBuf) -> Result<SkillsetConfig> {
-        let config_path = project_path.join("skillset.json");
-        if config_path.exists() {
-            SkillsetConfig::load_from_file(&config_path)
-        } else {
-            Ok(SkillsetConfig::default())
+    /// Apply command line overrides, which take precedence over everything else
+    pub fn with_overrides(mut self, overrides: Vec<ConfigLayer>) -> Self {
+        if overrides.is_empty() {
+            return self;
+        }
+        self.config.layers.above.splice(0..0, overrides);
+        self.convention_registry = Self::build_convention_registry(&self.config);
+        self
+    }
+
+    /// Send progress events to `reporter` instead of discarding them. Keys
+    /// of the manifest that loading ignored are reported to it right away.
+    pub fn with_reporter(mut self, reporter: Arc<dyn Reporter>) -> Result<Self> {
+        if let Some(config_path) = find_manifest(&self.manifest_dir)? {
+            let content = std::fs::read_to_string(&config_path)?;
+            for diagnostic in crate::config::validate::unknown_fields(&config_path, &content)? {
+                reporter.report(&Event::Warning(diagnostic));
+            }
+        }
+        self.source_registry = Self::build_source_registry(&self.config, &self.cache, &reporter)?;
+        self.reporter = reporter;
+        Ok(self)
+    }
+
+    /// Work out what would change without touching the project, the
+    /// manifest, the lockfile or the cache. Skills are still fetched to
+    /// resolve their versions, into a scratch cache. See `plan`.
+    pub fn with_dry_run(mut self) -> Result<Self> {
+        let scratch = tempfile::tempdir()?;
+        self.cache = CachePaths::at(scratch.path().to_path_buf());
+        self.source_registry =
+            Self::build_source_registry(&self.config, &self.cache, &self.reporter)?;
+        self.dry_run = Some(DryRun {
+            _scratch: scratch,
+            steps: Mutex::new(Vec::new()),
+            files: Mutex::new(BTreeMap::new()),
+        });
+        Ok(self)
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    /// Everything a dry run would have done so far, with the manifest and
+    /// lockfile changes as diffs against what is on disk. Empty unless the
+    /// manager was created `with_dry_run`.
+    pub fn plan(&self) -> Result<Plan> {
+        let Some(dry_run) = &self.dry_run else {
+            return Ok(Plan::default());
+        };
+
+        let current_dir = std::env::current_dir()?;
+        let mut files = Vec::new();
+        for (path, content) in dry_run.files.lock().unwrap().iter() {
+            let current = match std::fs::read_to_string(path) {
+                Ok(current) => current,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e.into()),
+            };
+            let shown = path.strip_prefix(&current_dir).unwrap_or(path);
+            files.extend(FileDiff::between(shown.to_path_buf(), &current, content));
+        }
+
+        Ok(Plan {
+            steps: dry_run.steps.lock().unwrap().clone(),
+            files,
+        })
+    }
+
+    fn plan_step(&self, step: PlanStep) {
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.steps.lock().unwrap().push(step);
+        }
+    }
+
+    /// Write a manifest or lockfile, or only remember its content in a dry run
+    fn write_file(&self, path: &Path, content: String) -> Result<()> {
+        if let Some(dry_run) = &self.dry_run {
+            dry_run
+                .files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), content);
+            return Ok(());
+        }
+        write_atomically(path, content.as_bytes())
+    }
+
+    /// Hold the project lock until the manager is dropped, so other skillset
+    /// processes wait instead of changing the project underneath it. The
+    /// manifest and lockfile are read again once the lock is held, keeping
+    /// the layers applied on top of the manifest.
+    pub async fn with_lock(mut self) -> Result<Self> {
+        self.project_lock =
+            Some(FileLock::acquire_async(self.project_lock_path(), self.reporter.clone()).await?);
+
+        if let Some(config_path) = find_manifest(&self.manifest_dir)? {
+            let layers = std::mem::take(&mut self.config.layers);
+            self.config = SkillsetConfig::load_from_file(&config_path)?;
+            self.config.layers = layers;
+            self.convention_registry = Self::build_convention_registry(&self.config);
+        }
+        let lockfile = Lockfile::load_from_file(&self.lock_path)?;
+        self.lockfile.skills = match &self.workspace_member {
+            Some(member) => lockfile.members.get(member).cloned().unwrap_or_default(),
+            None => lockfile.skills,
+        };
+        Ok(self)
+    }
+
+    fn project_lock_path(&self) -> PathBuf {
+        self.lock_path.with_file_name(PROJECT_LOCK_NAME)
+    }
+
+    /// Start staging changes under the project lock. Returns false when a
+    /// dry run or an enclosing call is already in charge, so nested calls
+    /// share one transaction.
+    async fn begin(&mut self) -> Result<bool> {
+        if self.dry_run.is_some() || self.pending.is_some() {
+            return Ok(false);
+        }
+
+        let lock = FileLock::acquire_async(self.project_lock_path(), self.reporter.clone()).await?;
+        self.pending = Some(Pending {
+            _lock: lock,
+            transaction: Transaction::new(&self.target),
+            config: self.config.clone(),
+            lockfile: self.lockfile.clone(),
+            save_config: AtomicBool::new(false),
+            save_lockfile: AtomicBool::new(false),
+            events: Vec::new(),
+        });
+        Ok(true)
+    }
+
+    /// Commit what `begin` started if `result` is a success, roll it back
+    /// otherwise
+    fn finish<T>(&mut self, began: bool, result: Result<T>) -> Result<T> {
+        if !began {
+            return result;
+        }
+        match result {
+            Ok(value) => self.commit().map(|()| value),
+            Err(e) => {
+                self.rollback();
+                Err(e)
+            }
+        }
+    }
+
+    /// Move staged skills into place, then write the manifest and lockfile.
+    /// They are rendered now rather than when saved so a shared workspace
+    /// lockfile picks up members committed in the meantime.
+    fn commit(&mut self) -> Result<()> {
+        let Some(mut pending) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let mut staged = Ok(());
+        if pending.save_config.load(Ordering::SeqCst) {
+            staged = self
+                .render_config()
+                .map(|(path, content)| pending.transaction.write(&path, content));
+        }
+        if staged.is_ok() && pending.save_lockfile.load(Ordering::SeqCst) {
+            staged = self
+                .render_lockfile()
+                .map(|content| pending.transaction.write(&self.lock_path, content));
+        }
+
+        if let Err(e) = staged.and_then(|()| pending.transaction.commit()) {
+            self.config = pending.config;
+            self.lockfile = pending.lockfile;
+            return Err(e);
+        }
+        for event in &pending.events {
+            self.reporter.report(event);
+        }
+        Ok(())
+    }
+
+    /// Discard staged changes and restore the manifest and lockfile
+    fn rollback(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            self.config = pending.config;
+            self.lockfile = pending.lockfile;
+        }
+    }
+
+    /// Report progress, held back until the current transaction commits
+    fn report(&mut self, event: Event) {
+        match &mut self.pending {
+            Some(pending) => pending.events.push(event),
+            None => self.reporter.report(&event),
+        }
+    }
+
+    fn build_source_registry(
+        config: &SkillsetConfig,
+        cache: &CachePaths,
+        reporter: &Arc<dyn Reporter>,
+    ) -> Result<SourceRegistry> {
+        // Sources manage their own caching
+        let mut source_registry = SourceRegistry::with_cache(cache.clone(), reporter.clone())?;
+        source_registry.register(Box::new(
+            OciSource::with_cache(cache.clone(), config.merged_registries())?
+                .with_reporter(reporter.clone()),
+        ));
+        Ok(source_registry)
+    }
+
+    fn build_convention_registry(config: &SkillsetConfig) -> ConventionRegistry {
+        let mut convention_registry = ConventionRegistry::new();
+
+        // Register only enabled conventions
+        let enabled_conventions = config.get_conventions();
+
+        for convention in crate::conventions::builtin_conventions() {
+            if enabled_conventions
+                .iter()
+                .any(|name| name == convention.name())
+            {
+                convention_registry.register(convention);
+            }
+        }
+
+        convention_registry
+    }
+
+    fn load_config(project_path: &Path) -> Result<SkillsetConfig> {
+        match find_manifest(project_path)? {
+            Some(config_path) => SkillsetConfig::load_from_file(&config_path),
+            None => Ok(SkillsetConfig::default()),
         }
     }
 
@@ -60,79 +432,397 @@ impl SkillManager {
         &self.config
     }
 
+    /// The manifest this manager reads and writes
+    pub fn manifest_path(&self) -> Result<PathBuf> {
+        manifest_path(&self.manifest_dir)
+    }
+
+    /// Directory installed skill paths are relative to: the project, or the
+    /// home directory for global skills
+    pub fn install_root(&self) -> &Path {
+        &self.target.root
+    }
+
+    pub fn scope(&self) -> InstallScope {
+        self.target.scope
+    }
+
+    /// Write the manifest, or once the current transaction commits
     pub fn save_config(&self) -> Result<()> {
-        let config_path = self.project_path.join("skillset.json");
-        self.config.save_to_file(&config_path)
+        if let Some(pending) = &self.pending {
+            pending.save_config.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        let (config_path, content) = self.render_config()?;
+        self.write_file(&config_path, content)
+    }
+
+    fn render_config(&self) -> Result<(PathBuf, String)> {
+        // Keep writing whichever format the project already uses
+        let config_path = manifest_path(&self.manifest_dir)?;
+        let content = ManifestFormat::from_path(&config_path).render(&self.config)?;
+        Ok((config_path, content))
+    }
+
+    pub fn lockfile(&self) -> &Lockfile {
+        &self.lockfile
+    }
+
+    /// Write the lockfile, or once the current transaction commits
+    pub fn save_lockfile(&self) -> Result<()> {
+        if let Some(pending) = &self.pending {
+            pending.save_lockfile.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        self.write_file(&self.lock_path, self.render_lockfile()?)
+    }
+
+    fn render_lockfile(&self) -> Result<String> {
+        // Re-read so entries of other workspace members are preserved
+        let mut lockfile = Lockfile::load_from_file(&self.lock_path)?;
+        match &self.workspace_member {
+            Some(member) if self.lockfile.skills.is_empty() => {
+                lockfile.members.remove(member);
+            }
+            Some(member) => {
+                lockfile
+                    .members
+                    .insert(member.clone(), self.lockfile.skills.clone());
+            }
+            None => lockfile.skills = self.lockfile.skills.clone(),
+        }
+        lockfile.render()
+    }
+
+    /// Add a skill given either a registry name with a version
+    /// (`file-analyzer@1.0.0`, `@user/skill@2.0.0`) or an explicit source.
+    /// With `targets`, the skill is installed for each of those conventions.
+    /// Nothing is changed unless the skill installs successfully.
+    pub async fn add(
+        &mut self,
+        reference: &str,
+        convention: Option<String>,
+        targets: Vec<String>,
+        version: Option<String>,
+    ) -> Result<InstalledSkill> {
+        let began = self.begin().await?;
+        let result = self
+            .add_reference(reference, convention, targets, version)
+            .await;
+        self.finish(began, result)
     }
 
+    async fn add_reference(
+        &mut self,
+        reference: &str,
+        convention: Option<String>,
+        targets: Vec<String>,
+        version: Option<String>,
+    ) -> Result<InstalledSkill> {
+        if !is_simplified_skill_reference(reference) {
+            return self
+                .add_skill(reference, convention, targets, version)
+                .await;
+        }
+
+        let (skill_name, skill_version) = parse_skill_reference(reference)?;
+        let skill_config = if version.is_some() || !targets.is_empty() {
+            // Version or targets were given on the command line
+            SkillConfig::Detailed {
+                version: version.unwrap_or_else(|| skill_version.to_string()),
+                source: None,
+                convention,
+                targets: (!targets.is_empty()).then_some(targets),
+                integrity: None,
+            }
+        } else {
+            SkillConfig::Simple(skill_version.to_string())
+        };
+
+        self.config
+            .skills
+            .insert(skill_name.clone(), skill_config.clone());
+        self.save_config()?;
+
+        self.install_declared(&skill_name, &skill_config, &mut HashMap::new())
+            .await
+    }
+
+    /// Add a skill from an explicit git, OCI or local source
     pub async fn add_skill(
         &mut self,
         reference: &str,
         convention: Option<String>,
+        targets: Vec<String>,
         version: Option<String>,
-    ) -> Result<()> {
+    ) -> Result<InstalledSkill> {
+        let began = self.begin().await?;
+        let result = self
+            .add_from_source(reference, convention, targets, version)
+            .await;
+        self.finish(began, result)
+    }
+
+    async fn add_from_source(
+        &mut self,
+        reference: &str,
+        convention: Option<String>,
+        targets: Vec<String>,
+        version: Option<String>,
+    ) -> Result<InstalledSkill> {
         // Parse reference to determine source type
-        let (source_type, source_ref, skill_name) = self.parse_reference(reference)?;
+        let (source_type, source_ref, _skill_name) = self.parse_reference(reference)?;
 
         // Fetch skill from source
         let fetched_skill = self.fetch_skill(&source_type, &source_ref, version).await?;
+        let source = Self::source_reference(&source_type, &source_ref);
+        let locked_source = Self::locked_source(&source_type, &source_ref, &fetched_skill);
+        // The manifest keeps an archive's integrity hash in its own field
+        let (source, integrity) = match source.split_once('#') {
+            Some((url, integrity)) if source_type == "archive" => {
+                (url.to_string(), Some(integrity.to_string()))
+            }
+            _ => (source, None),
+        };
+        self.plan_step(PlanStep::Fetch {
+            name: fetched_skill.name.clone(),
+            version: fetched_skill.version.clone(),
+            source: locked_source.clone(),
+        });
 
-        // Detect or use specified convention
-        let convention_name = if let Some(conv) = convention {
-            conv
+        // Install for the requested targets, or the project's, or the
+        // detected convention
+        let project_targets = self.config.get_targets();
+        let declared = match (&convention, targets.is_empty()) {
+            (_, false) => targets.clone(),
+            (Some(convention), true) => vec![convention.clone()],
+            (None, true) => project_targets.clone(),
+        };
+        let conventions = self.resolve_targets(declared, &fetched_skill).await?;
+        self.remove_stale_targets(&fetched_skill.name, &conventions)?;
+        let placed = self
+            .organize_targets(&fetched_skill.name, &fetched_skill, &conventions)
+            .await?;
+
+        // The manifest records the convention only when it is not left to
+        // the project's targets
+        let (convention, targets) = if !targets.is_empty() {
+            (None, Some(targets))
+        } else if convention.is_none() && !project_targets.is_empty() {
+            (None, None)
         } else {
-            self.detect_convention(&fetched_skill.source_path).await?
+            (Some(conventions[0].clone()), None)
+        };
+        let entry = SkillConfig::Detailed {
+            version: fetched_skill.version.clone(),
+            source: Some(source),
+            convention,
+            targets,
+            integrity,
         };
+        self.update_config(&fetched_skill, entry, &locked_source, &placed)?;
 
-        // Organize skill according to convention
-        self.organize_skill(&fetched_skill, &convention_name)
-            .await?;
+        Ok(self.report_installed(&fetched_skill.name))
+    }
+
+    /// Install every skill declared in the manifest that is not already
+    /// installed at its locked version
+    pub async fn install_all(&mut self) -> Result<InstallReport> {
+        let began = self.begin().await?;
+        let result = self.install_all_shared(&mut HashMap::new()).await;
+        self.finish(began, result)
+    }
 
-        // Update configuration
-        self.update_config(&fetched_skill, &convention_name)?;
+    /// Install the root and every member of a workspace. Skills resolving to
+    /// the same source are fetched once and shared between members.
+    pub async fn install_workspace(
+        workspace: &Workspace,
+        overrides: Vec<ConfigLayer>,
+        reporter: Arc<dyn Reporter>,
+    ) -> Result<InstallReport> {
+        let (report, _) = Self::install_members(workspace, overrides, reporter, false).await?;
+        Ok(report)
+    }
 
-        println!("Successfully added skill: {}", fetched_skill.name);
-        Ok(())
+    /// What `install_workspace` would do, without changing anything
+    pub async fn plan_workspace(
+        workspace: &Workspace,
+        overrides: Vec<ConfigLayer>,
+        reporter: Arc<dyn Reporter>,
+    ) -> Result<Plan> {
+        let (_, plan) = Self::install_members(workspace, overrides, reporter, true).await?;
+        Ok(plan)
+    }
+
+    /// Every project is staged before any is committed, so a failure in one
+    /// member leaves the whole workspace untouched
+    async fn install_members(
+        workspace: &Workspace,
+        overrides: Vec<ConfigLayer>,
+        reporter: Arc<dyn Reporter>,
+        dry_run: bool,
+    ) -> Result<(InstallReport, Plan)> {
+        let mut report = InstallReport::default();
+        let mut plan = Plan::default();
+        let mut fetched = HashMap::new();
+        // Shared fetches live in the scratch cache of the manager that made
+        // them when dry running, so every manager is kept until the end
+        let mut managers = Vec::new();
+        let projects = std::iter::once(&workspace.root).chain(workspace.members.iter());
+
+        for project_path in projects {
+            let mut manager = Self::with_workspace(project_path.clone(), Some(workspace))?
+                .with_overrides(overrides.clone())
+                .with_reporter(reporter.clone())?;
+            if dry_run {
+                manager = manager.with_dry_run()?;
+            }
+            manager.begin().await?;
+            manager.report(Event::Project {
+                path: project_path.clone(),
+            });
+            // Managers dropped on failure discard what they staged
+            report.merge(manager.install_all_shared(&mut fetched).await?);
+            plan.merge(manager.plan()?);
+            managers.push(manager);
+        }
+
+        for manager in &mut managers {
+            manager.commit()?;
+        }
+        Ok((report, plan))
+    }
+
+    async fn install_all_shared(
+        &mut self,
+        fetched: &mut HashMap<String, FetchedSkill>,
+    ) -> Result<InstallReport> {
+        let mut report = InstallReport::default();
+        let mut names: Vec<String> = self.config.skills.keys().cloned().collect();
+        names.sort();
+
+        for name in names {
+            let skill_config = self.config.skills[&name].clone();
+            let declared = self.config.skill_targets(&skill_config);
+            let up_to_date = self.lockfile.skills.get(&name).is_some_and(|locked| {
+                locked.version == skill_config.get_version()
+                    && (declared.is_empty() || declared == locked.conventions())
+                    && locked
+                        .installations()
+                        .iter()
+                        .all(|installed| self.target.root.join(&installed.path).exists())
+            });
+
+            if up_to_date {
+                let skill = self.installed_skill(&name);
+                self.report(Event::UpToDate(skill.clone()));
+                report.up_to_date.push(skill);
+                continue;
+            }
+            let skill = self.install_declared(&name, &skill_config, fetched).await?;
+            report.installed.push(skill);
+        }
+
+        Ok(report)
     }
 
     pub async fn add_skill_by_name(
         &mut self,
         skill_name: &str,
-        skill_config: &crate::config::skillset::SkillConfig,
-    ) -> Result<()> {
+        skill_config: &SkillConfig,
+    ) -> Result<InstalledSkill> {
+        let began = self.begin().await?;
+        let result = self
+            .install_declared(skill_name, skill_config, &mut HashMap::new())
+            .await;
+        self.finish(began, result)
+    }
+
+    /// Install a skill declared in the manifest, reusing an already fetched
+    /// copy of the same source when available
+    async fn install_declared(
+        &mut self,
+        skill_name: &str,
+        skill_config: &SkillConfig,
+        fetched: &mut HashMap<String, FetchedSkill>,
+    ) -> Result<InstalledSkill> {
         // Resolve skill name to OCI reference
-        let resolved_reference = self
+        let mut resolved_reference = self
             .config
             .resolve_skill_reference(skill_name, skill_config)?;
 
+        // Keep the digest the lockfile pinned while the reference is unchanged
+        if let Some(locked) = self.lockfile.skills.get(skill_name) {
+            let pinned = locked
+                .source
+                .split_once('#')
+                .or_else(|| locked.source.split_once('@'))
+                .map(|(unpinned, _)| unpinned);
+            if locked.version == skill_config.get_version()
+                && pinned == Some(resolved_reference.as_str())
+            {
+                resolved_reference = locked.source.clone();
+            }
+        }
+
         // Parse the resolved reference
-        let (source_type, source_ref, actual_name) = self.parse_reference(&resolved_reference)?;
+        let (source_type, source_ref, _actual_name) = self.parse_reference(&resolved_reference)?;
 
         // Fetch skill from source
-        let version = Some(skill_config.get_version().to_string());
-        let fetched_skill = self.fetch_skill(&source_type, &source_ref, version).await?;
-
-        // Use convention from config if specified, otherwise auto-detect
-        let convention_name = if let Some(conv) = skill_config.get_explicit_convention() {
-            conv.clone()
-        } else {
-            self.detect_convention(&fetched_skill.source_path).await?
+        let fetched_skill = match fetched.get(&resolved_reference) {
+            Some(fetched_skill) => fetched_skill.clone(),
+            None => {
+                let version = Some(skill_config.get_version().to_string());
+                let fetched_skill = self.fetch_skill(&source_type, &source_ref, version).await?;
+                self.plan_step(PlanStep::Fetch {
+                    name: skill_name.to_string(),
+                    version: fetched_skill.version.clone(),
+                    source: Self::locked_source(&source_type, &source_ref, &fetched_skill),
+                });
+                fetched.insert(resolved_reference.clone(), fetched_skill.clone());
+                fetched_skill
+            }
         };
 
-        // Organize skill according to convention
-        self.organize_skill(&fetched_skill, &convention_name)
+        // Use the targets from config if specified, otherwise auto-detect
+        let declared = self.config.skill_targets(skill_config);
+        let conventions = self.resolve_targets(declared, &fetched_skill).await?;
+        self.remove_stale_targets(skill_name, &conventions)?;
+        let placed = self
+            .organize_targets(skill_name, &fetched_skill, &conventions)
             .await?;
 
-        // Update configuration (this should already be in config, but we might need to add metadata)
-        self.update_config(&fetched_skill, &convention_name)?;
+        // The manifest entry is written by the caller, only the lockfile needs updating
+        let source = Self::locked_source(&source_type, &source_ref, &fetched_skill);
+        self.record_lock(
+            skill_name,
+            skill_config.get_version(),
+            &fetched_skill,
+            &source,
+            &placed,
+        )?;
+        self.save_lockfile()?;
 
-        println!("Successfully added skill: {}", skill_name);
-        Ok(())
+        Ok(self.report_installed(skill_name))
     }
 
     fn parse_reference(&self, reference: &str) -> Result<(String, String, String)> {
-        // Check for explicit sources first
-        if reference.starts_with("git:")
+        // Check for explicit sources first. Archives come first as release
+        // assets are often hosted on github.com.
+        if crate::sources::archive::is_archive_reference(reference) {
+            let source_ref = reference
+                .strip_prefix("archive:")
+                .unwrap_or(reference)
+                .to_string();
+            let skill_name =
+                crate::sources::archive::archive_name(&source_ref).ok_or_else(|| {
+                    crate::error::SkillsetError::Source(format!(
+                        "Unable to extract skill name from archive URL: {}",
+                        source_ref
+                    ))
+                })?;
+            Ok(("archive".to_string(), source_ref, skill_name))
+        } else if reference.starts_with("git:")
             || reference.starts_with("https://github.com")
             || reference.starts_with("git@")
         {
@@ -213,55 +903,120 @@ impl SkillManager {
             })
     }
 
-    pub async fn remove_skill(&mut self, skill_name: &str) -> Result<()> {
+    /// Remove a skill from the manifest, lockfile and disk. Returns the skill
+    /// as it was installed.
+    pub async fn remove_skill(&mut self, skill_name: &str) -> Result<InstalledSkill> {
+        let began = self.begin().await?;
+        let result = self.remove_declared(skill_name);
+        self.finish(began, result)
+    }
+
+    fn remove_declared(&mut self, skill_name: &str) -> Result<InstalledSkill> {
         // Check if skill exists
         if !self.config.skills.contains_key(skill_name) {
             return Err(crate::error::SkillsetError::SkillNotFound(
                 skill_name.to_string(),
             ));
         }
+        let removed = self.installed_skill(skill_name);
 
         // Remove from configuration
         self.config.skills.remove(skill_name);
 
-        // Remove from filesystem (this is complex, would need to find the actual location)
-        // For now, just update config
+        // Remove from filesystem using the location recorded in the lockfile
+        if let Some(locked) = self.lockfile.skills.remove(skill_name) {
+            self.remove_locked(skill_name, &locked)?;
+        }
+
         self.save_config()?;
+        self.save_lockfile()?;
 
-        println!("Successfully removed skill: {}", skill_name);
-        Ok(())
+        if !self.is_dry_run() {
+            self.report(Event::Removed {
+                name: skill_name.to_string(),
+            });
+        }
+        Ok(removed)
     }
 
-    pub async fn show_skill_info(&self, skill_name: &str) -> Result<()> {
-        if let Some(skill_config) = self.config.skills.get(skill_name) {
-            let version = skill_config.get_version();
-            println!("Skill: {}", skill_name);
-            println!("Version: {}", version);
-
-            if let Some(source) = skill_config.get_explicit_source() {
-                println!("Source: {}", source);
-            } else {
-                // Show resolved reference
-                let resolved = self
-                    .config
-                    .resolve_skill_reference(skill_name, skill_config)?;
-                println!("Resolved Source: {}", resolved);
-            }
+    /// Reinstall skills from the references declared in the manifest,
+    /// ignoring the digests pinned in the lockfile so tags and branches are
+    /// resolved again. `names` defaults to every declared skill.
+    pub async fn update(&mut self, names: &[String]) -> Result<InstallReport> {
+        let began = self.begin().await?;
+        let result = self.update_skills(names).await;
+        self.finish(began, result)
+    }
 
-            if let Some(convention) = skill_config.get_explicit_convention() {
-                println!("Convention: {}", convention);
+    async fn update_skills(&mut self, names: &[String]) -> Result<InstallReport> {
+        let names: Vec<String> = if names.is_empty() {
+            let mut names: Vec<String> = self.config.skills.keys().cloned().collect();
+            names.sort();
+            names
+        } else {
+            names.to_vec()
+        };
+
+        let mut report = InstallReport::default();
+        for name in names {
+            let skill_config = self
+                .config
+                .skills
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| crate::error::SkillsetError::SkillNotFound(name.clone()))?;
+            if let Some(locked) = self.lockfile.skills.remove(&name) {
+                self.remove_locked(&name, &locked)?;
             }
+            let skill = self
+                .install_declared(&name, &skill_config, &mut HashMap::new())
+                .await?;
+            report.installed.push(skill);
+        }
 
-            // TODO: Add more info like installation date, file location, etc.
-        } else {
-            return Err(crate::error::SkillsetError::SkillNotFound(
-                skill_name.to_string(),
-            ));
+        Ok(report)
+    }
+
+    /// Delete an installed skill given its path relative to the target
+    fn remove_installed(&mut self, skill_name: &str, path: &Path) -> Result<()> {
+        let installed_path = self.target.root.join(path);
+        if !installed_path.exists() {
+            return Ok(());
         }
 
+        if self.is_dry_run() {
+            self.plan_step(PlanStep::Remove {
+                name: skill_name.to_string(),
+                path: path.to_path_buf(),
+            });
+            return Ok(());
+        }
+        match &mut self.pending {
+            Some(pending) => pending.transaction.remove(installed_path),
+            None => std::fs::remove_dir_all(&installed_path)?,
+        }
         Ok(())
     }
 
+    pub fn skill_info(&self, skill_name: &str) -> Result<SkillInfo> {
+        let skill_config =
+            self.config.skills.get(skill_name).ok_or_else(|| {
+                crate::error::SkillsetError::SkillNotFound(skill_name.to_string())
+            })?;
+
+        Ok(SkillInfo {
+            name: skill_name.to_string(),
+            version: skill_config.get_version().to_string(),
+            source: skill_config.get_explicit_source().cloned(),
+            resolved_source: self
+                .config
+                .resolve_skill_reference(skill_name, skill_config)?,
+            convention: skill_config.get_explicit_convention().cloned(),
+            scope: self.target.scope,
+            locked: self.lockfile.skills.get(skill_name).cloned(),
+        })
+    }
+
     async fn fetch_skill(
         &self,
         source_type: &str,
@@ -279,8 +1034,30 @@ impl SkillManager {
                 }
             }
             "oci" => {
-                // TODO: Implement OCI source fetching
-                todo!("Implement OCI source fetching")
+                if let Some(source) = self.source_registry.get("oci") {
+                    source.fetch(source_ref).await
+                } else {
+                    Err(crate::error::SkillsetError::SourceNotFound(
+                        "oci".to_string(),
+                    ))
+                }
+            }
+            "archive" => {
+                if !source_ref.contains('#')
+                    && self.config.get_integrity_policy() == IntegrityPolicy::Required
+                {
+                    return Err(crate::error::SkillsetError::Verification(format!(
+                        "{} has no integrity hash and the integrity setting is required",
+                        source_ref
+                    )));
+                }
+                if let Some(source) = self.source_registry.get("archive") {
+                    source.fetch(source_ref).await
+                } else {
+                    Err(crate::error::SkillsetError::SourceNotFound(
+                        "archive".to_string(),
+                    ))
+                }
             }
             "local" => {
                 // TODO: Implement local source handling
@@ -292,7 +1069,7 @@ impl SkillManager {
         }
     }
 
-    async fn detect_convention(&self, path: &PathBuf) -> Result<String> {
+    async fn detect_convention(&self, path: &Path) -> Result<String> {
         if let Some(detected) = self.convention_registry.detect_convention(path).await? {
             Ok(detected)
         } else {
@@ -301,72 +1078,680 @@ impl SkillManager {
         }
     }
 
-    async fn organize_skill(
+    /// Conventions to install a fetched skill for: the declared targets, or
+    /// the one detected for it
+    async fn resolve_targets(
         &self,
+        declared: Vec<String>,
+        fetched_skill: &FetchedSkill,
+    ) -> Result<Vec<String>> {
+        if !declared.is_empty() {
+            return Ok(declared);
+        }
+        Ok(vec![
+            self.detect_convention(&fetched_skill.source_path).await?,
+        ])
+    }
+
+    /// Install a fetched skill for each of its conventions, in order
+    async fn organize_targets(
+        &mut self,
+        skill_name: &str,
+        fetched_skill: &FetchedSkill,
+        conventions: &[String],
+    ) -> Result<Vec<Placement>> {
+        let mut placed = Vec::new();
+        for convention_name in conventions {
+            placed.push(
+                self.organize_skill(skill_name, fetched_skill, convention_name)
+                    .await?,
+            );
+        }
+        Ok(placed)
+    }
+
+    /// Install a fetched skill, staged when a transaction is in progress,
+    /// and merge its sections into the instruction files it shares
+    async fn organize_skill(
+        &mut self,
+        skill_name: &str,
         fetched_skill: &FetchedSkill,
         convention_name: &str,
-    ) -> Result<()> {
-        if let Some(convention) = self.convention_registry.get(convention_name) {
+    ) -> Result<Placement> {
+        let convention = self
+            .convention_registry
+            .get(convention_name)
+            .ok_or_else(|| {
+                crate::error::SkillsetError::ConventionNotFound(convention_name.to_string())
+            })?;
+        let installed_path = convention.install_path(&fetched_skill.name, &self.target);
+        self.check_collision(
+            skill_name,
+            &installed_path,
+            convention.shares_install_dir(self.target.scope),
+        )?;
+
+        // Only the files the package ships are installed, converted first
+        // when it was published for another convention
+        let prepared = FetchedSkill {
+            source_path: self.prepare_for(convention, fetched_skill).await?,
+            ..fetched_skill.clone()
+        };
+        let fetched_skill = &prepared;
+
+        let mut sections = Vec::new();
+        for section in convention.sections(
+            &fetched_skill.name,
+            &fetched_skill.source_path,
+            &self.target,
+        )? {
+            self.check_server_collision(skill_name, &section.path)?;
+            self.edit_section(&section.path, skill_name, Some(&section))?;
+            sections.push(self.relative_to_target(&section.path));
+        }
+        let convention = self
+            .convention_registry
+            .get(convention_name)
+            .expect("convention was found above");
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.steps.lock().unwrap().push(PlanStep::Place {
+                name: fetched_skill.name.clone(),
+                convention: convention_name.to_string(),
+                path: self.relative_to_target(&installed_path),
+            });
+            // Conventions convert what they install, so the copy to hash is
+            // organized into a scratch target
+            let scratch = tempfile::Builder::new()
+                .prefix("skillset-organized-")
+                .tempdir()?;
+            let scratch_target = InstallTarget {
+                scope: self.target.scope,
+                root: scratch.path().to_path_buf(),
+            };
             convention
                 .organize(
                     &fetched_skill.name,
                     &fetched_skill.source_path,
-                    &self.project_path,
+                    &scratch_target,
                 )
-                .await
+                .await?;
+            let organized = convention.install_path(&fetched_skill.name, &scratch_target);
+            self.prepared.lock().unwrap().push(scratch);
+            return Ok(Placement {
+                convention: convention_name.to_string(),
+                path: organized,
+                sections,
+            });
+        }
+
+        let Some(pending) = &mut self.pending else {
+            convention
+                .organize(
+                    &fetched_skill.name,
+                    &fetched_skill.source_path,
+                    &self.target,
+                )
+                .await?;
+            return Ok(Placement {
+                convention: convention_name.to_string(),
+                path: installed_path,
+                sections,
+            });
+        };
+
+        let staging = pending.transaction.staging_target()?;
+        convention
+            .organize(&fetched_skill.name, &fetched_skill.source_path, &staging)
+            .await?;
+        let staged = convention.install_path(&fetched_skill.name, &staging);
+        pending.transaction.place(staged.clone(), installed_path);
+        Ok(Placement {
+            convention: convention_name.to_string(),
+            path: staged,
+            sections,
+        })
+    }
+
+    /// Copy the files a fetched skill ships for `convention` into a scratch
+    /// directory, converted to the layout the convention installs from when
+    /// needed, and return where the copy is
+    async fn prepare_for(
+        &self,
+        convention: &dyn Convention,
+        fetched_skill: &FetchedSkill,
+    ) -> Result<PathBuf> {
+        let scratch = tempfile::Builder::new()
+            .prefix("skillset-package-")
+            .tempdir()?;
+        let package = scratch.path().join("package").join(&fetched_skill.name);
+        PackageFilter::new(&fetched_skill.source_path, convention.exclude())?.copy_to(&package)?;
+
+        let converted = scratch.path().join("converted").join(&fetched_skill.name);
+        let prepared = if self
+            .convert_for(convention, &fetched_skill.name, &package, &converted)
+            .await?
+        {
+            converted
+        } else {
+            package
+        };
+        self.prepared.lock().unwrap().push(scratch);
+        Ok(prepared)
+    }
+
+    /// Convert the skill at `source` into `dest`, in the layout `convention`
+    /// installs from, when it does not recognize the skill as one of its
+    /// own. Returns whether it was converted.
+    async fn convert_for(
+        &self,
+        convention: &dyn Convention,
+        skill_name: &str,
+        source: &Path,
+        dest: &Path,
+    ) -> Result<bool> {
+        // Auto-GPT is where skills nothing recognizes end up, and its
+        // skills are code that cannot be generated, so those are copied
+        let to = match convention.formats().first() {
+            Some(&to) if to != Format::AutoGpt => to,
+            _ => return Ok(false),
+        };
+        let from = match Format::detect(source) {
+            Some(from) if !convention.formats().contains(&from) => from,
+            _ => return Ok(false),
+        };
+        if convention.detect(source).await? {
+            return Ok(false);
+        }
+
+        convert::convert(skill_name, source, to, dest)?;
+        tracing::debug!(
+            "Converted {} from {} to {} for {}",
+            skill_name,
+            from,
+            to,
+            convention.name()
+        );
+        Ok(true)
+    }
+
+    /// Replace `skill_name`'s section of the instruction file at `path`, or
+    /// take it out when `section` is `None`. Files left with nothing else in
+    /// them are deleted.
+    fn edit_section(
+        &mut self,
+        path: &Path,
+        skill_name: &str,
+        section: Option<&ManagedSection>,
+    ) -> Result<()> {
+        let current = self.read_shared(path)?;
+
+        // MCP configs keep each skill as a server entry instead of between
+        // markers
+        let (merged, empty) = if mcp::is_config(path) {
+            let merged = mcp::merge(&current, skill_name, section.map(|s| s.body.as_str()))?;
+            let empty = mcp::is_empty(&merged)?;
+            (merged, empty)
         } else {
-            Err(crate::error::SkillsetError::ConventionNotFound(
-                convention_name.to_string(),
-            ))
+            let merged = sections::merge(&current, skill_name, section)?;
+            let empty = sections::is_empty(&merged);
+            (merged, empty)
+        };
+        if merged == current {
+            return Ok(());
+        }
+        if !empty {
+            return match &mut self.pending {
+                Some(pending) => {
+                    pending.transaction.write(path, merged);
+                    Ok(())
+                }
+                None => self.write_file(path, merged),
+            };
+        }
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run
+                .files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), String::new());
+            return Ok(());
+        }
+        match &mut self.pending {
+            Some(pending) => pending.transaction.remove(path.to_path_buf()),
+            None if path.exists() => std::fs::remove_file(path)?,
+            None => {}
         }
+        Ok(())
     }
 
-    fn update_config(&mut self, fetched_skill: &FetchedSkill, convention_name: &str) -> Result<()> {
-        // Add skill to configuration with explicit source and convention
-        self.config.skills.insert(
-            fetched_skill.name.clone(),
-            crate::config::skillset::SkillConfig::Detailed {
-                version: fetched_skill.version.clone(),
-                source: Some(fetched_skill.source_path.display().to_string()),
-                convention: Some(convention_name.to_string()),
+    /// Content of a file shared with other skills as this run left it so far
+    fn read_shared(&self, path: &Path) -> Result<String> {
+        let staged = match (&self.dry_run, &self.pending) {
+            (Some(dry_run), _) => dry_run.files.lock().unwrap().get(path).cloned(),
+            (None, Some(pending)) if pending.transaction.removes(path) => Some(String::new()),
+            (None, Some(pending)) => pending.transaction.written(path).map(str::to_string),
+            (None, None) => None,
+        };
+        match staged {
+            Some(current) => Ok(current),
+            None => match std::fs::read_to_string(path) {
+                Ok(current) => Ok(current),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+                Err(e) => Err(e.into()),
             },
-        );
+        }
+    }
 
-        // Save configuration
-        let config_path = self.project_path.join("skillset.json");
-        self.config.save_to_file(&config_path)?;
+    /// Refuse to replace an MCP server entry skillset did not add, which
+    /// unlike marked sections cannot be told apart from hand-written ones
+    fn check_server_collision(&self, skill_name: &str, path: &Path) -> Result<()> {
+        if !mcp::is_config(path) {
+            return Ok(());
+        }
+        let relative = self.relative_to_target(path);
+        let managed = self.lockfile.skills.get(skill_name).is_some_and(|locked| {
+            locked
+                .installations()
+                .iter()
+                .any(|installed| installed.sections.contains(&relative))
+        });
+        if !managed && mcp::server(&self.read_shared(path)?, skill_name)?.is_some() {
+            return Err(crate::error::SkillsetError::Convention(format!(
+                "Cannot add the {} MCP server to {}, which already has one by that name not \
+                 installed by skillset. Remove or rename it first",
+                skill_name,
+                relative.display()
+            )));
+        }
+        Ok(())
+    }
 
+    /// Delete everything the lockfile records for a skill: its files for
+    /// each convention and its sections of instruction files
+    fn remove_locked(&mut self, skill_name: &str, locked: &LockedSkill) -> Result<()> {
+        for installed in locked.installations() {
+            self.remove_target(skill_name, &installed)?;
+        }
+        Ok(())
+    }
+
+    fn remove_target(&mut self, skill_name: &str, installed: &LockedTarget) -> Result<()> {
+        self.remove_installed(skill_name, &installed.path)?;
+        for path in &installed.sections {
+            let path = self.target.root.join(path);
+            self.edit_section(&path, skill_name, None)?;
+        }
         Ok(())
     }
 
-    pub fn list_skills(&self, verbose: bool) -> Result<()> {
-        if self.config.skills.is_empty() {
-            println!("No skills installed.");
+    /// Delete the installations of a skill for conventions it is no longer
+    /// installed for, so changing its targets leaves nothing behind
+    fn remove_stale_targets(&mut self, skill_name: &str, conventions: &[String]) -> Result<()> {
+        let Some(locked) = self.lockfile.skills.get(skill_name).cloned() else {
             return Ok(());
+        };
+        for installed in locked.installations() {
+            if !conventions.contains(&installed.convention) {
+                self.remove_target(skill_name, &installed)?;
+            }
         }
+        Ok(())
+    }
 
-        println!("Installed skills:");
-        for (name, skill_config) in &self.config.skills {
-            let version = skill_config.get_version();
-            if verbose {
-                if let (Some(source), Some(convention)) = (
-                    skill_config.get_explicit_source(),
-                    skill_config.get_explicit_convention(),
-                ) {
-                    println!(
-                        "  {} (v{}) - Source: {} - Convention: {}",
-                        name, version, source, convention
-                    );
-                } else if let Some(source) = skill_config.get_explicit_source() {
-                    println!("  {} (v{}) - Source: {}", name, version, source);
-                } else {
-                    println!("  {} (v{})", name, version);
+    /// Refuse to install over another skill of this project, or over a
+    /// directory skillset did not install where the convention shares its
+    /// install directory with other tools
+    fn check_collision(&self, skill_name: &str, installed_path: &Path, shared: bool) -> Result<()> {
+        let relative = self.relative_to_target(installed_path);
+        let installed_at = |locked: &LockedSkill| {
+            locked
+                .installations()
+                .iter()
+                .any(|installed| installed.path == relative)
+        };
+        let other = self
+            .lockfile
+            .skills
+            .iter()
+            .find(|(name, locked)| name.as_str() != skill_name && installed_at(locked));
+        if let Some((other, _)) = other {
+            return Err(crate::error::SkillsetError::Convention(format!(
+                "Cannot install {} at {}, {} is already installed there",
+                skill_name,
+                relative.display(),
+                other
+            )));
+        }
+
+        let managed = self
+            .lockfile
+            .skills
+            .get(skill_name)
+            .is_some_and(installed_at);
+        if shared && !managed && installed_path.exists() && !self.is_removing(installed_path) {
+            return Err(crate::error::SkillsetError::Convention(format!(
+                "Cannot install {} at {}, which already exists and was not installed by \
+                 skillset. Move it away first",
+                skill_name,
+                relative.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether this run already takes out `path`
+    fn is_removing(&self, path: &Path) -> bool {
+        if let Some(dry_run) = &self.dry_run {
+            let relative = self.relative_to_target(path);
+            return dry_run
+                .steps
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|step| matches!(step, PlanStep::Remove { path, .. } if *path == relative));
+        }
+        self.pending
+            .as_ref()
+            .is_some_and(|pending| pending.transaction.removes(path))
+    }
+
+    /// Declare a skill added from an explicit source in the manifest and
+    /// lock what was installed
+    fn update_config(
+        &mut self,
+        fetched_skill: &FetchedSkill,
+        entry: SkillConfig,
+        locked_source: &str,
+        placed: &[Placement],
+    ) -> Result<()> {
+        self.config.skills.insert(fetched_skill.name.clone(), entry);
+        self.record_lock(
+            &fetched_skill.name,
+            &fetched_skill.version,
+            fetched_skill,
+            locked_source,
+            placed,
+        )?;
+
+        // Save configuration
+        self.save_config()?;
+        self.save_lockfile()?;
+
+        Ok(())
+    }
+
+    /// Record a skill in the lockfile, `placed` holding one placement per
+    /// convention it was installed for
+    fn record_lock(
+        &mut self,
+        skill_name: &str,
+        version: &str,
+        fetched_skill: &FetchedSkill,
+        source: &str,
+        placed: &[Placement],
+    ) -> Result<()> {
+        let mut installed = Vec::new();
+        for placement in placed {
+            let convention = self
+                .convention_registry
+                .get(&placement.convention)
+                .ok_or_else(|| {
+                    crate::error::SkillsetError::ConventionNotFound(placement.convention.clone())
+                })?;
+            let installed_path = convention.install_path(&fetched_skill.name, &self.target);
+            installed.push(LockedTarget {
+                convention: placement.convention.clone(),
+                path: self.relative_to_target(&installed_path),
+                checksum: hash_dir(&placement.path)?,
+                sections: placement.sections.clone(),
+            });
+        }
+        let mut installed = installed.into_iter();
+        let first = installed
+            .next()
+            .expect("skills are installed for at least one convention");
+
+        self.lockfile.skills.insert(
+            skill_name.to_string(),
+            LockedSkill {
+                version: version.to_string(),
+                source: source.to_string(),
+                convention: first.convention,
+                path: first.path,
+                checksum: first.checksum,
+                sections: first.sections,
+                targets: installed.collect(),
+            },
+        );
+        Ok(())
+    }
+
+    fn relative_to_target(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.target.root)
+            .unwrap_or(path)
+            .to_path_buf()
+    }
+
+    /// Rebuild a full source reference from the output of `parse_reference`
+    fn source_reference(source_type: &str, source_ref: &str) -> String {
+        match source_type {
+            "local" => source_ref.to_string(),
+            _ => format!("{}:{}", source_type, source_ref),
+        }
+    }
+
+    /// Reference recorded in the lockfile. OCI references are pinned to the
+    /// manifest digest that was installed so reinstalls, including from
+    /// mirrors, must get identical content. Archives are pinned the same way
+    /// to the hash of the downloaded file, as the URL fragment.
+    fn locked_source(source_type: &str, source_ref: &str, fetched: &FetchedSkill) -> String {
+        match (source_type, &fetched.metadata.checksum) {
+            ("oci", Some(digest)) if !source_ref.contains('@') => {
+                format!("oci:{}@{}", source_ref, digest)
+            }
+            ("archive", Some(digest)) if !source_ref.contains('#') => {
+                format!("archive:{}#{}", source_ref, digest)
+            }
+            _ => Self::source_reference(source_type, source_ref),
+        }
+    }
+
+    /// Copy skills from their upstream registry to `mirror` (`host[/prefix]`).
+    /// `names` defaults to every OCI skill in the manifest; locked digests are
+    /// copied when available.
+    pub async fn mirror_sync(&self, mirror: &str, names: &[String]) -> Result<MirrorReport> {
+        let names: Vec<String> = if names.is_empty() {
+            let mut names: Vec<String> = self.config.skills.keys().cloned().collect();
+            names.sort();
+            names
+        } else {
+            names.to_vec()
+        };
+
+        let registries = Registries::new(self.config.merged_registries());
+        let mut report = MirrorReport {
+            mirror: mirror.to_string(),
+            ..Default::default()
+        };
+        for name in names {
+            let skill_config = self
+                .config
+                .skills
+                .get(&name)
+                .ok_or_else(|| crate::error::SkillsetError::SkillNotFound(name.clone()))?;
+            let source = match self.lockfile.skills.get(&name) {
+                Some(locked) if locked.version == skill_config.get_version() => {
+                    locked.source.clone()
+                }
+                _ => self.config.resolve_skill_reference(&name, skill_config)?,
+            };
+
+            let Some(reference) = source.strip_prefix("oci:") else {
+                let skipped = SkippedSkill {
+                    name,
+                    reason: "not an OCI skill".to_string(),
+                };
+                self.reporter.report(&Event::Skipped {
+                    name: skipped.name.clone(),
+                    reason: skipped.reason.clone(),
+                });
+                report.skipped.push(skipped);
+                continue;
+            };
+            let reference = OciReference::parse(reference)?;
+            let digest = mirror::sync(&reference, mirror, &registries).await?;
+            report.synced.push(SyncedSkill { name, digest });
+        }
+
+        Ok(report)
+    }
+
+    /// Push the skill at `path` to `reference`, shipping the files installing
+    /// it copies. A reference without a host, such as `web:1.0.0`, is
+    /// published under the configured registry.
+    pub async fn publish(&self, path: &Path, reference: &str) -> Result<PublishReport> {
+        let reference = reference.strip_prefix("oci:").unwrap_or(reference);
+        let reference = if reference.contains('/') {
+            OciReference::parse(reference)?
+        } else {
+            OciReference::parse(&format!("{}/{}", self.config.get_registry(), reference))?
+        };
+
+        // The convention is the installer's choice, so only the defaults
+        // every convention shares apply here
+        let package = PackageFilter::new(path, DEFAULT_EXCLUDE)?;
+        let registries = Registries::new(self.config.merged_registries());
+        let digest = OciPublisher::new(registries)
+            .publish_skill(&package, &reference)
+            .await?;
+
+        Ok(PublishReport {
+            reference: reference.to_string(),
+            digest,
+            files: package.files()?,
+        })
+    }
+
+    /// Check the manifest, lockfile and installed files for drift
+    pub async fn verify(&self) -> Result<VerifyReport> {
+        let skills = verify::verify_skills(
+            &self.target,
+            &self.config,
+            &self.lockfile,
+            &self.convention_registry,
+        )?;
+        let environment = verify::check_environment(&self.config).await;
+
+        Ok(VerifyReport {
+            skills,
+            environment,
+        })
+    }
+
+    /// Reinstall every declared skill that drifted and drop lockfile entries
+    /// for skills no longer in the manifest. Extra directories are left alone.
+    pub async fn fix(&mut self, report: &VerifyReport) -> Result<Vec<String>> {
+        let began = self.begin().await?;
+        let result = self.fix_drift(report).await;
+        self.finish(began, result)
+    }
+
+    async fn fix_drift(&mut self, report: &VerifyReport) -> Result<Vec<String>> {
+        let mut fixed = Vec::new();
+
+        for name in report.skills_to_reinstall() {
+            let Some(skill_config) = self.config.skills.get(&name).cloned() else {
+                self.lockfile.skills.remove(&name);
+                fixed.push(name);
+                continue;
+            };
+
+            // Prefer the locked source, it is what was originally installed
+            let locked = self.lockfile.skills.get(&name).cloned();
+            let source = match &locked {
+                Some(locked) if locked.version == skill_config.get_version() => {
+                    locked.source.clone()
                 }
-            } else {
-                println!("  {} (v{})", name, version);
+                _ => self.config.resolve_skill_reference(&name, &skill_config)?,
+            };
+            let (source_type, source_ref, _) = self.parse_reference(&source)?;
+
+            // Clear out whatever is currently on disk for this skill
+            for drift in report.skills.iter().filter(|d| d.name == name) {
+                if let Some(path) = &drift.path {
+                    self.remove_installed(&name, path)?;
+                }
+            }
+            if let Some(locked) = &locked {
+                self.remove_locked(&name, locked)?;
             }
+
+            let version = Some(skill_config.get_version().to_string());
+            let fetched_skill = self.fetch_skill(&source_type, &source_ref, version).await?;
+            let declared = match (self.config.skill_targets(&skill_config), &locked) {
+                (declared, Some(locked)) if declared.is_empty() => locked.conventions(),
+                (declared, _) => declared,
+            };
+            let conventions = self.resolve_targets(declared, &fetched_skill).await?;
+
+            let placed = self
+                .organize_targets(&name, &fetched_skill, &conventions)
+                .await?;
+            self.record_lock(
+                &name,
+                skill_config.get_version(),
+                &fetched_skill,
+                &source,
+                &placed,
+            )?;
+            fixed.push(name);
         }
 
-        Ok(())
+        self.save_lockfile()?;
+        Ok(fixed)
+    }
+
+    /// Every skill declared in the manifest, sorted by name
+    pub fn installed_skills(&self) -> Vec<InstalledSkill> {
+        let mut names: Vec<&String> = self.config.skills.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| self.installed_skill(name))
+            .collect()
+    }
+
+    fn installed_skill(&self, name: &str) -> InstalledSkill {
+        let skill_config = self.config.skills.get(name);
+        let locked = self.lockfile.skills.get(name);
+
+        InstalledSkill {
+            name: name.to_string(),
+            version: skill_config
+                .map(|config| config.get_version().to_string())
+                .or_else(|| locked.map(|locked| locked.version.clone()))
+                .unwrap_or_default(),
+            source: skill_config.and_then(|config| config.get_explicit_source().cloned()),
+            convention: skill_config
+                .and_then(|config| config.get_explicit_convention().cloned())
+                .or_else(|| locked.map(|locked| locked.convention.clone())),
+            path: locked.map(|locked| locked.path.clone()),
+            targets: locked
+                .filter(|locked| !locked.targets.is_empty())
+                .map(LockedSkill::conventions)
+                .unwrap_or_default(),
+            scope: self.target.scope,
+        }
+    }
+
+    fn report_installed(&mut self, name: &str) -> InstalledSkill {
+        let skill = self.installed_skill(name);
+        // A dry run reports its plan instead
+        if !self.is_dry_run() {
+            self.report(Event::Installed(skill.clone()));
+        }
+        skill
     }
 }
@@ -1,5 +1,14 @@
+pub mod checksum;
 pub mod manager;
+pub mod package;
+pub mod plan;
+pub mod reference;
+pub mod report;
+pub mod transaction;
 pub mod types;
+pub mod verify;
 
 pub use manager::SkillManager;
+pub use plan::{Plan, PlanStep};
+pub use report::{Event, InstallReport, InstalledSkill, Reporter, SkillInfo};
 pub use types::*;
@@ -0,0 +1,474 @@
+//! Which files of a skill package are installed.
+//!
+//! A package narrows its files down with `files` (only these) and `exclude`
+//! globs in its `skill.yaml`, and with a `.skillsetignore` using gitignore
+//! syntax. Conventions add defaults of their own, and `.git` is never
+//! copied. Installing, `skillset convert` and `skillset publish` go through
+//! `PackageFilter`, so a package publishes what it installs.
+//!
+//! Files are copied with their permissions, so scripts stay executable, and
+//! relative symlinks are recreated as links. Links pointing outside the
+//! package are refused rather than copied.
+
+use std::path::{Component, Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Deserialize;
+
+use crate::error::{Result, SkillsetError};
+
+/// Gitignore-style rules at the root of a package
+pub const IGNORE_FILE: &str = ".skillsetignore";
+
+/// The package's own manifest
+pub const MANIFEST_FILE: &str = "skill.yaml";
+
+/// Left out of every package, whatever its rules say
+const ALWAYS_EXCLUDED: &str = ".git";
+
+/// What conventions leave out unless they say otherwise: repository
+/// configuration that means nothing to an agent
+pub const DEFAULT_EXCLUDE: &[&str] = &[".github/", ".gitignore", ".gitattributes", IGNORE_FILE];
+
+/// The parts of `skill.yaml` about which files are shipped. Other keys
+/// belong to the skill and are ignored here.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PackageManifest {
+    /// Globs of the files to ship, everything when empty. A directory
+    /// brings everything in it.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Globs of files to leave out
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl PackageManifest {
+    pub fn read(root: &Path) -> Result<Self> {
+        let path = root.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content)
+            .map_err(|e| SkillsetError::Source(format!("Invalid {}: {}", path.display(), e)))
+    }
+}
+
+/// Decides which files under a package root are part of the package
+pub struct PackageFilter {
+    root: PathBuf,
+    include: Option<Gitignore>,
+    exclude: Gitignore,
+}
+
+impl PackageFilter {
+    /// The filter for the package at `root`, on top of the `defaults` its
+    /// convention leaves out. The package's own rules come last, so they can
+    /// bring back what a default excluded with `!pattern`.
+    pub fn new(root: &Path, defaults: &[&str]) -> Result<Self> {
+        let manifest = PackageManifest::read(root)?;
+        let invalid = |e: ignore::Error| {
+            SkillsetError::Source(format!("Invalid file pattern in {}: {}", root.display(), e))
+        };
+
+        let mut exclude = GitignoreBuilder::new(root);
+        for pattern in defaults
+            .iter()
+            .copied()
+            .chain(manifest.exclude.iter().map(String::as_str))
+        {
+            exclude.add_line(None, pattern).map_err(invalid)?;
+        }
+        let ignore_file = root.join(IGNORE_FILE);
+        if ignore_file.is_file() {
+            if let Some(e) = exclude.add(&ignore_file) {
+                return Err(invalid(e));
+            }
+        }
+
+        let include = if manifest.files.is_empty() {
+            None
+        } else {
+            let mut include = GitignoreBuilder::new(root);
+            for pattern in &manifest.files {
+                include.add_line(None, pattern).map_err(invalid)?;
+            }
+            // The manifest itself always ships, like a package.json
+            include.add_line(None, MANIFEST_FILE).map_err(invalid)?;
+            Some(include.build().map_err(invalid)?)
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            include,
+            exclude: exclude.build().map_err(invalid)?,
+        })
+    }
+
+    /// Whether `relative`, a path inside the package, is shipped. Directories
+    /// are kept unless excluded, `files` only decides about files.
+    pub fn includes(&self, relative: &Path, is_dir: bool) -> bool {
+        let in_git = relative
+            .components()
+            .any(|component| component == Component::Normal(ALWAYS_EXCLUDED.as_ref()));
+        if in_git
+            || self
+                .exclude
+                .matched_path_or_any_parents(relative, is_dir)
+                .is_ignore()
+        {
+            return false;
+        }
+        match &self.include {
+            Some(include) if !is_dir => include
+                .matched_path_or_any_parents(relative, false)
+                .is_ignore(),
+            _ => true,
+        }
+    }
+
+    /// Every file shipped, relative to the package root, in a stable order
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let mut found = Vec::new();
+        self.collect(Path::new(""), &mut found)?;
+        Ok(found)
+    }
+
+    /// Copy every file shipped to `dest`
+    pub fn copy_to(&self, dest: &Path) -> Result<()> {
+        std::fs::create_dir_all(dest)?;
+        for relative in self.files()? {
+            if let Some(parent) = dest.join(&relative).parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            copy_entry(&self.root, &relative, dest)?;
+        }
+        Ok(())
+    }
+
+    /// Every file shipped as a gzipped tarball, the layer a published skill
+    /// is pushed as. Timestamps and owners are left out, so the same files
+    /// always give the same bytes.
+    pub fn archive(&self) -> Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        builder.mode(tar::HeaderMode::Deterministic);
+        builder.follow_symlinks(false);
+        for relative in self.files()? {
+            let path = self.root.join(&relative);
+            if std::fs::symlink_metadata(&path)?.file_type().is_symlink() {
+                check_link(&self.root, &relative, &std::fs::read_link(&path)?)?;
+            }
+            builder.append_path_with_name(&path, &relative)?;
+        }
+        Ok(builder.into_inner()?.finish()?)
+    }
+
+    fn collect(&self, relative: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+        let mut entries =
+            std::fs::read_dir(self.root.join(relative))?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = relative.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if !self.includes(&path, file_type.is_dir()) {
+                continue;
+            }
+            // Links are shipped as links, never followed
+            if file_type.is_dir() {
+                self.collect(&path, found)?;
+            } else if file_type.is_file() || file_type.is_symlink() {
+                found.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy everything in `src` to `dest`, see [`copy_entry`]
+pub fn copy_tree(src: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+    let mut entries = std::fs::read_dir(src)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        copy_entry(src, Path::new(&entry.file_name()), dest)?;
+    }
+    Ok(())
+}
+
+/// Copy `relative`, a file, directory or symlink inside the package at
+/// `root`, to the same place below `dest`. Permissions are kept and symlinks
+/// are recreated, as long as they stay inside `root`.
+pub fn copy_entry(root: &Path, relative: &Path, dest: &Path) -> Result<()> {
+    let from = root.join(relative);
+    let to = dest.join(relative);
+    let metadata = std::fs::symlink_metadata(&from)?;
+
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(&from)?;
+        check_link(root, relative, &target)?;
+        if std::fs::symlink_metadata(&to).is_ok() {
+            std::fs::remove_file(&to)?;
+        }
+        return symlink(relative, &target, &to);
+    }
+
+    if metadata.is_dir() {
+        std::fs::create_dir_all(&to)?;
+        let mut entries = std::fs::read_dir(&from)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            copy_entry(root, &relative.join(entry.file_name()), dest)?;
+        }
+    } else {
+        std::fs::copy(&from, &to)?;
+    }
+    // Set last, so a read-only directory is filled in before it is locked
+    std::fs::set_permissions(&to, metadata.permissions())?;
+    Ok(())
+}
+
+fn unsafe_link(relative: &Path, reason: &str) -> SkillsetError {
+    SkillsetError::Source(format!(
+        "Refusing to install {}: {}",
+        relative.display(),
+        reason
+    ))
+}
+
+/// Symlinks must be relative and point inside the package, both as written
+/// and once links along the way are resolved
+fn check_link(root: &Path, relative: &Path, target: &Path) -> Result<()> {
+    let mut depth = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .count()
+        .saturating_sub(1);
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| unsafe_link(relative, "symlink points outside the skill"))?;
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_link(relative, "symlink target is absolute"))
+            }
+        }
+    }
+
+    // `..` after another link can still climb out. Dangling links have
+    // nothing to resolve and are copied as they are.
+    if let Ok(resolved) = std::fs::canonicalize(root.join(relative)) {
+        if !resolved.starts_with(std::fs::canonicalize(root)?) {
+            return Err(unsafe_link(relative, "symlink points outside the skill"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(_relative: &Path, target: &Path, path: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn symlink(relative: &Path, _target: &Path, _path: &Path) -> Result<()> {
+    Err(unsafe_link(
+        relative,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn shipped(dir: &Path, defaults: &[&str]) -> Vec<String> {
+        PackageFilter::new(dir, defaults)
+            .unwrap()
+            .files()
+            .unwrap()
+            .iter()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_ignore_file_and_defaults_leave_files_out() {
+        let dir = package(&[
+            ("SKILL.md", "# Skill"),
+            ("scripts/run.sh", "echo hi"),
+            ("tests/run_test.sh", "exit 0"),
+            ("assets/demo.mp4", "video"),
+            (".github/workflows/ci.yml", "on: push"),
+            (".git/HEAD", "ref: refs/heads/main"),
+            (IGNORE_FILE, "tests/\n*.mp4\n"),
+        ]);
+
+        assert_eq!(
+            shipped(dir.path(), &[".github/", IGNORE_FILE]),
+            vec!["SKILL.md", "scripts/run.sh"]
+        );
+
+        // Packages can bring back what a default leaves out, but never .git
+        std::fs::write(dir.path().join(IGNORE_FILE), "!.github/\n.git/\n!.git/\n").unwrap();
+        assert!(shipped(dir.path(), &[".github/"]).contains(&".github/workflows/ci.yml".into()));
+        assert!(!shipped(dir.path(), &[]).contains(&".git/HEAD".into()));
+    }
+
+    #[test]
+    fn test_manifest_files_and_exclude() {
+        let dir = package(&[
+            ("SKILL.md", "# Skill"),
+            ("scripts/run.sh", "echo hi"),
+            ("scripts/debug.sh", "set -x"),
+            ("notes.txt", "todo"),
+            (
+                MANIFEST_FILE,
+                "files: [SKILL.md, scripts/]\nexclude: [\"**/debug.sh\"]\n",
+            ),
+        ]);
+
+        assert_eq!(
+            shipped(dir.path(), &[]),
+            vec!["SKILL.md", "scripts/run.sh", MANIFEST_FILE]
+        );
+
+        std::fs::write(dir.path().join(MANIFEST_FILE), "files: SKILL.md\n").unwrap();
+        let error = PackageFilter::new(dir.path(), &[]).err().unwrap();
+        assert!(error.to_string().contains("Invalid"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_keeps_modes_and_links() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = package(&[("SKILL.md", "# Skill"), ("scripts/run.sh", "echo hi")]);
+        let script = dir.path().join("scripts/run.sh");
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        symlink("run.sh", dir.path().join("scripts/latest.sh")).unwrap();
+        symlink("scripts", dir.path().join("bin")).unwrap();
+
+        assert_eq!(
+            shipped(dir.path(), &[]),
+            vec!["SKILL.md", "bin", "scripts/latest.sh", "scripts/run.sh"]
+        );
+        let dest = tempfile::tempdir().unwrap();
+        PackageFilter::new(dir.path(), &[])
+            .unwrap()
+            .copy_to(dest.path())
+            .unwrap();
+
+        let mode = std::fs::metadata(dest.path().join("scripts/run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(
+            std::fs::read_link(dest.path().join("scripts/latest.sh")).unwrap(),
+            Path::new("run.sh")
+        );
+        assert_eq!(
+            std::fs::read_link(dest.path().join("bin")).unwrap(),
+            Path::new("scripts")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.path().join("bin/run.sh")).unwrap(),
+            "echo hi"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_archive_holds_what_is_shipped() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = package(&[
+            ("SKILL.md", "# Skill"),
+            ("scripts/run.sh", "echo hi"),
+            ("tests/run_test.sh", "exit 0"),
+            (IGNORE_FILE, "tests/\n"),
+        ]);
+        let script = dir.path().join("scripts/run.sh");
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        symlink("run.sh", dir.path().join("scripts/latest.sh")).unwrap();
+
+        let filter = PackageFilter::new(dir.path(), DEFAULT_EXCLUDE).unwrap();
+        let archive = filter.archive().unwrap();
+        // No timestamps or owners, so publishing twice gives one digest
+        assert_eq!(archive, filter.archive().unwrap());
+
+        let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(archive.as_slice()));
+        let entries: Vec<(String, u32, Option<PathBuf>)> = tarball
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                (
+                    entry.path().unwrap().to_string_lossy().into_owned(),
+                    entry.header().mode().unwrap() & 0o777,
+                    entry.link_name().unwrap().map(|link| link.into_owned()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("SKILL.md".to_string(), 0o644, None),
+                (
+                    "scripts/latest.sh".to_string(),
+                    0o755,
+                    Some(PathBuf::from("run.sh"))
+                ),
+                ("scripts/run.sh".to_string(), 0o755, None),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_links_out_of_the_skill_are_refused() {
+        use std::os::unix::fs::symlink;
+
+        let outside = package(&[("secret", "token")]);
+        for target in [PathBuf::from("../../secret"), outside.path().join("secret")] {
+            let dir = package(&[("SKILL.md", "# Skill")]);
+            std::fs::create_dir(dir.path().join("docs")).unwrap();
+            symlink(&target, dir.path().join("docs/secret")).unwrap();
+
+            let dest = tempfile::tempdir().unwrap();
+            let error = copy_tree(dir.path(), dest.path()).err().unwrap();
+            assert!(error.to_string().contains("docs/secret"), "{}", error);
+            assert!(!dest.path().join("docs/secret").exists());
+        }
+
+        // Climbing out through another link is caught as well
+        let dir = package(&[("SKILL.md", "# Skill"), ("deep/er/file", "")]);
+        symlink(".", dir.path().join("here")).unwrap();
+        symlink("here/deep/../..", dir.path().join("up")).unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        assert!(copy_tree(dir.path(), dest.path()).is_err());
+    }
+}
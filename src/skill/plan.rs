@@ -0,0 +1,120 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use similar::TextDiff;
+
+/// What a mutating command would do, computed by a dry run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+    /// Manifests and lockfiles that would be rewritten
+    pub files: Vec<FileDiff>,
+}
+
+impl Plan {
+    pub fn merge(&mut self, other: Plan) {
+        self.steps.extend(other.steps);
+        self.files.extend(other.files);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.files.is_empty()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "Nothing to do.");
+        }
+        for step in &self.steps {
+            writeln!(f, "  {}", step)?;
+        }
+        for file in &self.files {
+            write!(f, "\n{}", file.diff)?;
+        }
+        Ok(())
+    }
+}
+
+/// One change to the filesystem outside the manifest and lockfile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlanStep {
+    /// Download a skill, `source` being the exact reference resolved
+    Fetch {
+        name: String,
+        version: String,
+        source: String,
+    },
+    /// Copy a fetched skill into the project
+    Place {
+        name: String,
+        convention: String,
+        /// Relative to the project, or the home directory for global skills
+        path: PathBuf,
+    },
+    /// Delete an installed skill
+    Remove { name: String, path: PathBuf },
+}
+
+impl fmt::Display for PlanStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch {
+                name,
+                version,
+                source,
+            } => write!(f, "fetch {} {} from {}", name, version, source),
+            Self::Place {
+                name,
+                convention,
+                path,
+            } => write!(f, "place {} at {} via {}", name, path.display(), convention),
+            Self::Remove { name, path } => write!(f, "remove {} from {}", name, path.display()),
+        }
+    }
+}
+
+/// A file that would change, as a unified diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub diff: String,
+}
+
+impl FileDiff {
+    /// Diff `old` against `new`, or `None` when the content is unchanged.
+    /// A file that does not exist yet is diffed as empty.
+    pub fn between(path: PathBuf, old: &str, new: &str) -> Option<Self> {
+        if old == new {
+            return None;
+        }
+
+        let name = path.display().to_string();
+        let diff = TextDiff::from_lines(old, new)
+            .unified_diff()
+            .header(&name, &name)
+            .missing_newline_hint(false)
+            .to_string();
+        Some(Self { path, diff })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_diff_only_when_changed() {
+        let path = PathBuf::from("skillset.json");
+        assert_eq!(FileDiff::between(path.clone(), "{}\n", "{}\n"), None);
+
+        let diff = FileDiff::between(path, "a\nb\n", "a\nc\n").unwrap();
+        assert!(diff
+            .diff
+            .starts_with("--- skillset.json\n+++ skillset.json\n"));
+        assert!(diff.diff.contains("-b\n+c\n"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::{Result, SkillsetError};
+
+/// True for registry names like `file-analyzer@1.0.0` or `@user/skill@2.0.0`,
+/// as opposed to explicit git, OCI or local sources
+pub fn is_simplified_skill_reference(reference: &str) -> bool {
+    // Check if it's a simplified skill name (not an explicit source)
+    !reference.starts_with("git:")
+        && !reference.starts_with("oci:")
+        && !reference.starts_with("archive:")
+        && !reference.starts_with("file://")
+        && !reference.starts_with("https://")
+        && !reference.starts_with("http://")
+        && !reference.starts_with("/")
+        && !reference.starts_with("./")
+        && !reference.starts_with("../")
+        && reference.contains('@') // Should have @version
+}
+
+pub fn parse_skill_reference(reference: &str) -> Result<(String, &str)> {
+    // Parse skill references like "file-analyzer@1.0.0" or "@user/skill@2.0.0"
+    if let Some(at_pos) = reference.rfind('@') {
+        let skill_name = &reference[..at_pos];
+        let version = &reference[at_pos + 1..];
+
+        // Validate skill name format
+        validate_skill_name(skill_name)?;
+        validate_version(version)?;
+
+        Ok((skill_name.to_string(), version))
+    } else {
+        Err(SkillsetError::InvalidSkillName(
+            "Skill reference must include version with @. Expected: name@version".to_string(),
+        ))
+    }
+}
+
+pub fn validate_skill_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(SkillsetError::InvalidSkillName(
+            "Skill name cannot be empty".to_string(),
+        ));
+    }
+
+    // Handle scoped names like @user/skill
+    if let Some(scoped_part) = name.strip_prefix('@') {
+        if scoped_part.is_empty() {
+            return Err(SkillsetError::InvalidSkillName(
+                "Scoped skill name cannot be empty after @".to_string(),
+            ));
+        }
+
+        let parts: Vec<&str> = scoped_part.split('/').collect();
+        if parts.len() != 2 {
+            return Err(SkillsetError::InvalidSkillName(
+                "Scoped skill name must be in format @user/skill".to_string(),
+            ));
+        }
+
+        let (user, skill) = (parts[0], parts[1]);
+
+        // Validate username part
+        if user.is_empty() || user.len() > 39 {
+            return Err(SkillsetError::InvalidSkillName(
+                "Username in scoped skill name must be 1-39 characters".to_string(),
+            ));
+        }
+
+        // Validate skill name part
+        if skill.is_empty() || skill.len() > 100 {
+            return Err(SkillsetError::InvalidSkillName(
+                "Skill name must be 1-100 characters".to_string(),
+            ));
+        }
+
+        // Check for valid characters (alphanumeric, hyphens, underscores)
+        if !user
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SkillsetError::InvalidSkillName(
+                "Username can only contain alphanumeric characters, hyphens, and underscores"
+                    .to_string(),
+            ));
+        }
+
+        if !skill
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SkillsetError::InvalidSkillName(
+                "Skill name can only contain alphanumeric characters, hyphens, and underscores"
+                    .to_string(),
+            ));
+        }
+    } else {
+        // Validate simple skill names
+        if name.len() > 100 {
+            return Err(SkillsetError::InvalidSkillName(
+                "Skill name must be 1-100 characters".to_string(),
+            ));
+        }
+
+        if !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SkillsetError::InvalidSkillName(
+                "Skill name can only contain alphanumeric characters, hyphens, and underscores"
+                    .to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_version(version: &str) -> Result<()> {
+    if version.is_empty() {
+        return Err(SkillsetError::InvalidSkillName(
+            "Version cannot be empty".to_string(),
+        ));
+    }
+
+    // Allow "latest" or semantic version patterns
+    if version == "latest" {
+        return Ok(());
+    }
+
+    // Basic semantic version validation (X.Y.Z or vX.Y.Z)
+    let version_to_check = version.strip_prefix('v').unwrap_or(version);
+
+    // Split by dots and validate each part is numeric
+    let parts: Vec<&str> = version_to_check.split('.').collect();
+    if parts.len() < 2 || parts.len() > 4 {
+        return Err(SkillsetError::InvalidSkillName(
+            "Version must be in semantic version format (e.g., 1.0.0 or v1.0.0)".to_string(),
+        ));
+    }
+
+    for part in parts {
+        if part.is_empty() {
+            return Err(SkillsetError::InvalidSkillName(
+                "Version parts cannot be empty".to_string(),
+            ));
+        }
+
+        // Allow numeric parts and pre-release identifiers
+        if part
+            .chars()
+            .any(|c| !c.is_alphanumeric() && c != '-' && c != '+')
+        {
+            return Err(SkillsetError::InvalidSkillName(
+                "Version parts can only contain alphanumeric characters, hyphens, and plus signs"
+                    .to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
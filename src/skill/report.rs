@@ -0,0 +1,149 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::config::lockfile::LockedSkill;
+use crate::config::validate::Diagnostic;
+use crate::skill::types::InstallScope;
+
+/// A skill declared in a manifest, with where it was installed when the
+/// lockfile knows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledSkill {
+    pub name: String,
+    pub version: String,
+    /// Source as written in the manifest, when not resolved from the name
+    pub source: Option<String>,
+    pub convention: Option<String>,
+    /// Install location relative to the project, or the home directory for
+    /// global skills
+    pub path: Option<PathBuf>,
+    /// Every convention the skill is installed for, when it has several
+    /// targets
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+    pub scope: InstallScope,
+}
+
+/// Everything known about one declared skill
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillInfo {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+    /// Reference the skill is fetched from
+    pub resolved_source: String,
+    pub convention: Option<String>,
+    pub scope: InstallScope,
+    /// The lockfile entry, absent until the skill is installed
+    pub locked: Option<LockedSkill>,
+}
+
+/// Outcome of installing one or more skills
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InstallReport {
+    pub installed: Vec<InstalledSkill>,
+    /// Already installed at the declared version, left untouched
+    pub up_to_date: Vec<InstalledSkill>,
+}
+
+impl InstallReport {
+    pub fn merge(&mut self, other: InstallReport) {
+        self.installed.extend(other.installed);
+        self.up_to_date.extend(other.up_to_date);
+    }
+}
+
+/// Outcome of publishing a skill to a registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublishReport {
+    pub reference: String,
+    /// Manifest digest the registry now serves for the reference
+    pub digest: String,
+    /// Files shipped, relative to the skill directory
+    pub files: Vec<PathBuf>,
+}
+
+/// Outcome of copying skills to a registry mirror
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MirrorReport {
+    pub mirror: String,
+    pub synced: Vec<SyncedSkill>,
+    pub skipped: Vec<SkippedSkill>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncedSkill {
+    pub name: String,
+    /// Manifest digest now present on the mirror
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedSkill {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Progress while skills are installed and removed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Starting on one project of a workspace
+    Project {
+        path: PathBuf,
+    },
+    Installed(InstalledSkill),
+    /// Already installed at the declared version
+    UpToDate(InstalledSkill),
+    Removed {
+        name: String,
+    },
+    Skipped {
+        name: String,
+        reason: String,
+    },
+    /// Another process holds a lock this one needs
+    Waiting {
+        path: PathBuf,
+        /// The holder, when it recorded itself in the lock file
+        pid: Option<u32>,
+    },
+    /// A problem in the manifest that does not stop the command, such as
+    /// a misspelled key
+    Warning(Diagnostic),
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Project { path } => write!(f, "Installing {}", path.display()),
+            Self::Installed(skill) => write!(f, "Successfully added skill: {}", skill.name),
+            Self::UpToDate(skill) => write!(f, "Already installed: {}", skill.name),
+            Self::Removed { name } => write!(f, "Successfully removed skill: {}", name),
+            Self::Skipped { name, reason } => write!(f, "Skipping {}: {}", name, reason),
+            Self::Waiting { path, pid } => write!(
+                f,
+                "Waiting for lock on {}{}",
+                path.display(),
+                crate::lock::held_by(*pid)
+            ),
+            Self::Warning(diagnostic) => write!(f, "warning: {}", diagnostic),
+        }
+    }
+}
+
+/// Receives progress events from a `SkillManager` as they happen. Results
+/// are returned by the manager's methods, so a reporter is only needed to
+/// show progress.
+pub trait Reporter: Send + Sync {
+    fn report(&self, event: &Event);
+}
+
+/// Discards every event
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&self, _event: &Event) {}
+}
@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::skill::types::InstallTarget;
+
+/// Changes to an install target that are staged and only applied together.
+///
+/// Skills are organized into a staging directory next to the target, so
+/// moving them into place is a rename on the same filesystem. On commit,
+/// anything being replaced or removed is first moved aside, staged skills are
+/// renamed into place and the manifest and lockfile are written. If a step
+/// fails, the completed steps are undone. Dropping an uncommitted transaction
+/// deletes everything it staged and leaves the target untouched.
+pub struct Transaction {
+    target: InstallTarget,
+    /// Created on first use inside the target root
+    staging: Option<tempfile::TempDir>,
+    placements: Vec<(PathBuf, PathBuf)>,
+    removals: Vec<PathBuf>,
+    files: BTreeMap<PathBuf, String>,
+}
+
+/// A completed commit step, kept so it can be undone
+enum Step {
+    MovedAside {
+        path: PathBuf,
+        backup: PathBuf,
+    },
+    Placed {
+        path: PathBuf,
+    },
+    Wrote {
+        path: PathBuf,
+        original: Option<Vec<u8>>,
+    },
+}
+
+impl Transaction {
+    pub fn new(target: &InstallTarget) -> Self {
+        Self {
+            target: target.clone(),
+            staging: None,
+            placements: Vec::new(),
+            removals: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    fn staging_dir(&mut self) -> Result<PathBuf> {
+        if let Some(staging) = &self.staging {
+            return Ok(staging.path().to_path_buf());
+        }
+
+        std::fs::create_dir_all(&self.target.root)?;
+        let staging = tempfile::Builder::new()
+            .prefix(".skillset-staging-")
+            .tempdir_in(&self.target.root)?;
+        let path = staging.path().to_path_buf();
+        self.staging = Some(staging);
+        Ok(path)
+    }
+
+    /// The target conventions organize skills into while staging, laid out
+    /// like the real one
+    pub fn staging_target(&mut self) -> Result<InstallTarget> {
+        Ok(InstallTarget {
+            scope: self.target.scope,
+            root: self.staging_dir()?.join("install"),
+        })
+    }
+
+    /// Move `staged` to `path` on commit, replacing whatever is there
+    pub fn place(&mut self, staged: PathBuf, path: PathBuf) {
+        self.placements.push((staged, path));
+    }
+
+    /// Delete `path` on commit, dropping anything staged to be written there
+    pub fn remove(&mut self, path: PathBuf) {
+        self.files.remove(&path);
+        self.removals.push(path);
+    }
+
+    /// Whether `path` is deleted on commit
+    pub fn removes(&self, path: &Path) -> bool {
+        self.removals.iter().any(|removal| removal == path)
+    }
+
+    /// Write `content` to `path` on commit, after every skill is in place
+    pub fn write(&mut self, path: &Path, content: String) {
+        self.removals.retain(|removal| removal != path);
+        self.files.insert(path.to_path_buf(), content);
+    }
+
+    /// Content staged to be written to `path`
+    pub fn written(&self, path: &Path) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Apply every staged change, or none of them
+    pub fn commit(mut self) -> Result<()> {
+        let mut done = Vec::new();
+        if let Err(e) = self.apply(&mut done) {
+            for step in done.into_iter().rev() {
+                // Best effort, the original error is what gets reported
+                let _ = undo(step);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, done: &mut Vec<Step>) -> Result<()> {
+        let removals = std::mem::take(&mut self.removals);
+        let placements = std::mem::take(&mut self.placements);
+
+        for path in removals {
+            self.move_aside(&path, done)?;
+        }
+        for (staged, path) in placements {
+            self.move_aside(&path, done)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(&staged, &path)?;
+            done.push(Step::Placed { path });
+        }
+
+        for (path, content) in std::mem::take(&mut self.files) {
+            let original = match std::fs::read(&path) {
+                Ok(original) => Some(original),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            write_atomically(&path, content.as_bytes())?;
+            done.push(Step::Wrote { path, original });
+        }
+        Ok(())
+    }
+
+    fn move_aside(&mut self, path: &Path, done: &mut Vec<Step>) -> Result<()> {
+        if std::fs::symlink_metadata(path).is_err() {
+            return Ok(());
+        }
+
+        let backup_dir = self.staging_dir()?.join("backup");
+        std::fs::create_dir_all(&backup_dir)?;
+        let backup = backup_dir.join(done.len().to_string());
+        std::fs::rename(path, &backup)?;
+        done.push(Step::MovedAside {
+            path: path.to_path_buf(),
+            backup,
+        });
+        Ok(())
+    }
+}
+
+fn undo(step: Step) -> Result<()> {
+    match step {
+        Step::MovedAside { path, backup } => std::fs::rename(backup, path)?,
+        Step::Placed { path } => remove_path(&path)?,
+        Step::Wrote {
+            path,
+            original: Some(original),
+        } => write_atomically(&path, &original)?,
+        Step::Wrote {
+            path,
+            original: None,
+        } => std::fs::remove_file(path)?,
+    }
+    Ok(())
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)?;
+    } else {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Write through a temporary file in the same directory so readers never
+/// see a partially written file. The file keeps its permissions, and new
+/// files get the usual ones for the umask rather than the temporary file's
+/// private 0600.
+pub fn write_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(parent)?;
+    let mut builder = tempfile::Builder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // The umask applies on creation, as it would for `File::create`
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut file = builder.tempfile_in(parent)?;
+    if let Ok(existing) = std::fs::metadata(path) {
+        file.as_file().set_permissions(existing.permissions())?;
+    }
+    file.write_all(content)?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn staged_skill(transaction: &mut Transaction, name: &str, content: &str) -> PathBuf {
+        let staged = transaction.staging_target().unwrap().root.join(name);
+        std::fs::create_dir_all(&staged).unwrap();
+        std::fs::write(staged.join("SKILL.md"), content).unwrap();
+        staged
+    }
+
+    #[test]
+    fn test_commit_replaces_skills_and_writes_files() {
+        let root = tempfile::tempdir().unwrap();
+        let target = InstallTarget::project(root.path().to_path_buf());
+        let installed = root.path().join("skills/web");
+        std::fs::create_dir_all(&installed).unwrap();
+        std::fs::write(installed.join("stale.md"), "old").unwrap();
+
+        let mut transaction = Transaction::new(&target);
+        let staged = staged_skill(&mut transaction, "web", "new");
+        transaction.place(staged, installed.clone());
+        transaction.write(&root.path().join("skillset.lock"), "{}".to_string());
+        transaction.commit().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+            "new"
+        );
+        assert!(!installed.join("stale.md").exists());
+        assert!(root.path().join("skillset.lock").exists());
+        // Only the skill and the lockfile remain, the staging area is gone
+        assert_eq!(std::fs::read_dir(root.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let shared = root.path().join("skillset.json");
+        std::fs::write(&shared, "{}").unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o664)).unwrap();
+        write_atomically(&shared, b"{ \"skills\": {} }").unwrap();
+        assert_eq!(mode(&shared), 0o664);
+
+        // New files are created like any other, not private to the user
+        let created = root.path().join("skillset.lock");
+        write_atomically(&created, b"{}").unwrap();
+        let reference = root.path().join("reference");
+        std::fs::write(&reference, "").unwrap();
+        assert_eq!(mode(&created), mode(&reference));
+    }
+
+    #[test]
+    fn test_failed_commit_restores_everything() {
+        let root = tempfile::tempdir().unwrap();
+        let target = InstallTarget::project(root.path().to_path_buf());
+        let installed = root.path().join("skills/web");
+        std::fs::create_dir_all(&installed).unwrap();
+        std::fs::write(installed.join("SKILL.md"), "old").unwrap();
+        std::fs::write(root.path().join("skillset.json"), "original").unwrap();
+
+        let mut transaction = Transaction::new(&target);
+        let staged = staged_skill(&mut transaction, "web", "new");
+        transaction.place(staged, installed.clone());
+        transaction.write(&root.path().join("skillset.json"), "updated".to_string());
+        // Writing below a regular file fails after the other steps ran
+        transaction.write(
+            &root.path().join("skillset.json/skillset.lock"),
+            String::new(),
+        );
+        assert!(transaction.commit().is_err());
+
+        assert_eq!(
+            std::fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+            "old"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("skillset.json")).unwrap(),
+            "original"
+        );
+    }
+}
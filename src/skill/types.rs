@@ -42,4 +42,42 @@ pub struct FetchedSkill {
     pub version: String,
     pub source_path: PathBuf,
     pub metadata: SkillMetadata,
+    /// Keeps other processes from replacing the cache entry while it is used
+    pub lock: Option<std::sync::Arc<crate::lock::FileLock>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallScope {
+    /// Installed into the current project
+    Project,
+    /// Installed once for the user, shared by every project
+    Global,
+}
+
+/// Where skills get materialized: the project root, or the user's home
+/// directory for global installs
+#[derive(Debug, Clone)]
+pub struct InstallTarget {
+    pub scope: InstallScope,
+    pub root: PathBuf,
+}
+
+impl InstallTarget {
+    pub fn project(project_path: PathBuf) -> Self {
+        Self {
+            scope: InstallScope::Project,
+            root: project_path,
+        }
+    }
+
+    pub fn global() -> crate::error::Result<Self> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            crate::error::SkillsetError::Config("No home directory found".to_string())
+        })?;
+        Ok(Self {
+            scope: InstallScope::Global,
+            root: home,
+        })
+    }
 }
@@ -0,0 +1,521 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::cache::CachePaths;
+use crate::config::lockfile::Lockfile;
+use crate::config::skillset::{registry_host, RegistrySettings, SkillsetConfig};
+use crate::conventions::ConventionRegistry;
+use crate::error::Result;
+use crate::skill::checksum::hash_dir;
+use crate::skill::types::InstallTarget;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftKind {
+    /// Declared in the manifest but not present on disk
+    Missing,
+    /// Present on disk but not declared in the manifest
+    Extra,
+    /// Installed files no longer match the lockfile checksum
+    Modified,
+    /// Installed under a different location than the lockfile records
+    Misplaced,
+    /// Lockfile entry disagrees with the manifest or is missing entirely
+    Stale,
+}
+
+impl fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Missing => "missing",
+            Self::Extra => "extra",
+            Self::Modified => "modified",
+            Self::Misplaced => "misplaced",
+            Self::Stale => "stale",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillDrift {
+    pub name: String,
+    pub kind: DriftKind,
+    pub detail: String,
+    /// Location on disk the problem refers to, relative to the project
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Ok => "ok",
+            Self::Warning => "warn",
+            Self::Failed => "fail",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub skills: Vec<SkillDrift>,
+    pub environment: Vec<EnvironmentCheck>,
+}
+
+impl VerifyReport {
+    /// True when no drift was found and no environment check failed
+    pub fn is_clean(&self) -> bool {
+        self.skills.is_empty()
+            && self
+                .environment
+                .iter()
+                .all(|check| check.status != CheckStatus::Failed)
+    }
+
+    /// Names of declared skills that `--fix` should reinstall
+    pub fn skills_to_reinstall(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for drift in &self.skills {
+            if drift.kind != DriftKind::Extra && !names.contains(&drift.name) {
+                names.push(drift.name.clone());
+            }
+        }
+        names
+    }
+}
+
+/// Compare the manifest, the lockfile and the project directory
+pub fn verify_skills(
+    target: &InstallTarget,
+    config: &SkillsetConfig,
+    lockfile: &Lockfile,
+    conventions: &ConventionRegistry,
+) -> Result<Vec<SkillDrift>> {
+    let project_path = target.root.as_path();
+    let mut drifts = Vec::new();
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+
+    let mut names: Vec<&String> = config.skills.keys().collect();
+    names.sort();
+
+    for name in names {
+        let skill_config = &config.skills[name];
+        let Some(locked) = lockfile.skills.get(name) else {
+            // Without a lockfile entry we can only look for the skill by name
+            let found = find_installed(target, install_dir_name(name), conventions);
+            claimed.extend(found.iter().cloned());
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: if found.is_empty() {
+                    DriftKind::Missing
+                } else {
+                    DriftKind::Stale
+                },
+                detail: format!("no entry in {}", crate::config::lockfile::LOCKFILE_NAME),
+                path: found.first().map(|p| relative_to(project_path, p)),
+            });
+            continue;
+        };
+
+        if locked.version != skill_config.get_version() {
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: DriftKind::Stale,
+                detail: format!(
+                    "manifest requests version {} but lockfile has {}",
+                    skill_config.get_version(),
+                    locked.version
+                ),
+                path: None,
+            });
+        }
+        let declared = config.skill_targets(skill_config);
+        let locked_conventions = locked.conventions();
+        if !declared.is_empty() && declared != locked_conventions {
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: DriftKind::Stale,
+                detail: format!(
+                    "manifest requests convention {} but lockfile has {}",
+                    declared.join(", "),
+                    locked_conventions.join(", ")
+                ),
+                path: None,
+            });
+        }
+
+        // Further targets are only checked where the lockfile puts them
+        for installed in &locked.targets {
+            let expected = project_path.join(&installed.path);
+            if !expected.exists() {
+                drifts.push(SkillDrift {
+                    name: name.clone(),
+                    kind: DriftKind::Missing,
+                    detail: format!("expected at {}", installed.path.display()),
+                    path: Some(installed.path.clone()),
+                });
+                continue;
+            }
+            claimed.insert(expected.clone());
+            if hash_dir(&expected)? != installed.checksum {
+                drifts.push(SkillDrift {
+                    name: name.clone(),
+                    kind: DriftKind::Modified,
+                    detail: "installed files differ from the locked checksum".to_string(),
+                    path: Some(installed.path.clone()),
+                });
+            }
+        }
+
+        let expected = project_path.join(&locked.path);
+        if expected.exists() {
+            claimed.insert(expected.clone());
+            let checksum = hash_dir(&expected)?;
+            if checksum != locked.checksum {
+                drifts.push(SkillDrift {
+                    name: name.clone(),
+                    kind: DriftKind::Modified,
+                    detail: "installed files differ from the locked checksum".to_string(),
+                    path: Some(locked.path.clone()),
+                });
+            }
+            continue;
+        }
+
+        let dir_name = locked
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_else(|| install_dir_name(name));
+        let found = find_installed(target, dir_name, conventions);
+        if let Some(actual) = found.first() {
+            claimed.extend(found.iter().cloned());
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: DriftKind::Misplaced,
+                detail: format!("expected at {}", locked.path.display()),
+                path: Some(relative_to(project_path, actual)),
+            });
+        } else {
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: DriftKind::Missing,
+                detail: format!("expected at {}", locked.path.display()),
+                path: Some(locked.path.clone()),
+            });
+        }
+    }
+
+    for (name, locked) in &lockfile.skills {
+        if !config.skills.contains_key(name) {
+            for installed in locked.installations() {
+                claimed.insert(project_path.join(&installed.path));
+            }
+            drifts.push(SkillDrift {
+                name: name.clone(),
+                kind: DriftKind::Stale,
+                detail: "locked but not declared in the manifest".to_string(),
+                path: Some(locked.path.clone()),
+            });
+        }
+    }
+
+    // Anything else inside a convention directory was not installed by us
+    for root in convention_roots(target, conventions) {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        let mut extras: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| !claimed.contains(path))
+            .collect();
+        extras.sort();
+
+        for extra in extras {
+            let name = extra
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            drifts.push(SkillDrift {
+                name,
+                kind: DriftKind::Extra,
+                detail: "not declared in the manifest".to_string(),
+                path: Some(relative_to(project_path, &extra)),
+            });
+        }
+    }
+
+    Ok(drifts)
+}
+
+/// Check that the environment skillset depends on is usable
+pub async fn check_environment(config: &SkillsetConfig) -> Vec<EnvironmentCheck> {
+    let mut checks = vec![check_cache_writable(), check_git_available()];
+    for registry in config.all_registries() {
+        let host = registry_host(&registry);
+        checks.push(check_registry_reachable(host, &config.registry_settings(host)).await);
+    }
+    checks
+}
+
+fn check_cache_writable() -> EnvironmentCheck {
+    let name = "cache writable".to_string();
+    let result = CachePaths::new().and_then(|cache| {
+        cache.ensure_directories()?;
+        let probe = cache.base_dir().join(".write-probe");
+        std::fs::write(&probe, b"")?;
+        std::fs::remove_file(&probe)?;
+        Ok(cache.base_dir().to_path_buf())
+    });
+
+    match result {
+        Ok(path) => EnvironmentCheck {
+            name,
+            status: CheckStatus::Ok,
+            detail: path.display().to_string(),
+        },
+        Err(e) => EnvironmentCheck {
+            name,
+            status: CheckStatus::Failed,
+            detail: e.to_string(),
+        },
+    }
+}
+
+fn check_git_available() -> EnvironmentCheck {
+    let name = "git available".to_string();
+    match std::process::Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => EnvironmentCheck {
+            name,
+            status: CheckStatus::Ok,
+            detail: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        },
+        // Clones go through libgit2, so a missing git binary is not fatal
+        _ => EnvironmentCheck {
+            name,
+            status: CheckStatus::Warning,
+            detail: "git executable not found on PATH".to_string(),
+        },
+    }
+}
+
+async fn check_registry_reachable(domain: &str, settings: &RegistrySettings) -> EnvironmentCheck {
+    let name = "registry reachable".to_string();
+    let url = format!("{}/v2/", settings.base_url(domain));
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .danger_accept_invalid_certs(settings.insecure)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return EnvironmentCheck {
+                name,
+                status: CheckStatus::Warning,
+                detail: e.to_string(),
+            }
+        }
+    };
+
+    // Any HTTP response (including 401) means the registry is up
+    match client.get(&url).send().await {
+        Ok(response) => EnvironmentCheck {
+            name,
+            status: CheckStatus::Ok,
+            detail: format!("{} ({})", domain, response.status()),
+        },
+        Err(e) => EnvironmentCheck {
+            name,
+            status: CheckStatus::Warning,
+            detail: format!("{}: {}", domain, e),
+        },
+    }
+}
+
+/// Directory name a skill is installed under, e.g. `@user/skill` -> `skill`
+fn install_dir_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+fn find_installed(
+    target: &InstallTarget,
+    dir_name: &str,
+    conventions: &ConventionRegistry,
+) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = conventions
+        .list()
+        .into_iter()
+        .filter_map(|name| conventions.get(name))
+        .map(|convention| convention.install_path(dir_name, target))
+        .filter(|path| path.exists())
+        .collect();
+    found.sort();
+    found
+}
+
+fn convention_roots(target: &InstallTarget, conventions: &ConventionRegistry) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = conventions
+        .list()
+        .into_iter()
+        .filter_map(|name| conventions.get(name))
+        // Other skills living there are none of our business
+        .filter(|convention| !convention.shares_install_dir(target.scope))
+        .filter_map(|convention| {
+            convention
+                .install_path("_", target)
+                .parent()
+                .map(|p| p.to_path_buf())
+        })
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+fn relative_to(project_path: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(project_path)
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::lockfile::LockedSkill;
+    use crate::config::skillset::SkillConfig;
+    use crate::conventions::{AgentSkillsConvention, AutoGptConvention};
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, SkillsetConfig, Lockfile, ConventionRegistry) {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("skills/autogpt/file-analyzer");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("skill.py"), "print('hi')").unwrap();
+
+        let mut config = SkillsetConfig::default();
+        config.skills.insert(
+            "file-analyzer".to_string(),
+            SkillConfig::Simple("1.0.0".to_string()),
+        );
+
+        let mut lockfile = Lockfile::default();
+        lockfile.skills.insert(
+            "file-analyzer".to_string(),
+            LockedSkill {
+                version: "1.0.0".to_string(),
+                source: "oci:ghcr.io/skillset/file-analyzer:v1.0.0".to_string(),
+                convention: "autogpt".to_string(),
+                path: PathBuf::from("skills/autogpt/file-analyzer"),
+                checksum: hash_dir(&skill_dir).unwrap(),
+                sections: Vec::new(),
+                targets: Vec::new(),
+            },
+        );
+
+        let mut conventions = ConventionRegistry::new();
+        conventions.register(Box::new(AutoGptConvention::new()));
+        conventions.register(Box::new(AgentSkillsConvention::new()));
+
+        (temp_dir, config, lockfile, conventions)
+    }
+
+    #[test]
+    fn test_clean_project_has_no_drift() {
+        let (temp_dir, config, lockfile, conventions) = setup();
+        let target = InstallTarget::project(temp_dir.path().to_path_buf());
+        let drifts = verify_skills(&target, &config, &lockfile, &conventions).unwrap();
+        assert!(drifts.is_empty(), "{:?}", drifts);
+    }
+
+    #[test]
+    fn test_detects_modified_skill() {
+        let (temp_dir, config, lockfile, conventions) = setup();
+        let target = InstallTarget::project(temp_dir.path().to_path_buf());
+        std::fs::write(
+            temp_dir
+                .path()
+                .join("skills/autogpt/file-analyzer/skill.py"),
+            "print('edited')",
+        )
+        .unwrap();
+
+        let drifts = verify_skills(&target, &config, &lockfile, &conventions).unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].kind, DriftKind::Modified);
+    }
+
+    #[test]
+    fn test_detects_missing_and_extra_skills() {
+        let (temp_dir, config, lockfile, conventions) = setup();
+        let target = InstallTarget::project(temp_dir.path().to_path_buf());
+        std::fs::remove_dir_all(temp_dir.path().join("skills/autogpt/file-analyzer")).unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("skills/agent-skills/hand-made")).unwrap();
+
+        let drifts = verify_skills(&target, &config, &lockfile, &conventions).unwrap();
+        let kinds: Vec<DriftKind> = drifts.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![DriftKind::Missing, DriftKind::Extra]);
+        assert_eq!(drifts[1].name, "hand-made");
+    }
+
+    #[test]
+    fn test_detects_misplaced_skill() {
+        let (temp_dir, config, lockfile, conventions) = setup();
+        let target = InstallTarget::project(temp_dir.path().to_path_buf());
+        std::fs::create_dir_all(temp_dir.path().join("skills/agent-skills")).unwrap();
+        std::fs::rename(
+            temp_dir.path().join("skills/autogpt/file-analyzer"),
+            temp_dir.path().join("skills/agent-skills/file-analyzer"),
+        )
+        .unwrap();
+
+        let drifts = verify_skills(&target, &config, &lockfile, &conventions).unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].kind, DriftKind::Misplaced);
+        assert_eq!(
+            drifts[0].path,
+            Some(PathBuf::from("skills/agent-skills/file-analyzer"))
+        );
+    }
+
+    #[test]
+    fn test_detects_stale_lockfile() {
+        let (temp_dir, mut config, lockfile, conventions) = setup();
+        let target = InstallTarget::project(temp_dir.path().to_path_buf());
+        config.skills.insert(
+            "file-analyzer".to_string(),
+            SkillConfig::Simple("2.0.0".to_string()),
+        );
+
+        let drifts = verify_skills(&target, &config, &lockfile, &conventions).unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].kind, DriftKind::Stale);
+
+        let report = VerifyReport {
+            skills: drifts,
+            environment: Vec::new(),
+        };
+        assert!(!report.is_clean());
+        assert_eq!(report.skills_to_reinstall(), vec!["file-analyzer"]);
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::{SkillSource, SourceType};
+use crate::cache::CachePaths;
+use crate::error::{Result, SkillsetError};
+use crate::lock::FileLock;
+use crate::skill::report::{Reporter, SilentReporter};
+use crate::skill::types::{FetchedSkill, SkillMetadata};
+
+/// Downloads skills shipped as `.tar.gz`, `.tgz`, `.tar.zst`, `.tar` or `.zip`
+/// archives over HTTP(S) or from `file://` URLs. References may carry an
+/// integrity hash as the URL fragment, which the download must match.
+/// Extracted archives are cached by the sha256 of the archive file.
+pub struct ArchiveSource {
+    cache: CachePaths,
+    client: reqwest::Client,
+    reporter: Arc<dyn Reporter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    TarGz,
+    TarZst,
+    Tar,
+    Zip,
+}
+
+impl ArchiveFormat {
+    const EXTENSIONS: [(&'static str, Self); 6] = [
+        (".tar.gz", Self::TarGz),
+        (".tgz", Self::TarGz),
+        (".tar.zst", Self::TarZst),
+        (".tzst", Self::TarZst),
+        (".tar", Self::Tar),
+        (".zip", Self::Zip),
+    ];
+
+    /// Format from the extension of the URL path, ignoring query and fragment
+    fn from_url(url: &str) -> Option<(Self, &'static str)> {
+        let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+        Self::EXTENSIONS
+            .iter()
+            .find(|(extension, _)| path.ends_with(extension))
+            .map(|(extension, format)| (*format, *extension))
+    }
+}
+
+/// Whether `reference` names an archive: prefixed with `archive:`, or an
+/// http(s) or `file://` URL ending in an archive extension
+pub fn is_archive_reference(reference: &str) -> bool {
+    if reference.starts_with("archive:") {
+        return true;
+    }
+    let url_like = ["https://", "http://", "file://"]
+        .iter()
+        .any(|scheme| reference.starts_with(scheme));
+    url_like && ArchiveFormat::from_url(reference).is_some()
+}
+
+/// Skill name an archive URL implies: its file name without the extension
+pub fn archive_name(url: &str) -> Option<String> {
+    let (_, extension) = ArchiveFormat::from_url(url)?;
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let file_name = path.rsplit('/').next()?;
+    let name = &file_name[..file_name.len() - extension.len()];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// An expected archive hash: Subresource Integrity style `sha256-<base64>`
+/// (or sha384, sha512), or `sha256:<hex>` like OCI digests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Integrity {
+    Sha256(Vec<u8>),
+    Sha384(Vec<u8>),
+    Sha512(Vec<u8>),
+}
+
+impl Integrity {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || {
+            SkillsetError::Verification(format!(
+                "Invalid integrity {}, expected sha256-<base64>, sha384-<base64>, \
+                 sha512-<base64> or sha256:<hex>",
+                value
+            ))
+        };
+
+        let integrity = if let Some(hex) = value.strip_prefix("sha256:") {
+            Self::Sha256(decode_hex(hex).ok_or_else(invalid)?)
+        } else {
+            let (algorithm, digest) = value.split_once('-').ok_or_else(invalid)?;
+            let digest = base64::engine::general_purpose::STANDARD
+                .decode(digest)
+                .map_err(|_| invalid())?;
+            match algorithm {
+                "sha256" => Self::Sha256(digest),
+                "sha384" => Self::Sha384(digest),
+                "sha512" => Self::Sha512(digest),
+                _ => return Err(invalid()),
+            }
+        };
+
+        if integrity.digest().len() != integrity.of(b"").digest().len() {
+            return Err(invalid());
+        }
+        Ok(integrity)
+    }
+
+    /// Hash of `data` with the same algorithm
+    fn of(&self, data: &[u8]) -> Self {
+        match self {
+            Self::Sha256(_) => Self::Sha256(Sha256::digest(data).to_vec()),
+            Self::Sha384(_) => Self::Sha384(Sha384::digest(data).to_vec()),
+            Self::Sha512(_) => Self::Sha512(Sha512::digest(data).to_vec()),
+        }
+    }
+
+    fn digest(&self) -> &[u8] {
+        match self {
+            Self::Sha256(digest) | Self::Sha384(digest) | Self::Sha512(digest) => digest,
+        }
+    }
+
+    pub fn verify(&self, data: &[u8]) -> Result<()> {
+        let actual = self.of(data);
+        if actual != *self {
+            return Err(SkillsetError::Verification(format!(
+                "Archive integrity mismatch: expected {}, got {}",
+                self, actual
+            )));
+        }
+        Ok(())
+    }
+
+    /// Hex sha256 the cache is keyed by, when known up front
+    fn sha256_hex(&self) -> Option<String> {
+        match self {
+            Self::Sha256(digest) => Some(digest.iter().map(|b| format!("{:02x}", b)).collect()),
+            _ => None,
+        }
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl fmt::Display for Integrity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self {
+            Self::Sha256(_) => "sha256",
+            Self::Sha384(_) => "sha384",
+            Self::Sha512(_) => "sha512",
+        };
+        let digest = base64::engine::general_purpose::STANDARD.encode(self.digest());
+        write!(f, "{}-{}", algorithm, digest)
+    }
+}
+
+impl ArchiveSource {
+    pub fn new() -> Result<Self> {
+        Self::with_cache(CachePaths::new()?)
+    }
+
+    pub fn with_cache(cache: CachePaths) -> Result<Self> {
+        Ok(Self {
+            cache,
+            client: reqwest::Client::new(),
+            reporter: Arc::new(SilentReporter),
+        })
+    }
+
+    /// Report waiting for another process's extraction to `reporter`
+    pub fn with_reporter(mut self, reporter: Arc<dyn Reporter>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Ok(tokio::fs::read(path).await?);
+        }
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// Extract `data` into the cache entry `target` unless it is already there,
+/// staging next to it so an interrupted extraction never leaves a
+/// partial entry
+fn extract(
+    target: &Path,
+    lock_path: &Path,
+    data: &[u8],
+    format: ArchiveFormat,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    // Another process may be extracting the same archive
+    let _lock = FileLock::acquire(lock_path, reporter)?;
+    if target.exists() {
+        return Ok(());
+    }
+
+    let staging = target.with_extension(format!("tmp-{}", std::process::id()));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    let result = match format {
+        ArchiveFormat::TarGz => unpack_tar(flate2::read::GzDecoder::new(data), &staging),
+        ArchiveFormat::TarZst => unpack_tar(zstd::stream::read::Decoder::new(data)?, &staging),
+        ArchiveFormat::Tar => unpack_tar(data, &staging),
+        ArchiveFormat::Zip => unpack_zip(data, &staging),
+    };
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    std::fs::rename(&staging, target)?;
+    Ok(())
+}
+
+fn unsafe_entry(name: &Path, reason: &str) -> SkillsetError {
+    SkillsetError::Source(format!(
+        "Refusing archive entry {}: {}",
+        name.display(),
+        reason
+    ))
+}
+
+/// Where the entry `name` lands below `root`. Absolute names and names
+/// climbing out with `..` are refused.
+fn entry_path(root: &Path, name: &Path) -> Result<PathBuf> {
+    let mut path = root.to_path_buf();
+    for component in name.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err(unsafe_entry(name, "path escapes the archive")),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_entry(name, "path is absolute"))
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Symlinks must be relative and point inside the archive
+fn check_link(name: &Path, target: &Path) -> Result<()> {
+    let mut depth = name
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .count()
+        .saturating_sub(1);
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| unsafe_entry(name, "symlink points outside the archive"))?;
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_entry(name, "symlink target is absolute"))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Make room for an entry at `path`. Refuses to write through a symlink an
+/// earlier entry created, and replaces an earlier file or link of the same
+/// name rather than following it.
+fn prepare(root: &Path, name: &Path, path: &Path) -> Result<()> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut current = root.to_path_buf();
+    for part in relative.parent().into_iter().flat_map(Path::components) {
+        current.push(part);
+        if std::fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(unsafe_entry(name, "path goes through a symlink"));
+        }
+    }
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.is_dir() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+fn unpack_tar<R: Read>(reader: R, root: &Path) -> Result<()> {
+    use tar::EntryType;
+
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.into_owned();
+        let path = entry_path(root, &name)?;
+
+        match entry.header().entry_type() {
+            EntryType::Directory => {
+                prepare(root, &name, &path)?;
+                std::fs::create_dir_all(&path)?;
+            }
+            EntryType::Regular | EntryType::Continuous => {
+                prepare(root, &name, &path)?;
+                entry.unpack(&path)?;
+            }
+            EntryType::Symlink => {
+                let target = entry
+                    .link_name()?
+                    .ok_or_else(|| unsafe_entry(&name, "symlink has no target"))?;
+                check_link(&name, &target)?;
+                prepare(root, &name, &path)?;
+                entry.unpack(&path)?;
+            }
+            EntryType::XGlobalHeader | EntryType::XHeader => {}
+            EntryType::Link => return Err(unsafe_entry(&name, "hard links are not supported")),
+            other => {
+                return Err(unsafe_entry(
+                    &name,
+                    &format!("unsupported entry type {:?}", other),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unpack_zip(data: &[u8], root: &Path) -> Result<()> {
+    let zip_error = |e: zip::result::ZipError| SkillsetError::Source(format!("Invalid zip: {}", e));
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(zip_error)?;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(zip_error)?;
+        let name = PathBuf::from(file.name());
+        let path = entry_path(root, &name)?;
+        prepare(root, &name, &path)?;
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&path)?;
+        } else if file.is_symlink() {
+            let mut target = String::new();
+            file.read_to_string(&mut target)?;
+            check_link(&name, Path::new(&target))?;
+            symlink(&name, Path::new(&target), &path)?;
+        } else {
+            let mut out = std::fs::File::create(&path)?;
+            std::io::copy(&mut file, &mut out)?;
+            #[cfg(unix)]
+            if let Some(mode) = file.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode & 0o777))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(_name: &Path, target: &Path, path: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn symlink(name: &Path, _target: &Path, _path: &Path) -> Result<()> {
+    Err(unsafe_entry(
+        name,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+/// Release archives usually wrap everything in one `name-version/`
+/// directory, which is the skill itself
+fn skill_root(extracted: &Path) -> Result<PathBuf> {
+    let entries: Vec<_> = std::fs::read_dir(extracted)?.collect::<std::io::Result<_>>()?;
+    match entries.as_slice() {
+        [only] if only.file_type()?.is_dir() => Ok(only.path()),
+        _ => Ok(extracted.to_path_buf()),
+    }
+}
+
+#[async_trait]
+impl SkillSource for ArchiveSource {
+    async fn fetch(&self, reference: &str) -> Result<FetchedSkill> {
+        self.cache.ensure_directories()?;
+        let (url, integrity) = match reference.split_once('#') {
+            Some((url, integrity)) => (url, Some(Integrity::parse(integrity)?)),
+            None => (reference, None),
+        };
+        let (format, _) = ArchiveFormat::from_url(url).ok_or_else(|| {
+            SkillsetError::Source(format!("Not a supported archive URL: {}", url))
+        })?;
+        let name = archive_name(url).ok_or_else(|| {
+            SkillsetError::Source(format!("Unable to extract skill name from {}", url))
+        })?;
+
+        // A sha256 integrity that is already extracted needs no download
+        let cached = integrity
+            .as_ref()
+            .and_then(Integrity::sha256_hex)
+            .filter(|hex| self.cache.archive_path(hex).exists());
+        let sha256_hex = match cached {
+            Some(hex) => hex,
+            None => {
+                let data = self.download(url).await?;
+                if let Some(integrity) = &integrity {
+                    integrity.verify(&data)?;
+                }
+                let hex = format!("{:x}", Sha256::digest(&data));
+                let target = self.cache.archive_path(&hex);
+                let lock_path = self.cache.entry_lock_path(&target);
+                let reporter = self.reporter.clone();
+                tokio::task::spawn_blocking(move || {
+                    extract(&target, &lock_path, &data, format, reporter.as_ref())
+                })
+                .await
+                .map_err(|e| SkillsetError::Source(format!("Task execution failed: {}", e)))??;
+                hex
+            }
+        };
+        let source_path = skill_root(&self.cache.archive_path(&sha256_hex))?;
+
+        Ok(FetchedSkill {
+            name,
+            version: "latest".to_string(),
+            source_path: source_path.clone(),
+            metadata: SkillMetadata {
+                installed_at: chrono::Utc::now().to_rfc3339(),
+                repo_path: source_path,
+                convention: "unknown".to_string(), // Will be detected later
+                checksum: Some(format!("sha256:{}", sha256_hex)),
+                description: None,
+                author: None,
+                dependencies: Vec::new(),
+            },
+            // Extracted entries never change once in place
+            lock: None,
+        })
+    }
+
+    async fn get_metadata(&self, reference: &str) -> Result<SkillMetadata> {
+        let fetched_skill = self.fetch(reference).await?;
+        Ok(fetched_skill.metadata)
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::Archive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_with(build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        build(&mut builder);
+        builder.into_inner().unwrap()
+    }
+
+    fn append_link(builder: &mut tar::Builder<Vec<u8>>, name: &str, target: &str) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, name, target).unwrap();
+    }
+
+    fn append_raw_path(builder: &mut tar::Builder<Vec<u8>>, name: &str) {
+        // `append_data` refuses `..`, so write the name into the header directly
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(1);
+        header.set_cksum();
+        builder.append(&header, &b"x"[..]).unwrap();
+    }
+
+    #[test]
+    fn test_integrity_formats() {
+        let hex = format!("{:x}", Sha256::digest(b"skill"));
+        let sri = Integrity::Sha256(Sha256::digest(b"skill").to_vec()).to_string();
+        assert!(sri.starts_with("sha256-"));
+
+        for value in [format!("sha256:{}", hex), sri] {
+            let integrity = Integrity::parse(&value).unwrap();
+            integrity.verify(b"skill").unwrap();
+            assert_eq!(
+                integrity.verify(b"other").unwrap_err().kind(),
+                "verification"
+            );
+        }
+        assert!(Integrity::parse("sha256:abcd").is_err());
+        assert!(Integrity::parse("md5-abcd").is_err());
+    }
+
+    #[test]
+    fn test_archive_references() {
+        assert!(is_archive_reference("https://example.com/skill.tar.gz"));
+        assert!(is_archive_reference("file:///tmp/skill.zip#sha256:ab"));
+        assert!(is_archive_reference(
+            "https://example.com/skill.tgz?token=1"
+        ));
+        assert!(!is_archive_reference("https://github.com/user/skill"));
+        assert_eq!(
+            archive_name("https://example.com/dl/helper-1.2.tar.zst?x=1").as_deref(),
+            Some("helper-1.2")
+        );
+    }
+
+    #[test]
+    fn test_unsafe_entries_are_refused() {
+        let traversal = tar_with(|b| append_raw_path(b, "../evil"));
+        let absolute = tar_with(|b| append_raw_path(b, "/etc/evil"));
+        let escaping_link = tar_with(|b| append_link(b, "skill/link", "../../outside"));
+        let absolute_link = tar_with(|b| append_link(b, "link", "/etc/passwd"));
+        let through_link = tar_with(|b| {
+            append_link(b, "dir", ".");
+            append_raw_path(b, "dir/file");
+        });
+
+        for (data, reason) in [
+            (traversal, "escapes"),
+            (absolute, "absolute"),
+            (escaping_link, "outside"),
+            (absolute_link, "absolute"),
+            (through_link, "through a symlink"),
+        ] {
+            let root = tempfile::tempdir().unwrap();
+            let error = unpack_tar(data.as_slice(), root.path()).unwrap_err();
+            assert!(error.to_string().contains(reason), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_relative_links_inside_the_archive_are_kept() {
+        let data = tar_with(|b| {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(5);
+            header.set_mode(0o755);
+            b.append_data(&mut header, "skill/run.sh", &b"echo\n"[..])
+                .unwrap();
+            append_link(b, "skill/bin/run", "../run.sh");
+        });
+        let root = tempfile::tempdir().unwrap();
+        unpack_tar(data.as_slice(), root.path()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("skill/bin/run")).unwrap(),
+            "echo\n"
+        );
+        assert_eq!(skill_root(root.path()).unwrap(), root.path().join("skill"));
+    }
+}
@@ -1,22 +1,40 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use git2::Repository;
+use git2::build::RepoBuilder;
+use git2::FetchOptions;
 
+use super::git_auth::GitAuth;
 use super::{SkillSource, SourceType};
 use crate::cache::{CacheMetadata, CachePaths};
 use crate::error::{Result, SkillsetError};
+use crate::lock::FileLock;
+use crate::skill::report::{Reporter, SilentReporter};
 use crate::skill::types::{FetchedSkill, SkillMetadata};
 
 pub struct GitSource {
     cache: CachePaths,
+    reporter: Arc<dyn Reporter>,
 }
 
 impl GitSource {
     pub fn new() -> Result<Self> {
-        let cache = CachePaths::new()?;
-        cache.ensure_directories()?;
-        Ok(Self { cache })
+        Self::with_cache(CachePaths::new()?)
+    }
+
+    /// Cache directories are created on first fetch
+    pub fn with_cache(cache: CachePaths) -> Result<Self> {
+        Ok(Self {
+            cache,
+            reporter: Arc::new(SilentReporter),
+        })
+    }
+
+    /// Report waiting for another process's checkout to `reporter`
+    pub fn with_reporter(mut self, reporter: Arc<dyn Reporter>) -> Self {
+        self.reporter = reporter;
+        self
     }
 
     fn parse_reference(&self, reference: &str) -> Result<(String, Option<String>)> {
@@ -51,15 +69,20 @@ impl GitSource {
         url: &str,
         reference: Option<&str>,
         skill_name: &str,
-    ) -> Result<PathBuf> {
+    ) -> Result<(PathBuf, Arc<FileLock>)> {
         let cache_key = self.cache.git_cache_key(url, reference);
         let checkout_path = self.cache.git_checkout_path(skill_name);
+        let lock_path = self.cache.entry_lock_path(&checkout_path);
         let url_clone = url.to_string();
 
         // For now, we'll just clone directly to checkout location
         // The bare repository caching can be added later if needed
         let checkout_path_clone = checkout_path.clone();
-        tokio::task::spawn_blocking(move || {
+        let reporter = self.reporter.clone();
+        let lock = tokio::task::spawn_blocking(move || {
+            // Another process may be cloning into or reading the checkout
+            let lock = FileLock::acquire(&lock_path, reporter.as_ref())?;
+
             // Remove existing checkout if it exists
             if checkout_path_clone.exists() {
                 std::fs::remove_dir_all(&checkout_path_clone)?;
@@ -68,12 +91,11 @@ impl GitSource {
             // Clone repository directly to checkout location
             // Remove any existing directory first to avoid lock conflicts
             if checkout_path_clone.exists() {
-                std::fs::remove_dir_all(&checkout_path_clone).map_err(|e| SkillsetError::Io(e))?;
+                std::fs::remove_dir_all(&checkout_path_clone).map_err(SkillsetError::Io)?;
             }
-            Repository::clone(&url_clone, &checkout_path_clone)
-                .map_err(|e| SkillsetError::Git(e))?;
+            clone(&url_clone, &checkout_path_clone)?;
 
-            Ok::<PathBuf, SkillsetError>(checkout_path_clone)
+            Ok::<Arc<FileLock>, SkillsetError>(lock)
         })
         .await
         .map_err(|e| SkillsetError::Source(format!("Task execution failed: {}", e)))??;
@@ -89,16 +111,43 @@ impl GitSource {
         let metadata_path = self.cache.metadata_path(&cache_key);
         metadata.save(&metadata_path).await?;
 
-        Ok(checkout_path)
+        Ok((checkout_path, lock))
+    }
+}
+
+/// Clone `url`, authenticating with whatever credentials the environment
+/// offers. When none are accepted the error lists every method tried.
+fn clone(url: &str, path: &std::path::Path) -> Result<()> {
+    let mut auth = GitAuth::for_url(url);
+    let result = {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(auth.callbacks());
+        RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(url, path)
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) if auth.exhausted() || e.code() == git2::ErrorCode::Auth => {
+            Err(SkillsetError::Source(format!(
+                "Authentication failed for {}: {}\n{}",
+                url,
+                e.message(),
+                auth.summary()
+            )))
+        }
+        Err(e) => Err(SkillsetError::Git(e)),
     }
 }
 
 #[async_trait]
 impl SkillSource for GitSource {
     async fn fetch(&self, reference: &str) -> Result<FetchedSkill> {
+        self.cache.ensure_directories()?;
         let (url, ref_spec) = self.parse_reference(reference)?;
         let skill_name = self.extract_skill_name_from_url(&url)?;
-        let checkout_path = self
+        let (checkout_path, lock) = self
             .get_or_clone(&url, ref_spec.as_deref(), &skill_name)
             .await?;
 
@@ -115,6 +164,7 @@ impl SkillSource for GitSource {
                 author: None,
                 dependencies: Vec::new(),
             },
+            lock: Some(lock),
         })
     }
 
@@ -132,7 +182,6 @@ impl SkillSource for GitSource {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
 
     #[test]
     fn test_parse_reference() {
@@ -0,0 +1,421 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use git2::{Cred, CredentialType, RemoteCallbacks};
+
+/// Comma-separated private key files to offer before the ones in `~/.ssh`
+pub const SSH_KEY_VAR: &str = "SKILLSET_GIT_SSH_KEY";
+pub const SSH_PASSPHRASE_VAR: &str = "SKILLSET_GIT_SSH_PASSPHRASE";
+/// Token used for any HTTPS host, after the host-specific variables
+pub const TOKEN_VAR: &str = "SKILLSET_GIT_TOKEN";
+
+/// Default key files, in the order ssh itself tries them
+const DEFAULT_KEYS: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// One way of authenticating to a git remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Method {
+    SshAgent,
+    SshKey(PathBuf),
+    Token {
+        var: String,
+        username: String,
+        token: String,
+    },
+    CredentialHelper,
+}
+
+impl Method {
+    fn describe(&self) -> String {
+        match self {
+            Self::SshAgent => "ssh-agent".to_string(),
+            Self::SshKey(path) => format!("key file {}", path.display()),
+            Self::Token { var, .. } => format!("token from ${}", var),
+            Self::CredentialHelper => "git credential fill".to_string(),
+        }
+    }
+}
+
+/// Hands out credentials to libgit2 one method at a time. libgit2 calls back
+/// again whenever the previous credential was rejected, so each method is
+/// offered once and everything tried is kept for the error message.
+pub struct GitAuth {
+    host: String,
+    path: String,
+    protocol: &'static str,
+    ssh_methods: Vec<Method>,
+    http_methods: Vec<Method>,
+    passphrase: Option<String>,
+    /// Why methods of each kind are unavailable, added to the report once
+    /// libgit2 asks for that kind of credential
+    ssh_notes: Vec<String>,
+    http_notes: Vec<String>,
+    /// What happened with each method, in order
+    report: Vec<String>,
+    offered: Option<Method>,
+    exhausted: bool,
+}
+
+impl GitAuth {
+    /// Methods available for `url` given the current environment
+    pub fn for_url(url: &str) -> Self {
+        let ssh_dir = dirs::home_dir().map(|home| home.join(".ssh"));
+        Self::new(url, &|var| std::env::var(var).ok(), ssh_dir.as_deref())
+    }
+
+    fn new(url: &str, env: &dyn Fn(&str) -> Option<String>, ssh_dir: Option<&Path>) -> Self {
+        let (protocol, host, path) = split_url(url);
+        let mut ssh_notes = Vec::new();
+        let mut http_notes = Vec::new();
+
+        let mut ssh_methods = Vec::new();
+        if env("SSH_AUTH_SOCK").is_some() {
+            ssh_methods.push(Method::SshAgent);
+        } else {
+            ssh_notes.push("ssh-agent: not running (SSH_AUTH_SOCK is not set)".to_string());
+        }
+        let configured = env(SSH_KEY_VAR)
+            .map(|keys| crate::config::layered::parse_list(&keys))
+            .unwrap_or_default();
+        for key in &configured {
+            let path = expand_home(key);
+            if path.exists() {
+                ssh_methods.push(Method::SshKey(path));
+            } else {
+                ssh_notes.push(format!(
+                    "key file {}: does not exist (from ${})",
+                    path.display(),
+                    SSH_KEY_VAR
+                ));
+            }
+        }
+        let defaults: Vec<PathBuf> = ssh_dir
+            .map(|dir| DEFAULT_KEYS.iter().map(|key| dir.join(key)).collect())
+            .unwrap_or_default();
+        let found: Vec<PathBuf> = defaults.into_iter().filter(|key| key.exists()).collect();
+        if found.is_empty() && configured.is_empty() {
+            ssh_notes.push(format!(
+                "key files: none found in ~/.ssh and ${} is not set",
+                SSH_KEY_VAR
+            ));
+        }
+        ssh_methods.extend(found.into_iter().map(Method::SshKey));
+
+        let mut http_methods = Vec::new();
+        let token_vars = token_vars(&host);
+        for (var, username) in &token_vars {
+            if let Some(token) = env(var) {
+                http_methods.push(Method::Token {
+                    var: var.to_string(),
+                    username: username.to_string(),
+                    token,
+                });
+            }
+        }
+        if http_methods.is_empty() {
+            let names: Vec<String> = token_vars
+                .iter()
+                .map(|(var, _)| format!("${}", var))
+                .collect();
+            http_notes.push(format!("tokens: none of {} are set", names.join(", ")));
+        }
+        http_methods.push(Method::CredentialHelper);
+
+        Self {
+            host,
+            path,
+            protocol,
+            ssh_methods,
+            http_methods,
+            passphrase: env(SSH_PASSPHRASE_VAR),
+            ssh_notes,
+            http_notes,
+            report: Vec::new(),
+            offered: None,
+            exhausted: false,
+        }
+    }
+
+    /// Callbacks for a fetch or clone that draw credentials from `self`
+    pub fn callbacks(&mut self) -> RemoteCallbacks<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |_url, username, allowed| self.next(username, allowed));
+        callbacks
+    }
+
+    /// True once every applicable method was offered and rejected
+    pub fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// What was tried and why each method failed or was unavailable
+    pub fn summary(&self) -> String {
+        self.report
+            .iter()
+            .map(|line| format!("  {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn next(
+        &mut self,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        // Being called again means the credential we offered was refused
+        if let Some(method) = self.offered.take() {
+            self.report.push(format!("{}: rejected", method.describe()));
+        }
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.unwrap_or("git"));
+        }
+
+        let username = username.unwrap_or("git");
+        if allowed.contains(CredentialType::SSH_KEY) {
+            self.report.append(&mut self.ssh_notes);
+            while !self.ssh_methods.is_empty() {
+                let method = self.ssh_methods.remove(0);
+                let cred = match &method {
+                    Method::SshAgent => Cred::ssh_key_from_agent(username),
+                    Method::SshKey(path) => {
+                        Cred::ssh_key(username, None, path, self.passphrase.as_deref())
+                    }
+                    _ => unreachable!("only ssh methods are queued here"),
+                };
+                match cred {
+                    Ok(cred) => {
+                        self.offered = Some(method);
+                        return Ok(cred);
+                    }
+                    Err(e) => self
+                        .report
+                        .push(format!("{}: {}", method.describe(), e.message())),
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            self.report.append(&mut self.http_notes);
+            while !self.http_methods.is_empty() {
+                let method = self.http_methods.remove(0);
+                let credentials = match &method {
+                    Method::Token {
+                        username, token, ..
+                    } => Some((username.clone(), token.clone())),
+                    Method::CredentialHelper => {
+                        match credential_fill(self.protocol, &self.host, &self.path) {
+                            Ok(Some(credentials)) => Some(credentials),
+                            Ok(None) => {
+                                self.report.push(format!(
+                                    "{}: no credentials for {}",
+                                    method.describe(),
+                                    self.host
+                                ));
+                                None
+                            }
+                            Err(e) => {
+                                self.report.push(format!("{}: {}", method.describe(), e));
+                                None
+                            }
+                        }
+                    }
+                    _ => unreachable!("only http methods are queued here"),
+                };
+                if let Some((username, password)) = credentials {
+                    self.offered = Some(method);
+                    return Cred::userpass_plaintext(&username, &password);
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::DEFAULT) && !self.exhausted {
+            self.exhausted = true;
+            return Cred::default();
+        }
+
+        self.exhausted = true;
+        Err(git2::Error::from_str("no more credentials to try"))
+    }
+}
+
+/// Token variables for a host with the username each token type expects
+fn token_vars(host: &str) -> Vec<(&'static str, &'static str)> {
+    let mut vars = Vec::new();
+    if host == "github.com" || host.ends_with(".github.com") {
+        vars.push(("GITHUB_TOKEN", "x-access-token"));
+        vars.push(("GH_TOKEN", "x-access-token"));
+    }
+    if host == "gitlab.com" || host.contains("gitlab") {
+        vars.push(("GITLAB_TOKEN", "oauth2"));
+        vars.push(("CI_JOB_TOKEN", "gitlab-ci-token"));
+    }
+    vars.push((TOKEN_VAR, "x-access-token"));
+    vars
+}
+
+/// Protocol, host and path of an HTTPS, ssh:// or scp-style (`git@host:path`) URL
+fn split_url(url: &str) -> (&'static str, String, String) {
+    let (protocol, authority, path) = match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let protocol = if scheme == "http" { "http" } else { "https" };
+            (protocol, authority, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':').unwrap_or((url, ""));
+            ("https", authority, path)
+        }
+    };
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    (protocol, host.to_string(), path.to_string())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Ask git's configured credential helpers for a username and password.
+/// Terminal prompts are disabled so a missing helper fails instead of hanging.
+fn credential_fill(
+    protocol: &str,
+    host: &str,
+    path: &str,
+) -> std::result::Result<Option<(String, String)>, String> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not run git: {}", e))?;
+
+    let request = format!("protocol={}\nhost={}\npath={}\n\n", protocol, host, path);
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(str::to_string)
+    };
+    Ok(field("username").zip(field("password")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn auth(url: &str, vars: &[(&str, &str)], ssh_dir: Option<&Path>) -> GitAuth {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        GitAuth::new(url, &|var| vars.get(var).cloned(), ssh_dir)
+    }
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(
+            split_url("https://github.com/acme/skills.git"),
+            (
+                "https",
+                "github.com".to_string(),
+                "acme/skills.git".to_string()
+            )
+        );
+        assert_eq!(
+            split_url("git@gitlab.com:acme/skills.git"),
+            (
+                "https",
+                "gitlab.com".to_string(),
+                "acme/skills.git".to_string()
+            )
+        );
+        assert_eq!(
+            split_url("ssh://git@git.acme.dev:2222/skills.git"),
+            (
+                "https",
+                "git.acme.dev:2222".to_string(),
+                "skills.git".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_ssh_methods_are_offered_once_each() {
+        let ssh_dir = tempfile::tempdir().unwrap();
+        std::fs::write(ssh_dir.path().join("id_ed25519"), "key").unwrap();
+        std::fs::write(ssh_dir.path().join("id_rsa"), "key").unwrap();
+        let mut auth = auth(
+            "git@github.com:acme/skills.git",
+            &[("SSH_AUTH_SOCK", "/tmp/agent.sock")],
+            Some(ssh_dir.path()),
+        );
+
+        for _ in 0..3 {
+            assert!(auth.next(Some("git"), CredentialType::SSH_KEY).is_ok());
+        }
+        assert!(auth.next(Some("git"), CredentialType::SSH_KEY).is_err());
+        assert!(auth.exhausted());
+
+        let summary = auth.summary();
+        assert!(summary.contains("ssh-agent: rejected"));
+        assert!(summary.contains("id_ed25519: rejected"));
+        assert!(summary.contains("id_rsa: rejected"));
+    }
+
+    #[test]
+    fn test_missing_methods_are_explained() {
+        let ssh_dir = tempfile::tempdir().unwrap();
+        let mut auth = auth(
+            "git@github.com:acme/skills.git",
+            &[(SSH_KEY_VAR, "/nonexistent/deploy_key")],
+            Some(ssh_dir.path()),
+        );
+        assert!(auth.next(Some("git"), CredentialType::SSH_KEY).is_err());
+
+        let summary = auth.summary();
+        assert!(summary.contains("SSH_AUTH_SOCK is not set"));
+        assert!(summary.contains("/nonexistent/deploy_key: does not exist"));
+        // Tokens only matter for HTTPS remotes
+        assert!(!summary.contains("$GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_tokens_match_the_host() {
+        let auth = auth(
+            "https://gitlab.com/acme/skills.git",
+            &[("GITHUB_TOKEN", "gh"), ("GITLAB_TOKEN", "gl")],
+            None,
+        );
+        assert_eq!(
+            auth.http_methods,
+            vec![
+                Method::Token {
+                    var: "GITLAB_TOKEN".to_string(),
+                    username: "oauth2".to_string(),
+                    token: "gl".to_string(),
+                },
+                Method::CredentialHelper,
+            ]
+        );
+    }
+}
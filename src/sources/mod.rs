@@ -1,10 +1,17 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use crate::cache::CachePaths;
 use crate::error::Result;
+use crate::skill::report::{Reporter, SilentReporter};
 use crate::skill::types::FetchedSkill;
 
+pub mod archive;
 pub mod git;
+pub mod git_auth;
+pub mod oci;
+use archive::ArchiveSource;
 use git::GitSource;
 
 #[async_trait]
@@ -18,6 +25,7 @@ pub trait SkillSource: Send + Sync {
 pub enum SourceType {
     Git,
     Oci,
+    Archive,
     Local,
 }
 
@@ -27,12 +35,24 @@ pub struct SourceRegistry {
 
 impl SourceRegistry {
     pub fn new() -> Result<Self> {
+        Self::with_cache(CachePaths::new()?, Arc::new(SilentReporter))
+    }
+
+    /// Built-in sources caching into `cache`, reporting waits for other
+    /// processes' cache entries to `reporter`
+    pub fn with_cache(cache: CachePaths, reporter: Arc<dyn Reporter>) -> Result<Self> {
         let mut sources = HashMap::new();
 
         // Register built-in sources
         sources.insert(
             "git".to_string(),
-            Box::new(GitSource::new()?) as Box<dyn SkillSource>,
+            Box::new(GitSource::with_cache(cache.clone())?.with_reporter(reporter.clone()))
+                as Box<dyn SkillSource>,
+        );
+        sources.insert(
+            "archive".to_string(),
+            Box::new(ArchiveSource::with_cache(cache)?.with_reporter(reporter))
+                as Box<dyn SkillSource>,
         );
 
         Ok(Self { sources })
@@ -43,6 +63,7 @@ impl SourceRegistry {
         let type_name = match source_type {
             SourceType::Git => "git",
             SourceType::Oci => "oci",
+            SourceType::Archive => "archive",
             SourceType::Local => "local",
         };
         self.sources.insert(type_name.to_string(), source);
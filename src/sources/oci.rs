@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{SkillSource, SourceType};
+use crate::cache::CachePaths;
+use crate::config::skillset::RegistrySettings;
+use crate::error::{Result, SkillsetError};
+use crate::lock::FileLock;
+use crate::registry::client::Registries;
+use crate::registry::mirror::{self, PulledArtifact};
+use crate::registry::reference::OciReference;
+use crate::skill::report::{Reporter, SilentReporter};
+use crate::skill::types::{FetchedSkill, SkillMetadata};
+
+/// Pulls skills published as OCI artifacts whose layers are tarballs of the
+/// skill directory. Unpacked artifacts are cached by manifest digest.
+pub struct OciSource {
+    cache: CachePaths,
+    registries: Registries,
+    reporter: Arc<dyn Reporter>,
+}
+
+impl OciSource {
+    pub fn new(registries: BTreeMap<String, RegistrySettings>) -> Result<Self> {
+        Self::with_cache(CachePaths::new()?, registries)
+    }
+
+    pub fn with_cache(
+        cache: CachePaths,
+        registries: BTreeMap<String, RegistrySettings>,
+    ) -> Result<Self> {
+        Ok(Self {
+            cache,
+            registries: Registries::new(registries),
+            reporter: Arc::new(SilentReporter),
+        })
+    }
+
+    /// Report waiting for another process's unpacking to `reporter`
+    pub fn with_reporter(mut self, reporter: Arc<dyn Reporter>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
+    /// Unpack `artifact` into its cache entry on a blocking thread, as it
+    /// may wait for another process unpacking the same one
+    async fn unpack(&self, artifact: PulledArtifact) -> Result<PathBuf> {
+        let target = self.cache.oci_path(&artifact.manifest.digest);
+        let lock_path = self.cache.entry_lock_path(&target);
+        let reporter = self.reporter.clone();
+        tokio::task::spawn_blocking(move || {
+            unpack_artifact(&target, &lock_path, &artifact, reporter.as_ref()).map(|()| target)
+        })
+        .await
+        .map_err(|e| SkillsetError::Oci(format!("Task execution failed: {}", e)))?
+    }
+}
+
+/// Extract the layers of `artifact` into the cache entry `target` unless it
+/// is already there
+fn unpack_artifact(
+    target: &Path,
+    lock_path: &Path,
+    artifact: &PulledArtifact,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    // Another process may be unpacking the same artifact
+    let _lock = FileLock::acquire(lock_path, reporter)?;
+    if target.exists() {
+        return Ok(());
+    }
+
+    // Unpack next to the final location and move it into place once
+    // complete, so an interrupted pull never leaves a partial entry
+    let staging = target.with_extension(format!("tmp-{}", std::process::id()));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    for (descriptor, data) in &artifact.blobs {
+        let media_type = descriptor.media_type.as_str();
+        if media_type.ends_with("tar+gzip") || media_type.ends_with("tar.gzip") {
+            unpack_tar(flate2::read::GzDecoder::new(data.as_slice()), &staging)?;
+        } else if media_type.ends_with(".tar") || media_type.ends_with("+tar") {
+            unpack_tar(data.as_slice(), &staging)?;
+        }
+    }
+
+    std::fs::rename(&staging, target)?;
+    Ok(())
+}
+
+fn unpack_tar<R: Read>(reader: R, target: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        // unpack_in refuses entries that would land outside `target`
+        if !entry.unpack_in(target)? {
+            return Err(SkillsetError::Oci(format!(
+                "Layer entry escapes the skill directory: {}",
+                entry.path()?.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl SkillSource for OciSource {
+    async fn fetch(&self, reference: &str) -> Result<FetchedSkill> {
+        self.cache.ensure_directories()?;
+        let reference = OciReference::parse(reference)?;
+
+        // A pinned digest that is already unpacked needs no network at all
+        let cached = reference
+            .digest
+            .as_ref()
+            .map(|digest| (digest.clone(), self.cache.oci_path(digest)))
+            .filter(|(_, path)| path.exists());
+        let (digest, source_path) = match cached {
+            Some(cached) => cached,
+            None => {
+                let artifact = mirror::pull(&reference, &self.registries).await?;
+                tracing::debug!("Pulled {} from {}", reference, artifact.host);
+                (
+                    artifact.manifest.digest.clone(),
+                    self.unpack(artifact).await?,
+                )
+            }
+        };
+
+        Ok(FetchedSkill {
+            name: reference.name().to_string(),
+            version: reference.tag.clone().unwrap_or_else(|| digest.clone()),
+            source_path: source_path.clone(),
+            metadata: SkillMetadata {
+                installed_at: chrono::Utc::now().to_rfc3339(),
+                repo_path: source_path,
+                convention: "unknown".to_string(), // Will be detected later
+                checksum: Some(digest),
+                description: None,
+                author: None,
+                dependencies: Vec::new(),
+            },
+            // Unpacked entries never change once in place
+            lock: None,
+        })
+    }
+
+    async fn get_metadata(&self, reference: &str) -> Result<SkillMetadata> {
+        let fetched_skill = self.fetch(reference).await?;
+        Ok(fetched_skill.metadata)
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::Oci
+    }
+}
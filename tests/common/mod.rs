@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 pub struct TestProject {
@@ -58,6 +60,62 @@ impl TestProject {
     }
 }
 
+/// Create a local git repository containing the given files, so tests can
+/// install skills without network access. Returns the repository path.
+pub fn create_git_skill(
+    parent: &Path,
+    name: &str,
+    files: &[(&str, &str)],
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let repo_path = parent.join(name);
+    std::fs::create_dir_all(&repo_path)?;
+    let repo = git2::Repository::init(&repo_path)?;
+
+    for (file, content) in files {
+        let file_path = repo_path.join(file);
+        if let Some(dir) = file_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&file_path, content)?;
+    }
+
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = git2::Signature::now("skillset", "skillset@example.com")?;
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])?;
+
+    Ok(repo_path)
+}
+
+/// Commit changed files on top of a repository made by `create_git_skill`
+pub fn commit_to_git_skill(
+    repo_path: &Path,
+    files: &[(&str, &str)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = git2::Repository::open(repo_path)?;
+    for (file, content) in files {
+        std::fs::write(repo_path.join(file), content)?;
+    }
+
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let parent = repo.head()?.peel_to_commit()?;
+    let signature = git2::Signature::now("skillset", "skillset@example.com")?;
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "change",
+        &tree,
+        &[&parent],
+    )?;
+    Ok(())
+}
+
 impl Drop for TestProject {
     fn drop(&mut self) {
         // TempDir will automatically clean up
@@ -0,0 +1,122 @@
+use serde_json::Value;
+
+mod common;
+
+fn run(
+    test_project: &common::TestProject,
+    home: &std::path::Path,
+    args: &[&str],
+) -> (bool, String) {
+    let mut cmd = test_project.run_skillset_command(args);
+    cmd.env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME");
+    let output = cmd.output().expect("Failed to run skillset");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )
+}
+
+/// A release-style tarball wrapping the skill in a `helper-1.0/` directory
+fn create_tarball(dir: &std::path::Path) -> std::path::PathBuf {
+    let path = dir.join("helper.tar.gz");
+    let file = std::fs::File::create(&path).unwrap();
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let content = b"# Helper\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    builder
+        .append_data(&mut header, "helper-1.0/SKILL.md", &content[..])
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+    path
+}
+
+fn create_zip(dir: &std::path::Path) -> std::path::PathBuf {
+    use std::io::Write;
+
+    let path = dir.join("helper.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    zip.start_file("SKILL.md", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"# Helper\n").unwrap();
+    zip.finish().unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_add_tarball_pins_its_hash() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let tarball = create_tarball(home.path());
+    let reference = format!("file://{}", tarball.display());
+
+    let (ok, _) = run(&test_project, home.path(), &["add", &reference]);
+    assert!(ok);
+
+    let manifest: Value =
+        serde_json::from_str(&test_project.read_skillset_config().unwrap()).unwrap();
+    assert_eq!(
+        manifest["skills"]["helper"]["source"],
+        format!("archive:{}", reference)
+    );
+    let lockfile: Value = serde_json::from_str(
+        &std::fs::read_to_string(test_project.project_path().join("skillset.lock")).unwrap(),
+    )
+    .unwrap();
+    let locked = lockfile["skills"]["helper"]["source"].as_str().unwrap();
+    assert!(locked.starts_with(&format!("archive:{}#sha256:", reference)));
+
+    // The wrapping directory is stripped, the skill itself is installed
+    let installed = test_project
+        .project_path()
+        .join(lockfile["skills"]["helper"]["path"].as_str().unwrap());
+    assert!(installed.join("SKILL.md").exists());
+
+    // Reinstalling from the pinned hash works once the archive is gone
+    std::fs::remove_file(&tarball).unwrap();
+    std::fs::remove_dir_all(&installed).unwrap();
+    let (ok, _) = run(&test_project, home.path(), &["install"]);
+    assert!(ok);
+    assert!(installed.join("SKILL.md").exists());
+}
+
+#[tokio::test]
+async fn test_integrity_mismatch_and_requirement() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let zip = create_zip(home.path());
+    let wrong = format!("sha256:{}", "0".repeat(64));
+
+    let reference = format!("file://{}#{}", zip.display(), wrong);
+    let (ok, stdout) = run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--format", "json"],
+    );
+    assert!(!ok);
+    let document: Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(document["error"]["kind"], "verification");
+    assert!(document["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("integrity mismatch"));
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+
+    // Without a hash the archive is refused once integrity is required
+    let reference = format!("file://{}", zip.display());
+    let mut cmd = test_project.run_skillset_command(&["add", &reference]);
+    cmd.env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME")
+        .env("SKILLSET_INTEGRITY", "required");
+    assert!(!cmd.output().unwrap().status.success());
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+
+    let (ok, _) = run(&test_project, home.path(), &["add", &reference]);
+    assert!(ok);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+const SKILL_MD: &str = "---\nname: helper\ndescription: Helps with things\n---\n# Helper\n";
+
+fn run(test_project: &common::TestProject, home: &std::path::Path, args: &[&str]) -> bool {
+    let mut cmd = test_project.run_skillset_command(args);
+    cmd.env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME");
+    cmd.output()
+        .expect("Failed to run skillset")
+        .status
+        .success()
+}
+
+fn claude_project() -> common::TestProject {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(
+            r#"{ "skills": {}, "conventions": ["claude-code", "agent-skills"] }"#,
+        )
+        .unwrap();
+    test_project
+}
+
+#[tokio::test]
+async fn test_skill_md_installs_into_claude_skills() {
+    let test_project = claude_project();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", SKILL_MD)])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    assert!(run(&test_project, home.path(), &["add", &reference]));
+    let installed = test_project.project_path().join(".claude/skills/helper");
+    assert_eq!(
+        std::fs::read_to_string(installed.join("SKILL.md")).unwrap(),
+        SKILL_MD
+    );
+
+    // Hand-written skills next to it are not drift
+    let own = test_project.project_path().join(".claude/skills/mine");
+    std::fs::create_dir_all(&own).unwrap();
+    std::fs::write(own.join("SKILL.md"), "# Mine").unwrap();
+    assert!(run(&test_project, home.path(), &["verify"]));
+
+    assert!(run(
+        &test_project,
+        home.path(),
+        &[
+            "add",
+            &reference,
+            "--global",
+            "--conventions",
+            "claude-code"
+        ]
+    ));
+    assert!(home.path().join(".claude/skills/helper/SKILL.md").exists());
+}
+
+#[tokio::test]
+async fn test_invalid_or_colliding_skills_are_refused() {
+    let test_project = claude_project();
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    let invalid = common::create_git_skill(home.path(), "nameless", &[("SKILL.md", "# Nameless")])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", invalid.display());
+    assert!(!run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--convention", "claude-code"],
+    ));
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+
+    // A skill someone put there by hand is left alone
+    let own = test_project.project_path().join(".claude/skills/helper");
+    std::fs::create_dir_all(&own).unwrap();
+    std::fs::write(own.join("SKILL.md"), "# Mine").unwrap();
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", SKILL_MD)])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+    assert!(!run(&test_project, home.path(), &["add", &reference]));
+    assert_eq!(
+        std::fs::read_to_string(own.join("SKILL.md")).unwrap(),
+        "# Mine"
+    );
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+const SKILL_MD: &str = "---\nname: helper\ndescription: Helps with things\n---\n# Helper\n";
+
+#[tokio::test]
+async fn test_skill_is_converted_for_a_requested_convention() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[("SKILL.md", SKILL_MD), ("scripts/run.sh", "echo helper\n")],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference, "--convention", "langchain"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let installed = test_project.project_path().join("skills/langchain/helper");
+    assert_eq!(
+        std::fs::read_to_string(installed.join("tool.yaml")).unwrap(),
+        "name: helper\ndescription: Helps with things\nmodule: helper.py\n"
+    );
+    assert!(std::fs::read_to_string(installed.join("helper.py"))
+        .unwrap()
+        .contains("def helper(task: str) -> str:"));
+    assert_eq!(
+        std::fs::read_to_string(installed.join("INSTRUCTIONS.md")).unwrap(),
+        "# Helper\n"
+    );
+    assert!(installed.join("scripts/run.sh").exists());
+    assert!(!installed.join("SKILL.md").exists());
+
+    let output = test_project
+        .run_skillset_command(&["verify"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn test_convert_writes_what_a_convention_would_install() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let skill = test_project.project_path().join("helper");
+    std::fs::create_dir_all(&skill).unwrap();
+    std::fs::write(skill.join("SKILL.md"), SKILL_MD).unwrap();
+
+    let output = test_project
+        .run_skillset_command(&["convert", "helper", "--to", "cursor", "--format", "json"])
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["data"]["from"], "SKILL.md");
+    assert_eq!(document["data"]["to"], "cursor");
+
+    let rule = test_project.project_path().join("helper-cursor/helper.mdc");
+    assert_eq!(
+        std::fs::read_to_string(rule).unwrap(),
+        "---\ndescription: Helps with things\nglobs: ''\nalwaysApply: false\n---\n# Helper\n"
+    );
+
+    // Auto-GPT skills are code, there is nothing to generate them from
+    let output = test_project
+        .run_skillset_command(&["convert", "helper", "--to", "autogpt"])
+        .output()
+        .expect("Failed to run skillset");
+    assert!(!output.status.success());
+}
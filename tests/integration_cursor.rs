@@ -0,0 +1,175 @@
+mod common;
+
+#[tokio::test]
+async fn test_skill_md_is_installed_as_a_cursor_rule() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["cursor", "agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[
+            (
+                "SKILL.md",
+                "---\nname: helper\ndescription: \"Helps with things: all of them\"\nglobs: \"*.rs\"\n---\n# Helper\n",
+            ),
+            ("scripts/run.sh", "echo helper\n"),
+        ],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    // A dry run locks what the real install will
+    let output = test_project
+        .run_skillset_command(&[
+            "add",
+            &reference,
+            "--convention",
+            "cursor",
+            "--dry-run",
+            "--format",
+            "json",
+        ])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let planned = document["data"]["plan"]["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|file| file["path"] == "skillset.lock")
+        .and_then(|file| file["diff"].as_str())
+        .unwrap()
+        .to_string();
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference, "--convention", "cursor"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+
+    let rules = test_project.project_path().join(".cursor/rules");
+    assert_eq!(
+        std::fs::read_to_string(rules.join("helper.mdc")).unwrap(),
+        "---\ndescription: 'Helps with things: all of them'\nglobs: '*.rs'\nalwaysApply: false\n---\n\
+         <!-- skillset:begin helper -->\n# Helper\n\n\
+         Scripts and resources for this skill are in `.cursor/rules/helper/`.\n\
+         <!-- skillset:end helper -->\n"
+    );
+    let installed = rules.join("helper");
+    assert!(installed.join("scripts/run.sh").exists());
+    assert!(!installed.join("SKILL.md").exists());
+    assert!(!installed.join("helper.mdc").exists());
+
+    let lockfile =
+        std::fs::read_to_string(test_project.project_path().join("skillset.lock")).unwrap();
+    let checksum = lockfile
+        .lines()
+        .find(|line| line.contains("\"checksum\""))
+        .unwrap()
+        .trim()
+        .trim_end_matches(',');
+    assert!(
+        planned.contains(checksum),
+        "{} not in {}",
+        checksum,
+        planned
+    );
+
+    // What was installed is what the lockfile recorded
+    let output = test_project
+        .run_skillset_command(&["verify"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn test_removing_a_cursor_skill_deletes_its_rule() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["cursor", "agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[(
+            "SKILL.md",
+            "---\nname: helper\ndescription: Helps\n---\n# Helper\n",
+        )],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    for args in [
+        vec!["add", reference.as_str(), "--convention", "cursor"],
+        vec!["remove", "helper"],
+    ] {
+        let output = test_project
+            .run_skillset_command(&args)
+            .env("HOME", home.path())
+            .output()
+            .expect("Failed to run skillset");
+        assert!(output.status.success());
+    }
+
+    let rules = test_project.project_path().join(".cursor/rules");
+    assert!(!rules.join("helper.mdc").exists());
+    assert!(!rules.join("helper").exists());
+}
+
+#[tokio::test]
+async fn test_reinstalling_rewrites_the_rule_frontmatter() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["cursor", "agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[(
+            "SKILL.md",
+            "---\nname: helper\ndescription: Helps\n---\n# Helper\n",
+        )],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let run = |args: &[&str]| {
+        let output = test_project
+            .run_skillset_command(args)
+            .env("HOME", home.path())
+            .output()
+            .expect("Failed to run skillset");
+        assert!(output.status.success());
+    };
+    run(&["add", &reference, "--convention", "cursor"]);
+
+    common::commit_to_git_skill(
+        &repo,
+        &[(
+            "SKILL.md",
+            "---\nname: helper\ndescription: Helps more\nglobs: \"*.rs\"\nalwaysApply: true\n---\n# Helper\n",
+        )],
+    )
+    .unwrap();
+    run(&["add", &reference, "--convention", "cursor"]);
+
+    let rule =
+        std::fs::read_to_string(test_project.project_path().join(".cursor/rules/helper.mdc"))
+            .unwrap();
+    assert!(
+        rule.starts_with("---\ndescription: Helps more\nglobs: '*.rs'\nalwaysApply: true\n---\n"),
+        "{}",
+        rule
+    );
+    assert_eq!(rule.matches("description:").count(), 1);
+}
@@ -0,0 +1,98 @@
+use serde_json::Value;
+
+mod common;
+
+fn run(
+    test_project: &common::TestProject,
+    home: &std::path::Path,
+    args: &[&str],
+) -> (bool, String) {
+    let mut cmd = test_project.run_skillset_command(args);
+    cmd.env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME");
+    let output = cmd.output().expect("Failed to run skillset");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_add_dry_run_changes_nothing() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let (ok, stdout) = run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--dry-run", "--format", "json"],
+    );
+    assert!(ok);
+    let document: Value = serde_json::from_str(&stdout).expect("add output is not JSON");
+    assert_eq!(document["data"]["dry_run"], true);
+
+    let plan = &document["data"]["plan"];
+    assert_eq!(plan["steps"][0]["action"], "fetch");
+    assert_eq!(plan["steps"][0]["name"], "helper");
+    assert_eq!(plan["steps"][0]["source"], reference);
+    assert_eq!(plan["steps"][1]["action"], "place");
+    assert!(plan["steps"][1]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("helper"));
+
+    let files: Vec<&str> = plan["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(files, vec!["skillset.json", "skillset.lock"]);
+    assert!(plan["files"][0]["diff"]
+        .as_str()
+        .unwrap()
+        .contains("+    \"helper\": {"));
+
+    // Neither the project nor the cache were touched
+    let entries: Vec<_> = std::fs::read_dir(test_project.project_path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, vec!["skillset.json"]);
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+    assert!(!home.path().join(".cache").exists());
+}
+
+#[tokio::test]
+async fn test_remove_dry_run_keeps_the_skill() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let (ok, _) = run(&test_project, home.path(), &["add", &reference]);
+    assert!(ok);
+    let manifest = test_project.read_skillset_config().unwrap();
+
+    let (ok, stdout) = run(
+        &test_project,
+        home.path(),
+        &["remove", "helper", "--dry-run"],
+    );
+    assert!(ok);
+    assert!(stdout.starts_with("Dry run, nothing was changed:\n"));
+    assert!(stdout.contains("  remove helper from "));
+    assert!(stdout.contains("-    \"helper\": {"));
+    assert!(!stdout.contains("Successfully removed"));
+
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+    let (ok, stdout) = run(&test_project, home.path(), &["list"]);
+    assert!(ok);
+    assert!(stdout.contains("helper"));
+}
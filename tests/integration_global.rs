@@ -0,0 +1,49 @@
+use assert_cmd::prelude::*;
+
+mod common;
+
+#[tokio::test]
+async fn test_global_add_list_remove() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "global-helper",
+        &[("SKILL.md", "# Global helper")],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    // Install for the user rather than the project
+    let mut cmd = test_project.run_skillset_command(&["add", "--global", &reference]);
+    cmd.env("HOME", home.path()).env_remove("XDG_CONFIG_HOME");
+    cmd.assert().success();
+
+    let global_manifest = home.path().join(".config/skillset/skillset.json");
+    let manifest = std::fs::read_to_string(&global_manifest).expect("No global manifest");
+    assert!(manifest.contains("global-helper"));
+    assert!(home
+        .path()
+        .join(".agents/skills/global-helper/SKILL.md")
+        .exists());
+
+    // The project itself is untouched
+    let project_config = test_project
+        .read_skillset_config()
+        .expect("Failed to read skillset.json");
+    assert!(!project_config.contains("global-helper"));
+
+    // Listing shows global and project skills separately
+    let mut cmd = test_project.run_skillset_command(&["list"]);
+    cmd.env("HOME", home.path()).env_remove("XDG_CONFIG_HOME");
+    let output = cmd.output().expect("Failed to run list");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No project skills installed."));
+    assert!(stdout.contains("Installed global skills:"));
+    assert!(stdout.contains("global-helper"));
+
+    let mut cmd = test_project.run_skillset_command(&["remove", "--global", "global-helper"]);
+    cmd.env("HOME", home.path()).env_remove("XDG_CONFIG_HOME");
+    cmd.assert().success();
+    assert!(!home.path().join(".agents/skills/global-helper").exists());
+}
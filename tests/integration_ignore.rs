@@ -0,0 +1,68 @@
+mod common;
+
+#[tokio::test]
+async fn test_only_the_files_a_package_ships_are_installed() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[
+            (
+                "SKILL.md",
+                "---\nname: helper\ndescription: Helps\n---\n# Helper\n",
+            ),
+            ("scripts/run.sh", "echo helper\n"),
+            ("scripts/debug.sh", "set -x\n"),
+            ("tests/run_test.sh", "exit 0\n"),
+            ("assets/demo.mp4", "video\n"),
+            (".github/workflows/ci.yml", "on: push\n"),
+            (".skillsetignore", "tests/\n*.mp4\n"),
+            ("skill.yaml", "exclude:\n  - scripts/debug.sh\n"),
+        ],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let installed = test_project
+        .project_path()
+        .join("skills/agent-skills/helper");
+    assert!(installed.join("SKILL.md").exists());
+    assert!(installed.join("scripts/run.sh").exists());
+    assert!(installed.join("skill.yaml").exists());
+    for left_out in [
+        ".git",
+        ".github",
+        ".skillsetignore",
+        "tests",
+        "assets/demo.mp4",
+        "scripts/debug.sh",
+    ] {
+        assert!(
+            !installed.join(left_out).exists(),
+            "{} was installed",
+            left_out
+        );
+    }
+
+    let output = test_project
+        .run_skillset_command(&["verify"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+}
@@ -0,0 +1,127 @@
+mod common;
+
+fn run(test_project: &common::TestProject, home: &std::path::Path, args: &[&str]) {
+    let output = test_project
+        .run_skillset_command(args)
+        .env("HOME", home)
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[tokio::test]
+async fn test_agents_md_section_leaves_hand_written_text_alone() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["agents-md"] }"#)
+        .unwrap();
+    let agents_md = test_project.project_path().join("AGENTS.md");
+    let hand_written = "# Agents\n\nRun `make test` before committing.\n";
+    std::fs::write(&agents_md, hand_written).unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[
+            (
+                "SKILL.md",
+                "---\nname: helper\ndescription: Helps with things\n---\nAlways be helpful.\n",
+            ),
+            ("scripts/run.sh", "echo helper\n"),
+        ],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--convention", "agents-md"],
+    );
+    let content = std::fs::read_to_string(&agents_md).unwrap();
+    assert!(content.starts_with(hand_written));
+    assert!(content.contains(
+        "<!-- skillset:begin helper -->\nAlways be helpful.\n\nScripts and resources for this \
+         skill are in `skills/agents-md/helper/`.\n<!-- skillset:end helper -->\n"
+    ));
+    assert!(test_project
+        .project_path()
+        .join("skills/agents-md/helper/scripts/run.sh")
+        .exists());
+
+    // Reinstalling replaces the section instead of adding another
+    run(&test_project, home.path(), &["update", "helper"]);
+    let updated = std::fs::read_to_string(&agents_md).unwrap();
+    assert_eq!(updated, content);
+
+    run(&test_project, home.path(), &["remove", "helper"]);
+    assert_eq!(std::fs::read_to_string(&agents_md).unwrap(), hand_written);
+}
+
+#[tokio::test]
+async fn test_copilot_skill_with_globs_gets_its_own_instructions_file() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["copilot"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "react",
+        &[(
+            "SKILL.md",
+            "---\nname: react\ndescription: React conventions\nglobs:\n  - \"*.tsx\"\n  - \"*.jsx\"\n---\nUse function components.\n",
+        )],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--convention", "copilot"],
+    );
+    let instructions = test_project
+        .project_path()
+        .join(".github/instructions/react.instructions.md");
+    assert_eq!(
+        std::fs::read_to_string(&instructions).unwrap(),
+        "---\napplyTo: '*.tsx,*.jsx'\n---\n<!-- skillset:begin react -->\nUse function \
+         components.\n<!-- skillset:end react -->\n"
+    );
+    assert!(!test_project
+        .project_path()
+        .join(".github/copilot-instructions.md")
+        .exists());
+
+    // Reinstalling writes the new globs, quoted as YAML needs
+    common::commit_to_git_skill(
+        &repo,
+        &[(
+            "SKILL.md",
+            "---\nname: react\ndescription: React conventions\nglobs: 'app/\"odd\"\\*.tsx'\n---\nUse function components.\n",
+        )],
+    )
+    .unwrap();
+    run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--convention", "copilot"],
+    );
+    let content = std::fs::read_to_string(&instructions).unwrap();
+    let frontmatter = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map(|(frontmatter, _)| frontmatter)
+        .unwrap();
+    let frontmatter: serde_yaml::Mapping = serde_yaml::from_str(frontmatter).unwrap();
+    assert_eq!(frontmatter["applyTo"], "app/\"odd\"\\*.tsx");
+
+    // Nothing else is left in the file, so it goes with the skill
+    run(&test_project, home.path(), &["remove", "react"]);
+    assert!(!instructions.exists());
+}
@@ -0,0 +1,64 @@
+use std::sync::{Arc, Mutex};
+
+use skillset::skill::{Event, Reporter, SkillManager};
+
+mod common;
+
+#[derive(Default)]
+struct CollectingReporter {
+    events: Mutex<Vec<Event>>,
+}
+
+impl Reporter for CollectingReporter {
+    fn report(&self, event: &Event) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_manager_returns_results_and_reports_progress() {
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    std::env::set_var("HOME", home.path());
+    std::env::remove_var("XDG_CONFIG_HOME");
+    std::env::remove_var("XDG_CACHE_HOME");
+
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let reporter = Arc::new(CollectingReporter::default());
+    let mut manager = SkillManager::new(test_project.project_path().clone())
+        .unwrap()
+        .with_reporter(reporter.clone())
+        .unwrap();
+
+    let added = manager
+        .add(&format!("git:{}", repo.display()), None, Vec::new(), None)
+        .await
+        .unwrap();
+    assert_eq!(added.name, "helper");
+    let path = added.path.clone().expect("No install path recorded");
+    assert!(test_project.project_path().join(&path).exists());
+
+    let report = manager.install_all().await.unwrap();
+    assert!(report.installed.is_empty());
+    assert_eq!(report.up_to_date, vec![added.clone()]);
+
+    let info = manager.skill_info("helper").unwrap();
+    assert_eq!(info.locked.map(|locked| locked.path), Some(path.clone()));
+
+    let removed = manager.remove_skill("helper").await.unwrap();
+    assert_eq!(removed, added);
+    assert!(manager.installed_skills().is_empty());
+
+    let events = reporter.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            Event::Installed(added.clone()),
+            Event::UpToDate(added),
+            Event::Removed {
+                name: "helper".to_string()
+            },
+        ]
+    );
+}
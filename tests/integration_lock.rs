@@ -0,0 +1,92 @@
+use std::io::Write;
+
+mod common;
+
+/// Lock the project the way another skillset process would
+fn hold_project_lock(test_project: &common::TestProject) -> std::fs::File {
+    let mut file = std::fs::File::create(test_project.project_path().join(".skillset-lock"))
+        .expect("Failed to create lock file");
+    file.try_lock().expect("Failed to lock project");
+    write!(file, "4242").unwrap();
+    file
+}
+
+#[tokio::test]
+async fn test_gives_up_on_a_held_project_lock() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let _lock = hold_project_lock(&test_project);
+
+    let output = test_project
+        .run_skillset_command(&["remove", "helper", "--format", "json"])
+        .env("HOME", home.path())
+        .env("SKILLSET_LOCK_TIMEOUT", "0")
+        .output()
+        .expect("Failed to run skillset");
+    assert!(!output.status.success());
+
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["error"]["kind"], "lock");
+    assert!(document["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("held by pid 4242"));
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+}
+
+#[tokio::test]
+async fn test_waits_for_the_project_lock() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+    let lock = hold_project_lock(&test_project);
+
+    let child = test_project
+        .run_skillset_command(&["add", &reference])
+        .env("HOME", home.path())
+        .env("SKILLSET_LOCK_TIMEOUT", "30")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to run skillset");
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    drop(lock);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Waiting for lock on"));
+    assert!(stderr.contains("held by pid 4242"));
+    assert!(test_project
+        .read_skillset_config()
+        .unwrap()
+        .contains("helper"));
+}
+
+#[tokio::test]
+async fn test_manifest_edits_take_the_project_lock() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let _lock = hold_project_lock(&test_project);
+
+    for args in [
+        &["config", "set", "registry", "registry.example.com/skills"][..],
+        &["config", "convert", "--to", "yaml"],
+        &["convention", "enable", "cursor"],
+        &["convention", "disable", "langchain"],
+    ] {
+        let output = test_project
+            .run_skillset_command(args)
+            .env("HOME", home.path())
+            .env("SKILLSET_LOCK_TIMEOUT", "0")
+            .output()
+            .expect("Failed to run skillset");
+        assert!(!output.status.success(), "{:?} did not wait", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("held by pid 4242"));
+    }
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+const MCP_JSON: &str = r#"{
+  "name": "notes",
+  "description": "Searches the team's notes",
+  "command": "node",
+  "args": ["${SKILL_DIR}/server.js"],
+  "inputs": [{ "name": "NOTES_TOKEN", "description": "API token for the notes service" }]
+}
+"#;
+
+fn notes_repo(home: &std::path::Path) -> String {
+    let repo = common::create_git_skill(
+        home,
+        "notes",
+        &[("mcp.json", MCP_JSON), ("server.js", "// serves notes\n")],
+    )
+    .expect("Failed to create skill repo");
+    format!("git:{}", repo.display())
+}
+
+#[tokio::test]
+async fn test_mcp_server_is_merged_into_the_project_config() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["mcp", "cursor-mcp"] }"#)
+        .unwrap();
+    let project = test_project.project_path();
+    let mcp_json = project.join(".mcp.json");
+    let hand_written =
+        "{\n  \"mcpServers\": {\n    \"local\": {\n      \"command\": \"./serve\"\n    }\n  }\n}\n";
+    std::fs::write(&mcp_json, hand_written).unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = notes_repo(home.path());
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference, "--target", "mcp,cursor-mcp"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&mcp_json).unwrap()).unwrap();
+    assert_eq!(config["mcpServers"]["local"]["command"], "./serve");
+    let notes = &config["mcpServers"]["notes"];
+    assert_eq!(notes["command"], "node");
+    assert_eq!(
+        notes["args"][0],
+        project
+            .join("skills/mcp/notes/server.js")
+            .display()
+            .to_string()
+    );
+    assert_eq!(notes["env"]["NOTES_TOKEN"], "${NOTES_TOKEN}");
+    assert!(project.join("skills/mcp/notes/server.js").exists());
+
+    let cursor: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(project.join(".cursor/mcp.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        cursor["mcpServers"]["notes"]["env"]["NOTES_TOKEN"],
+        "${env:NOTES_TOKEN}"
+    );
+
+    let output = test_project
+        .run_skillset_command(&["remove", "notes"])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(output.status.success());
+    assert_eq!(std::fs::read_to_string(&mcp_json).unwrap(), hand_written);
+    assert!(!project.join(".cursor/mcp.json").exists());
+}
+
+#[tokio::test]
+async fn test_hand_written_server_of_the_same_name_is_not_replaced() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["mcp"] }"#)
+        .unwrap();
+    let mcp_json = test_project.project_path().join(".mcp.json");
+    let hand_written = r#"{ "mcpServers": { "notes": { "command": "my-notes" } } }"#;
+    std::fs::write(&mcp_json, hand_written).unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = notes_repo(home.path());
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not installed by skillset"));
+    assert_eq!(std::fs::read_to_string(&mcp_json).unwrap(), hand_written);
+}
+
+#[tokio::test]
+async fn test_claude_desktop_config_gets_the_values() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = notes_repo(home.path());
+    let run = |args: &[&str], token: Option<&str>| {
+        let mut command = test_project.run_skillset_command(args);
+        command
+            .env("HOME", home.path())
+            .env("SKILLSET_CONVENTIONS", "claude-desktop")
+            .env_remove("NOTES_TOKEN");
+        if let Some(token) = token {
+            command.env("NOTES_TOKEN", token);
+        }
+        command.output().expect("Failed to run skillset")
+    };
+
+    let add = [
+        "add",
+        "--global",
+        &reference,
+        "--convention",
+        "claude-desktop",
+    ];
+
+    // Without a terminal to ask on, the value has to be in the environment
+    let output = run(&add, None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("NOTES_TOKEN"));
+
+    let output = run(&add, Some("secret"));
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config_path = home
+        .path()
+        .join(skillset::conventions::ClaudeDesktopConvention::config_path());
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["mcpServers"]["notes"]["env"]["NOTES_TOKEN"],
+        "secret"
+    );
+
+    let output = run(&["remove", "--global", "notes"], None);
+    assert!(output.status.success());
+    assert!(!config_path.exists());
+}
@@ -0,0 +1,157 @@
+use serde_json::Value;
+
+mod common;
+
+fn run_json(
+    test_project: &common::TestProject,
+    home: &std::path::Path,
+    args: &[&str],
+) -> (bool, String) {
+    let mut cmd = test_project.run_skillset_command(args);
+    cmd.env("HOME", home).env_remove("XDG_CONFIG_HOME");
+    let output = cmd.output().expect("Failed to run skillset");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_add_and_list_as_json() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let (ok, stdout) = run_json(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--format", "json"],
+    );
+    assert!(ok);
+    // Progress text must not leak into the JSON document
+    let document: Value = serde_json::from_str(&stdout).expect("add output is not JSON");
+    assert_eq!(document["schema_version"], 1);
+    assert_eq!(document["command"], "add");
+    assert_eq!(document["ok"], true);
+    assert_eq!(document["data"]["installed"][0]["name"], "helper");
+
+    let (ok, stdout) = run_json(&test_project, home.path(), &["list", "--format", "json"]);
+    assert!(ok);
+    let document: Value = serde_json::from_str(&stdout).expect("list output is not JSON");
+    let project = document["data"]["project"].as_array().unwrap();
+    assert_eq!(project.len(), 1);
+    assert_eq!(project[0]["name"], "helper");
+    assert_eq!(project[0]["scope"], "project");
+    assert!(project[0]["path"].as_str().unwrap().ends_with("helper"));
+    assert_eq!(document["data"]["global"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_errors_are_structured() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    let (ok, stdout) = run_json(
+        &test_project,
+        home.path(),
+        &["info", "missing", "--format", "json"],
+    );
+    assert!(!ok);
+    let document: Value = serde_json::from_str(&stdout).expect("error output is not JSON");
+    assert_eq!(document["ok"], false);
+    assert_eq!(document["error"]["kind"], "skill_not_found");
+    assert!(document["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("missing"));
+}
+
+#[tokio::test]
+async fn test_ndjson_streams_events_before_the_result() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(
+            r#"{ "skills": { "helper": { "version": "1.0.0", "source": "git:https://example.com/helper.git" } } }"#,
+        )
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    // A git skill is skipped by mirror sync, which is reported as an event
+    let (ok, stdout) = run_json(
+        &test_project,
+        home.path(),
+        &["mirror", "sync", "localhost:5000", "--format", "ndjson"],
+    );
+    assert!(ok);
+    let lines: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("line is not JSON"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["type"], "event");
+    assert_eq!(lines[0]["event"], "skipped");
+    assert_eq!(lines[0]["name"], "helper");
+    assert_eq!(lines[1]["type"], "result");
+    assert_eq!(lines[1]["command"], "mirror sync");
+    assert_eq!(lines[1]["data"]["synced"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_unknown_manifest_keys_are_warned_about() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(
+            r#"{ "skills": { "helper": { "version": "1.0.0", "convetion": "cursor" } } }"#,
+        )
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    let mut cmd = test_project.run_skillset_command(&["list", "--format", "json"]);
+    cmd.env("HOME", home.path()).env_remove("XDG_CONFIG_HOME");
+    let output = cmd.output().expect("Failed to run skillset");
+    assert!(output.status.success());
+    // The typo does not stop the command or leak into the JSON document
+    let document: Value = serde_json::from_slice(&output.stdout).expect("list output is not JSON");
+    assert_eq!(document["ok"], true);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning:"), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("unknown field `convetion`"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("did you mean `convention`?"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn test_config_convert_keeps_the_original() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let original = "# kept by hand\nskills: {}\n";
+    std::fs::remove_file(test_project.skillset_config_path()).unwrap();
+    std::fs::write(test_project.project_path().join("skillset.yaml"), original).unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    let (ok, stdout) = run_json(
+        &test_project,
+        home.path(),
+        &["config", "convert", "--to", "toml", "--format", "json"],
+    );
+    assert!(ok);
+    let document: Value = serde_json::from_str(&stdout).expect("convert output is not JSON");
+    assert_eq!(document["data"]["converted"], true);
+    let backup = test_project.project_path().join("skillset.yaml.bak");
+    assert_eq!(document["data"]["backup"], backup.to_str().unwrap());
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), original);
+    assert!(!test_project.project_path().join("skillset.yaml").exists());
+    assert!(test_project.project_path().join("skillset.toml").exists());
+
+    // The backup is not mistaken for a second manifest
+    let (ok, _) = run_json(&test_project, home.path(), &["list", "--format", "json"]);
+    assert!(ok);
+}
@@ -0,0 +1,112 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::Path;
+
+/// Make `scripts/run.sh` executable and link to it, as a second commit on
+/// top of what `create_git_skill` wrote
+fn commit_script_and_links(repo_path: &Path, links: &[(&str, &str)]) {
+    let script = repo_path.join("scripts/run.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    for (link, target) in links {
+        symlink(target, repo_path.join(link)).unwrap();
+    }
+
+    let repo = git2::Repository::open(repo_path).unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    let signature = git2::Signature::now("skillset", "skillset@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "scripts",
+        &tree,
+        &[&parent],
+    )
+    .unwrap();
+}
+
+fn script_skill(home: &Path, links: &[(&str, &str)]) -> String {
+    let repo = common::create_git_skill(
+        home,
+        "helper",
+        &[
+            (
+                "SKILL.md",
+                "---\nname: helper\ndescription: Helps\n---\nRun scripts/run.sh\n",
+            ),
+            ("scripts/run.sh", "#!/bin/sh\necho helper\n"),
+        ],
+    )
+    .expect("Failed to create skill repo");
+    commit_script_and_links(&repo, links);
+    format!("git:{}", repo.display())
+}
+
+#[tokio::test]
+async fn test_scripts_stay_executable_and_links_are_kept() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = script_skill(home.path(), &[("scripts/latest.sh", "run.sh")]);
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let installed = test_project
+        .project_path()
+        .join("skills/agent-skills/helper");
+    let mode = std::fs::metadata(installed.join("scripts/run.sh"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o111, 0o111, "mode {:o}", mode);
+    assert_eq!(
+        std::fs::read_link(installed.join("scripts/latest.sh")).unwrap(),
+        Path::new("run.sh")
+    );
+}
+
+#[tokio::test]
+async fn test_links_out_of_the_skill_fail_the_install() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(r#"{ "skills": {}, "conventions": ["agent-skills"] }"#)
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = script_skill(home.path(), &[("scripts/profile", "../../../.profile")]);
+
+    let output = test_project
+        .run_skillset_command(&["add", &reference])
+        .env("HOME", home.path())
+        .output()
+        .expect("Failed to run skillset");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("outside the skill"));
+    assert!(!test_project
+        .project_path()
+        .join("skills/agent-skills/helper")
+        .exists());
+    assert!(!test_project
+        .read_skillset_config()
+        .unwrap()
+        .contains("helper"));
+}
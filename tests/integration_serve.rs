@@ -0,0 +1,66 @@
+mod common;
+
+use std::io::Write;
+
+#[tokio::test]
+async fn test_serve_mcp_adds_and_exposes_skills() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(
+        home.path(),
+        "helper",
+        &[(
+            "SKILL.md",
+            "---\nname: helper\ndescription: Helps with things\n---\nAlways be helpful.\n",
+        )],
+    )
+    .expect("Failed to create skill repo");
+    let reference = format!("git:{}", repo.display());
+
+    let requests = [
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05", "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1" } } }),
+        serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        serde_json::json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": { "name": "add_skill", "arguments": { "reference": reference } } }),
+        serde_json::json!({ "jsonrpc": "2.0", "id": 3, "method": "prompts/list" }),
+        serde_json::json!({ "jsonrpc": "2.0", "id": 4, "method": "unknown/method" }),
+    ];
+    let mut child = test_project
+        .run_skillset_command(&["serve", "--mcp"])
+        .env("HOME", home.path())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to run skillset");
+    let mut stdin = child.stdin.take().unwrap();
+    for request in &requests {
+        writeln!(stdin, "{}", request).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // The notification gets no answer
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "skillset");
+
+    let added = &responses[1]["result"];
+    assert!(added.get("isError").is_none(), "{}", added);
+    assert!(test_project
+        .read_skillset_config()
+        .unwrap()
+        .contains("helper"));
+
+    assert_eq!(
+        responses[2]["result"]["prompts"],
+        serde_json::json!([{ "name": "helper", "description": "Helps with things" }])
+    );
+    assert_eq!(responses[3]["error"]["code"], -32601);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+const CONVENTIONS: &str = r#"["agent-skills", "claude-code", "cursor", "agents-md"]"#;
+
+fn run(test_project: &common::TestProject, home: &std::path::Path, args: &[&str]) {
+    let output = test_project
+        .run_skillset_command(args)
+        .env("HOME", home)
+        .output()
+        .expect("Failed to run skillset");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn helper_repo(home: &std::path::Path) -> String {
+    let repo = common::create_git_skill(
+        home,
+        "helper",
+        &[(
+            "SKILL.md",
+            "---\nname: helper\ndescription: Helps with things\n---\nAlways be helpful.\n",
+        )],
+    )
+    .expect("Failed to create skill repo");
+    format!("git:{}", repo.display())
+}
+
+fn read_lockfile(test_project: &common::TestProject) -> serde_json::Value {
+    let content =
+        std::fs::read_to_string(test_project.project_path().join("skillset.lock")).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+#[tokio::test]
+async fn test_project_targets_install_every_skill_for_each_agent() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(&format!(
+            r#"{{ "skills": {{}}, "conventions": {}, "targets": ["claude-code", "cursor", "agents-md"] }}"#,
+            CONVENTIONS
+        ))
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = helper_repo(home.path());
+    let project = test_project.project_path();
+
+    run(&test_project, home.path(), &["add", &reference]);
+    assert!(project.join(".claude/skills/helper/SKILL.md").exists());
+    assert!(project.join(".cursor/rules/helper.mdc").exists());
+    assert!(std::fs::read_to_string(project.join("AGENTS.md"))
+        .unwrap()
+        .contains("Always be helpful."));
+
+    // The project's targets stay in charge of the skill
+    let manifest: serde_json::Value =
+        serde_json::from_str(&test_project.read_skillset_config().unwrap()).unwrap();
+    assert!(manifest["skills"]["helper"]["convention"].is_null());
+    let locked = &read_lockfile(&test_project)["skills"]["helper"];
+    assert_eq!(locked["convention"], "claude-code");
+    assert_eq!(locked["targets"][0]["convention"], "cursor");
+    assert_eq!(locked["targets"][1]["convention"], "agents-md");
+    run(&test_project, home.path(), &["verify"]);
+
+    // Dropping a target takes the skill out of that agent's files
+    let mut manifest = manifest;
+    manifest["targets"] = serde_json::json!(["claude-code"]);
+    test_project
+        .write_skillset_config(&manifest.to_string())
+        .unwrap();
+    run(&test_project, home.path(), &["install"]);
+    assert!(project.join(".claude/skills/helper").exists());
+    assert!(!project.join(".cursor/rules/helper").exists());
+    assert!(!project.join("AGENTS.md").exists());
+    assert!(read_lockfile(&test_project)["skills"]["helper"]
+        .get("targets")
+        .is_none());
+
+    run(&test_project, home.path(), &["remove", "helper"]);
+    assert!(!project.join(".claude/skills/helper").exists());
+}
+
+#[tokio::test]
+async fn test_targets_given_to_add_are_kept_in_the_manifest() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    test_project
+        .write_skillset_config(&format!(
+            r#"{{ "skills": {{}}, "conventions": {} }}"#,
+            CONVENTIONS
+        ))
+        .unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let reference = helper_repo(home.path());
+    let project = test_project.project_path();
+
+    run(
+        &test_project,
+        home.path(),
+        &["add", &reference, "--target", "claude-code,cursor"],
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_str(&test_project.read_skillset_config().unwrap()).unwrap();
+    assert_eq!(
+        manifest["skills"]["helper"]["targets"],
+        serde_json::json!(["claude-code", "cursor"])
+    );
+
+    // Updating reinstalls every target
+    std::fs::remove_dir_all(project.join(".cursor/rules/helper")).unwrap();
+    run(&test_project, home.path(), &["update", "helper"]);
+    assert!(project.join(".claude/skills/helper/SKILL.md").exists());
+    assert!(project.join(".cursor/rules/helper").exists());
+    run(&test_project, home.path(), &["verify"]);
+
+    run(&test_project, home.path(), &["remove", "helper"]);
+    assert!(!project.join(".claude/skills/helper").exists());
+    assert!(!project.join(".cursor/rules/helper").exists());
+    assert!(!project.join(".cursor/rules/helper.mdc").exists());
+}
@@ -0,0 +1,106 @@
+mod common;
+
+fn run(test_project: &common::TestProject, home: &std::path::Path, args: &[&str]) -> bool {
+    let mut cmd = test_project.run_skillset_command(args);
+    cmd.env("HOME", home)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME");
+    cmd.output()
+        .expect("Failed to run skillset")
+        .status
+        .success()
+}
+
+/// Everything in the project apart from the lock coordinating skillset runs
+fn project_entries(test_project: &common::TestProject) -> Vec<String> {
+    let mut entries: Vec<String> = std::fs::read_dir(test_project.project_path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name != ".skillset-lock")
+        .collect();
+    entries.sort();
+    entries
+}
+
+#[tokio::test]
+async fn test_failed_add_leaves_manifest_untouched() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let manifest = test_project.read_skillset_config().unwrap();
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+
+    // Nothing listens on port 1, so resolving the name succeeds but the fetch fails
+    let ok = run(
+        &test_project,
+        home.path(),
+        &["add", "helper@1.0.0", "--registry", "localhost:1/skills"],
+    );
+    assert!(!ok);
+
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+    assert_eq!(project_entries(&test_project), vec!["skillset.json"]);
+}
+
+#[tokio::test]
+async fn test_failed_install_rolls_back_every_skill() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "good", &[("SKILL.md", "# Good")])
+        .expect("Failed to create skill repo");
+    let missing = home.path().join("missing");
+    test_project
+        .write_skillset_config(&format!(
+            r#"{{ "skills": {{
+                "good": {{ "version": "1.0.0", "source": "git:{}" }},
+                "zbad": {{ "version": "1.0.0", "source": "git:{}" }}
+            }} }}"#,
+            repo.display(),
+            missing.display()
+        ))
+        .unwrap();
+    let manifest = test_project.read_skillset_config().unwrap();
+
+    // `good` installs first, then `zbad` fails and takes it back out
+    assert!(!run(&test_project, home.path(), &["install"]));
+    assert_eq!(test_project.read_skillset_config().unwrap(), manifest);
+    assert_eq!(project_entries(&test_project), vec!["skillset.json"]);
+
+    test_project
+        .write_skillset_config(&format!(
+            r#"{{ "skills": {{ "good": {{ "version": "1.0.0", "source": "git:{}" }} }} }}"#,
+            repo.display()
+        ))
+        .unwrap();
+    assert!(run(&test_project, home.path(), &["install"]));
+    assert_eq!(
+        project_entries(&test_project),
+        vec!["skills", "skillset.json", "skillset.lock"]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_add_keeps_the_manifest_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let home = tempfile::tempdir().expect("Failed to create home dir");
+    let repo = common::create_git_skill(home.path(), "helper", &[("SKILL.md", "# Helper")])
+        .expect("Failed to create skill repo");
+    let manifest = test_project.project_path().join("skillset.json");
+    std::fs::set_permissions(&manifest, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    let reference = format!("git:{}", repo.display());
+    assert!(run(&test_project, home.path(), &["add", &reference]));
+
+    let mode = |name: &str| {
+        std::fs::metadata(test_project.project_path().join(name))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode("skillset.json"), 0o644);
+    // A new lockfile gets the mode any new file would, not 0600
+    std::fs::write(test_project.project_path().join("reference"), "").unwrap();
+    assert_eq!(mode("skillset.lock"), mode("reference"));
+}
@@ -0,0 +1,66 @@
+use assert_cmd::prelude::*;
+
+mod common;
+
+#[tokio::test]
+async fn test_install_workspace_members() {
+    let test_project = common::TestProject::new().expect("Failed to create test project");
+    let sources = tempfile::tempdir().expect("Failed to create sources dir");
+    let repo = common::create_git_skill(
+        sources.path(),
+        "shared-skill",
+        &[("SKILL.md", "# Shared skill")],
+    )
+    .expect("Failed to create skill repo");
+
+    // Root declares members and the conventions they inherit
+    test_project
+        .write_skillset_config(
+            r#"{
+  "skills": {},
+  "conventions": ["agent-skills"],
+  "workspace": { "members": ["packages/*"] }
+}"#,
+        )
+        .expect("Failed to write root config");
+
+    let member_config = format!(
+        r#"{{
+  "skills": {{
+    "shared-skill": {{ "version": "latest", "source": "git:{}" }}
+  }}
+}}"#,
+        repo.display()
+    );
+    for member in ["web", "api"] {
+        let member_dir = test_project.project_path().join("packages").join(member);
+        std::fs::create_dir_all(&member_dir).expect("Failed to create member");
+        std::fs::write(member_dir.join("skillset.json"), &member_config)
+            .expect("Failed to write member config");
+    }
+
+    let mut cmd = test_project.run_skillset_command(&["install", "--workspace"]);
+    cmd.current_dir(test_project.project_path().join("packages/web"));
+    cmd.assert().success();
+
+    for member in ["web", "api"] {
+        let skill_dir = test_project
+            .project_path()
+            .join("packages")
+            .join(member)
+            .join("skills/agent-skills/shared-skill");
+        assert!(skill_dir.join("SKILL.md").exists());
+        // Members only get the single root lockfile
+        assert!(!test_project
+            .project_path()
+            .join("packages")
+            .join(member)
+            .join("skillset.lock")
+            .exists());
+    }
+
+    let lockfile = std::fs::read_to_string(test_project.project_path().join("skillset.lock"))
+        .expect("No root lockfile");
+    assert!(lockfile.contains("packages/web"));
+    assert!(lockfile.contains("packages/api"));
+}